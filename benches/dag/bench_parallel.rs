@@ -0,0 +1,34 @@
+use btree_dag::*;
+use criterion::{black_box, BenchmarkId, Criterion};
+
+/// Returns the edges of a layered dag of 100 layers of 1000 vertices, where
+/// each vertex depends on 8 vertices of the next layer.
+fn layered_edges() -> Vec<(usize, usize)> {
+    let width: usize = 1000;
+    (0..99 * width)
+        .flat_map(|x| {
+            let next = (x / width + 1) * width;
+            (0..8).map(move |i| (x, next + (x * 7 + i * 131) % width))
+        })
+        .collect()
+}
+
+pub fn from_edges_benchmark(c: &mut Criterion) {
+    let edges = layered_edges();
+    let mut group = c.benchmark_group("dag::api::FromEdges (100000 vertices)");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| black_box(BTreeDAG::from_edges(edges.clone())))
+    });
+    // Validate on pools of increasing size, to show how validation scales.
+    for threads in [1, 2, 4, 8].iter() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(*threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("parallel", threads), threads, |b, _| {
+            b.iter(|| pool.install(|| black_box(BTreeDAG::par_from_edges(edges.clone()))))
+        });
+    }
+    group.finish();
+}
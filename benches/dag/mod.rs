@@ -2,6 +2,10 @@ use criterion::criterion_group;
 
 mod bench_api;
 pub use bench_api::*;
+#[cfg(feature = "rayon")]
+mod bench_parallel;
+#[cfg(feature = "rayon")]
+pub use bench_parallel::*;
 
 criterion_group!(
     dag_benches,
@@ -15,3 +19,6 @@ criterion_group!(
     adjacent_benchmark,
     connections_benchmark
 );
+
+#[cfg(feature = "rayon")]
+criterion_group!(parallel_benches, from_edges_benchmark);
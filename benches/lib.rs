@@ -3,4 +3,7 @@ use criterion::criterion_main;
 mod dag;
 use dag::*;
 
+#[cfg(not(feature = "rayon"))]
 criterion_main!(dag_benches,);
+#[cfg(feature = "rayon")]
+criterion_main!(dag_benches, parallel_benches);
@@ -0,0 +1,5 @@
+//! Builds the C bindings of the `ffi` module of `btree_dag` as a shared and
+//! a static library, declared by `include/btree_dag.h`. The `btree_dag` crate
+//! is `no_std`, so it cannot be built as either itself; this crate links it
+//! against std, which provides the allocator and the panic handler.
+pub use btree_dag_core::ffi::*;
@@ -0,0 +1,41 @@
+#![no_main]
+//! Applies a sequence of operations decoded from the fuzzer input to a dag,
+//! checking the invariants of the dag after each operation, and within each
+//! operation through the `debug-invariants` feature. Each operation is
+//! encoded as three bytes: the kind of the operation, then its two vertices.
+//! A merge operation merges the dag with the edge between its two vertices.
+
+use btree_dag::{AddEdge, AddVertex, BTreeDAG, MutationOp};
+use libfuzzer_sys::fuzz_target;
+
+/// Decodes the operation encoded by a chunk of the fuzzer input.
+fn decode(chunk: &[u8]) -> MutationOp<u8> {
+    let (x, y) = (chunk[1], chunk[2]);
+    match chunk[0] % 6 {
+        0 => MutationOp::AddVertex(x),
+        1 => MutationOp::AddEdge(x, y),
+        2 => MutationOp::RemoveEdge(x, y),
+        3 => MutationOp::RemoveVertex(x),
+        4 => MutationOp::Prune(x),
+        _ => {
+            let mut other: BTreeDAG<u8> = BTreeDAG::new();
+            other.add_vertex(x);
+            other.add_vertex(y);
+            // An edge from a vertex to itself is rejected.
+            let _ = other.add_edge(x, y);
+            MutationOp::Merge(other)
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut dag: BTreeDAG<u8> = BTreeDAG::new();
+    for chunk in data.chunks_exact(3) {
+        let before: BTreeDAG<u8> = dag.clone();
+        // A failed operation must leave the dag unchanged.
+        if decode(chunk).apply(&mut dag).is_err() {
+            assert_eq!(dag, before);
+        }
+        assert_eq!(dag.check_invariants(), Ok(()));
+    }
+});
@@ -0,0 +1,253 @@
+#ifndef BTREE_DAG_H
+#define BTREE_DAG_H
+
+/* This file is generated by cbindgen from src/ffi; do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status codes returned by the fallible bindings.
+ */
+typedef enum btree_dag_status {
+  BTREE_DAG_STATUS_OK = 0,
+  BTREE_DAG_STATUS_VERTEX_DOES_NOT_EXIST = 1,
+  BTREE_DAG_STATUS_EDGE_DOES_NOT_EXIST = 2,
+  BTREE_DAG_STATUS_VERTEX_EXISTS = 3,
+  BTREE_DAG_STATUS_EDGE_EXISTS = 4,
+  BTREE_DAG_STATUS_IMPROPER_DIMENSION = 5,
+  BTREE_DAG_STATUS_NULL_POINTER = 6,
+  BTREE_DAG_STATUS_INVALID_UTF8 = 7,
+  BTREE_DAG_STATUS_OTHER = 8,
+} btree_dag_status;
+
+/**
+ * Opaque handle to a dag keyed by strings.
+ */
+typedef struct btree_dag_str_t btree_dag_str_t;
+
+/**
+ * Opaque handle to a dag keyed by unsigned 64 bit integers.
+ */
+typedef struct btree_dag_t btree_dag_t;
+
+/**
+ * Returns a new, empty dag keyed by integers.
+ */
+struct btree_dag_t *btree_dag_new(void);
+
+/**
+ * Releases the dag. Passing a null pointer is a no-op.
+ *
+ * # Safety
+ *
+ * The dag must have been returned by `btree_dag_new`, and must not be used
+ * after it is released.
+ */
+void btree_dag_free(struct btree_dag_t *dag);
+
+/**
+ * Adds the vertex x, if it is not there. A vertex which is there, and its
+ * edges, are left unchanged.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+enum btree_dag_status btree_dag_add_vertex(struct btree_dag_t *dag, uint64_t x);
+
+/**
+ * Adds an edge from the vertex x to the vertex y, unless it would introduce
+ * a cycle.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+enum btree_dag_status btree_dag_add_edge(struct btree_dag_t *dag, uint64_t x, uint64_t y);
+
+/**
+ * Removes the edge from the vertex x to the vertex y, if it is there.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+enum btree_dag_status btree_dag_remove_edge(struct btree_dag_t *dag, uint64_t x, uint64_t y);
+
+/**
+ * Removes the vertex x and its incident edges.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+enum btree_dag_status btree_dag_remove_vertex(struct btree_dag_t *dag, uint64_t x);
+
+/**
+ * Removes the vertex x and recursively all of its children.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+enum btree_dag_status btree_dag_prune(struct btree_dag_t *dag, uint64_t x);
+
+/**
+ * Tests whether there is an edge from the vertex x to the vertex y, writing
+ * the answer to out.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`, and out
+ * must be null or valid for writes.
+ */
+enum btree_dag_status btree_dag_adjacent(const struct btree_dag_t *dag,
+                                         uint64_t x,
+                                         uint64_t y,
+                                         bool *out);
+
+/**
+ * Returns the number of vertices in the dag, or 0 if the dag is null.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`.
+ */
+size_t btree_dag_vertex_count(const struct btree_dag_t *dag);
+
+/**
+ * Writes up to len vertices of the dag, in topological order, to out, and
+ * returns the total number of vertices. Call with a null out to query the
+ * required length.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_new`, and out
+ * must be null or valid for writes of len integers.
+ */
+size_t btree_dag_topological_sort(const struct btree_dag_t *dag, uint64_t *out, size_t len);
+
+/**
+ * Returns a new, empty dag keyed by strings.
+ */
+struct btree_dag_str_t *btree_dag_str_new(void);
+
+/**
+ * Releases the dag. Passing a null pointer is a no-op.
+ *
+ * # Safety
+ *
+ * The dag must have been returned by `btree_dag_str_new`, and must not be
+ * used after it is released.
+ */
+void btree_dag_str_free(struct btree_dag_str_t *dag);
+
+/**
+ * Adds the vertex x, if it is not there. A vertex which is there, and its
+ * edges, are left unchanged. The key is copied.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * x must be null or a NUL terminated string.
+ */
+enum btree_dag_status btree_dag_str_add_vertex(struct btree_dag_str_t *dag, const char *x);
+
+/**
+ * Adds an edge from the vertex x to the vertex y, unless it would introduce
+ * a cycle.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * x and y must be null or NUL terminated strings.
+ */
+enum btree_dag_status btree_dag_str_add_edge(struct btree_dag_str_t *dag,
+                                             const char *x,
+                                             const char *y);
+
+/**
+ * Removes the edge from the vertex x to the vertex y, if it is there.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * x and y must be null or NUL terminated strings.
+ */
+enum btree_dag_status btree_dag_str_remove_edge(struct btree_dag_str_t *dag,
+                                                const char *x,
+                                                const char *y);
+
+/**
+ * Removes the vertex x and its incident edges.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * x must be null or a NUL terminated string.
+ */
+enum btree_dag_status btree_dag_str_remove_vertex(struct btree_dag_str_t *dag, const char *x);
+
+/**
+ * Removes the vertex x and recursively all of its children.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * x must be null or a NUL terminated string.
+ */
+enum btree_dag_status btree_dag_str_prune(struct btree_dag_str_t *dag, const char *x);
+
+/**
+ * Tests whether there is an edge from the vertex x to the vertex y, writing
+ * the answer to out.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, x
+ * and y must be null or NUL terminated strings, and out must be null or
+ * valid for writes.
+ */
+enum btree_dag_status btree_dag_str_adjacent(const struct btree_dag_str_t *dag,
+                                             const char *x,
+                                             const char *y,
+                                             bool *out);
+
+/**
+ * Returns the number of vertices in the dag, or 0 if the dag is null.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`.
+ */
+size_t btree_dag_str_vertex_count(const struct btree_dag_str_t *dag);
+
+/**
+ * Writes up to len vertices of the dag, in topological order, to out, and
+ * returns the total number of vertices. Call with a null out to query the
+ * required length. Each string written is a copy, which must be released
+ * with `btree_dag_string_free`.
+ *
+ * # Safety
+ *
+ * The dag must be null or a live handle returned by `btree_dag_str_new`, and
+ * out must be null or valid for writes of len pointers.
+ */
+size_t btree_dag_str_topological_sort(const struct btree_dag_str_t *dag, char **out, size_t len);
+
+/**
+ * Releases a string returned by the bindings. Passing a null pointer is a
+ * no-op.
+ *
+ * # Safety
+ *
+ * The string must have been returned by the bindings, and must not be used
+ * after it is released.
+ */
+void btree_dag_string_free(char *s);
+
+#endif  /* BTREE_DAG_H */
@@ -0,0 +1,5 @@
+//! Builds the `python` module of `btree_dag` as the Python extension module
+//! `btree_dag`. The module itself, `PyInit_btree_dag`, is defined by the
+//! `btree_dag` crate; this crate links it into a shared library which does
+//! not link against libpython, as the interpreter requires.
+pub use btree_dag_core::PyDag;
@@ -0,0 +1,196 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+
+use crate::dag::{
+    is_acyclic, AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex,
+};
+use crate::Error;
+
+/// `AliasedDAG` wraps a `BTreeDAG`, allowing keys to be declared aliases of a
+/// vertex. Every key resolves to a canonical vertex of the dag, so adding or
+/// removing an edge to an alias adds or removes the edge to its vertex.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AliasedDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    // Each alias, and the canonical vertex it resolves to.
+    aliases: BTreeMap<T, T>,
+}
+
+impl<T> AliasedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        AliasedDAG {
+            dag: BTreeDAG::new(),
+            aliases: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the dag of canonical vertices.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the canonical vertex the key x resolves to, which is x itself
+    /// unless x is an alias.
+    pub fn resolve<'a>(&'a self, x: &'a T) -> &'a T {
+        self.aliases.get(x).unwrap_or(x)
+    }
+
+    /// Returns every alias of the vertex x.
+    pub fn aliases_of(&self, x: &T) -> BTreeSet<&T> {
+        self.aliases
+            .iter()
+            .filter(|(_, v)| *v == x)
+            .map(|(alias, _)| alias)
+            .collect()
+    }
+}
+
+impl<T> AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Declares the key alias an alias of the key target, so both resolve to
+    /// the same vertex. If alias already resolves to a different vertex, the
+    /// two vertices are unified: every edge to or from the alias's vertex is
+    /// moved to the target's vertex. An error is returned if the target does
+    /// not resolve to a vertex, or if the unified vertices would form a cycle,
+    /// in which case the dag is unchanged.
+    pub fn alias(&mut self, alias: T, target: T) -> Result<(), Error> {
+        let target: T = self.resolve(&target).clone();
+        if !self.dag.vertices.contains_key(&target) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let merged: T = self.resolve(&alias).clone();
+        if merged == target {
+            return Ok(());
+        }
+        if let Some(adj_merged) = self.dag.vertices.get(&merged) {
+            // Build the unified vertices separately, so the dag is
+            // unchanged on error.
+            let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self.dag.vertices.clone();
+            let adj_merged = Arc::clone(adj_merged);
+            vertices.remove(&merged);
+            for adj in vertices.values_mut() {
+                if adj.contains(&merged) {
+                    let adj = Arc::make_mut(adj);
+                    adj.remove(&merged);
+                    adj.insert(target.clone());
+                }
+            }
+            if adj_merged.contains(&target) || vertices[&target].contains(&target) {
+                return Err(Error::EdgeExists);
+            }
+            // We have already verified target is in vertices, so we can
+            // safely unwrap.
+            Arc::make_mut(vertices.get_mut(&target).unwrap()).extend(adj_merged.iter().cloned());
+            if !is_acyclic(&vertices) {
+                return Err(Error::EdgeExists);
+            }
+            self.dag.vertices = vertices;
+        }
+        for v in self.aliases.values_mut() {
+            if *v == merged {
+                *v = target.clone();
+            }
+        }
+        if alias != merged {
+            self.aliases.insert(merged, target.clone());
+        }
+        self.aliases.insert(alias, target);
+        Ok(())
+    }
+}
+
+impl<T> Default for AliasedDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AddVertex<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        let x = self.resolve(&x).clone();
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T> AddEdge<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let x = self.resolve(&x).clone();
+        let y = self.resolve(&y).clone();
+        // An edge between aliases of one vertex is a cycle.
+        if x == y {
+            return Err(Error::EdgeExists);
+        }
+        self.dag.add_edge(x, y)
+    }
+}
+
+impl<T> RemoveEdge<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let x = self.resolve(&x).clone();
+        let y = self.resolve(&y).clone();
+        self.dag.remove_edge(x, y)
+    }
+}
+
+impl<T> RemoveVertex<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let x = self.resolve(&x).clone();
+        let adj_x = self.dag.remove_vertex(x.clone())?;
+        self.aliases.retain(|_, v| *v != x);
+        Ok(adj_x)
+    }
+}
+
+impl<T> Prune<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        let x = self.resolve(&x).clone();
+        self.dag.prune(x)?;
+        let dag = &self.dag;
+        self.aliases.retain(|_, v| dag.vertices.contains_key(v));
+        Ok(())
+    }
+}
+
+impl<T> Adjacent<T> for AliasedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        let x = self.resolve(&x).clone();
+        let y = self.resolve(&y).clone();
+        self.dag.adjacent(x, y)
+    }
+}
@@ -0,0 +1,61 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::alias::*;
+    use crate::dag::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn alias() -> Result<(), Error> {
+        // Add two nodes, and declare 10 an alias of 1.
+        let mut dag: AliasedDAG<usize> = AliasedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.alias(10, 1)?;
+        assert_eq!(dag.alias(11, 2), Err(Error::VertexDoesNotExist));
+
+        // Edges to the alias resolve to its vertex.
+        dag.add_edge(0, 10)?;
+        assert!(dag.dag().adjacent(0, 1)?);
+        assert!(dag.adjacent(0, 10)?);
+        assert_eq!(dag.add_edge(10, 0), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(1, 10), Err(Error::EdgeExists));
+        assert_eq!(dag.resolve(&10), &1);
+        assert_eq!(dag.aliases_of(&1), BTreeSet::from([&10]));
+
+        // Removing the vertex removes its aliases.
+        dag.remove_vertex(10)?;
+        assert_eq!(dag.resolve(&10), &10);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn unify() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (2, 3) and (3, 1).
+        let mut dag: AliasedDAG<usize> = AliasedDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(3, 1)?;
+
+        // Unifying 1 with 2 would form a cycle, so the dag is unchanged.
+        let before: AliasedDAG<usize> = dag.clone();
+        assert_eq!(dag.alias(1, 2), Err(Error::EdgeExists));
+        assert_eq!(dag, before);
+
+        // Unifying 0 with 2 moves the edges of 0 to 2.
+        dag.alias(0, 2)?;
+        assert_eq!(dag.dag().vertices(), BTreeSet::from([&1, &2, &3]));
+        assert!(dag.adjacent(0, 1)?);
+        assert!(dag.adjacent(2, 1)?);
+        assert_eq!(dag.resolve(&0), &2);
+
+        // Test passed.
+        Ok(())
+    }
+}
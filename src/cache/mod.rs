@@ -0,0 +1,250 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `Invalidation` is the direction in which a `DagCache` invalidates entries when
+/// the edges of a vertex change.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Invalidation {
+    /// Each value is computed from the vertices reachable from its vertex, so a
+    /// change to the edges of x invalidates x and every ancestor of x.
+    Ancestors,
+    /// Each value is computed from the vertices from which its vertex is
+    /// reachable, so a change to an edge (x, y) invalidates y and every
+    /// descendant of y.
+    Descendants,
+}
+
+/// `DagCache` wraps a `BTreeDAG`, storing a computed value for each vertex. Every
+/// mutation through the cache invalidates the entries whose values it may
+/// change, in the direction given by its `Invalidation`, so only those entries
+/// need to be recomputed. Every invalidated vertex is also marked dirty, until
+/// the dirty vertices are taken.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DagCache<T, V>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    invalidation: Invalidation,
+    values: BTreeMap<T, V>,
+    dirty: BTreeSet<T>,
+}
+
+impl<T, V> DagCache<T, V>
+where
+    T: Ord,
+{
+    pub fn new(dag: BTreeDAG<T>, invalidation: Invalidation) -> Self {
+        DagCache {
+            dag,
+            invalidation,
+            values: BTreeMap::new(),
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the cached dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the cached value of x, if it is cached.
+    pub fn get(&self, x: &T) -> Option<&V> {
+        self.values.get(x)
+    }
+
+    /// Removes the cached value of x, returning it if it was cached. Entries
+    /// which depend on x are not invalidated.
+    pub fn invalidate(&mut self, x: &T) -> Option<V> {
+        self.values.remove(x)
+    }
+
+    /// Returns the number of cached values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no values are cached.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T, V> DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    /// Caches the value of the vertex x, returning the previously cached value.
+    /// An error is returned if x does not exist.
+    pub fn insert(&mut self, x: T, value: V) -> Result<Option<V>, Error> {
+        if !self.dag.vertices.contains_key(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        Ok(self.values.insert(x, value))
+    }
+
+    /// Returns the cached value of the vertex x, computing and caching it first
+    /// if it is not cached. An error is returned if x does not exist.
+    pub fn get_or_insert_with(
+        &mut self,
+        x: &T,
+        f: impl FnOnce(&BTreeDAG<T>) -> V,
+    ) -> Result<&V, Error> {
+        if !self.dag.vertices.contains_key(x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let dag = &self.dag;
+        Ok(self.values.entry(x.clone()).or_insert_with(|| f(dag)))
+    }
+
+    /// Marks the vertex x dirty, along with every vertex whose value depends on
+    /// x in the direction of the cache's `Invalidation`, invalidating each of
+    /// their values. An error is returned if x does not exist.
+    pub fn mark_dirty(&mut self, x: T) -> Result<(), Error> {
+        if !self.dag.vertices.contains_key(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let mut affected: Vec<T> = match self.invalidation {
+            Invalidation::Ancestors => self.dag.ancestors_of(&x).into_iter().cloned().collect(),
+            Invalidation::Descendants => self.dag.descendants_of(&x).into_iter().cloned().collect(),
+        };
+        affected.push(x);
+        self.invalidate_all(affected);
+        Ok(())
+    }
+
+    /// Returns every vertex marked dirty since the dirty vertices were last
+    /// taken, clearing them. Vertices which have since been removed are not
+    /// returned.
+    pub fn take_dirty(&mut self) -> BTreeSet<T> {
+        let dag = &self.dag;
+        let mut dirty = core::mem::take(&mut self.dirty);
+        dirty.retain(|v| dag.vertices.contains_key(v));
+        dirty
+    }
+
+    /// Returns the vertices whose values may change when the edges from x to
+    /// each of the given vertices change.
+    fn affected<'a>(&self, x: &T, ys: impl Iterator<Item = &'a T>) -> Vec<T>
+    where
+        T: 'a,
+    {
+        let mut affected: Vec<T> = Vec::new();
+        match self.invalidation {
+            Invalidation::Ancestors => {
+                affected.push(x.clone());
+                affected.extend(self.dag.ancestors_of(x).into_iter().cloned());
+            }
+            Invalidation::Descendants => {
+                for y in ys {
+                    affected.push(y.clone());
+                    affected.extend(self.dag.descendants_of(y).into_iter().cloned());
+                }
+            }
+        }
+        affected
+    }
+
+    fn invalidate_all(&mut self, affected: Vec<T>) {
+        for v in affected.into_iter() {
+            self.values.remove(&v);
+            self.dirty.insert(v);
+        }
+    }
+}
+
+impl<T, V> AddVertex<T> for DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        let affected = match self.dag.vertices.get(&x) {
+            Some(adj_x) => self.affected(&x, adj_x.iter()),
+            None => Vec::new(),
+        };
+        self.invalidate_all(affected);
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T, V> AddEdge<T> for DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.add_edge(x.clone(), y.clone())?;
+        let affected = self.affected(&x, core::iter::once(&y));
+        self.invalidate_all(affected);
+        Ok(adj_x)
+    }
+}
+
+impl<T, V> RemoveEdge<T> for DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_edge(x.clone(), y.clone())?;
+        let affected = self.affected(&x, core::iter::once(&y));
+        self.invalidate_all(affected);
+        Ok(adj_x)
+    }
+}
+
+impl<T, V> RemoveVertex<T> for DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let affected = match self.dag.vertices.get(&x) {
+            Some(adj_x) => self.affected(&x, adj_x.iter()),
+            None => Vec::new(),
+        };
+        let adj_x = self.dag.remove_vertex(x.clone())?;
+        self.invalidate_all(affected);
+        self.values.remove(&x);
+        Ok(adj_x)
+    }
+}
+
+impl<T, V> Prune<T> for DagCache<T, V>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        if !self.dag.vertices.contains_key(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        // Pruning removes x and every descendant of x, so their values are
+        // invalidated in either direction.
+        let mut pruned: BTreeSet<T> = self.dag.descendants_of(&x).into_iter().cloned().collect();
+        pruned.insert(x.clone());
+        let mut affected: Vec<T> = pruned.iter().cloned().collect();
+        if self.invalidation == Invalidation::Ancestors {
+            // Every vertex with an edge into the pruned vertices loses that
+            // edge, which changes its value and the values of its ancestors.
+            let predecessors: Vec<&T> = self
+                .dag
+                .vertices
+                .iter()
+                .filter(|(v, adj)| !pruned.contains(*v) && adj.iter().any(|y| pruned.contains(y)))
+                .map(|(v, _)| v)
+                .collect();
+            let mut changed: BTreeSet<&T> = self.dag.ancestors_of_all(predecessors.iter().copied());
+            changed.extend(predecessors);
+            affected.extend(changed.into_iter().cloned());
+        }
+        self.dag.prune(x)?;
+        self.invalidate_all(affected);
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::cache::*;
+    use crate::dag::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    // Build a dag with four nodes, and an edge (0, 1), (1, 2) and (3, 2).
+    fn dag() -> Result<BTreeDAG<usize>, Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(3, 2)?;
+        Ok(dag)
+    }
+
+    fn fill(cache: &mut DagCache<usize, usize>) -> Result<(), Error> {
+        for x in 0..4 {
+            cache.get_or_insert_with(&x, |dag| dag.descendants_of(&x).len())?;
+        }
+        Ok(())
+    }
+
+    fn cached(cache: &DagCache<usize, usize>) -> Vec<usize> {
+        (0..4).filter(|x| cache.get(x).is_some()).collect()
+    }
+
+    #[test]
+    fn invalidate_ancestors() -> Result<(), Error> {
+        let mut cache: DagCache<usize, usize> = DagCache::new(dag()?, Invalidation::Ancestors);
+        fill(&mut cache)?;
+        assert_eq!(cache.get(&0), Some(&2));
+        assert_eq!(
+            cache.get_or_insert_with(&4, |_| 0),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Adding an edge from 1 invalidates 1 and its ancestor 0.
+        cache.add_vertex(4);
+        cache.add_edge(1, 4)?;
+        assert_eq!(cached(&cache), [2, 3]);
+        fill(&mut cache)?;
+        assert_eq!(cache.get(&0), Some(&3));
+
+        // A failed mutation does not invalidate anything.
+        assert!(cache.add_edge(2, 0).is_err());
+        assert_eq!(cache.len(), 4);
+
+        // Removing 2 invalidates 2 and its ancestors.
+        cache.remove_vertex(2)?;
+        assert!(cached(&cache).is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn invalidate_descendants() -> Result<(), Error> {
+        let mut cache: DagCache<usize, usize> = DagCache::new(dag()?, Invalidation::Descendants);
+        fill(&mut cache)?;
+
+        // Removing an edge (0, 1) invalidates 1 and its descendant 2.
+        cache.remove_edge(0, 1)?;
+        assert_eq!(cached(&cache), [0, 3]);
+        fill(&mut cache)?;
+
+        // Pruning 3 invalidates 3 and its descendant 2.
+        cache.prune(3)?;
+        assert_eq!(cached(&cache), [0, 1]);
+        assert_eq!(cache.invalidate(&0), Some(2));
+        assert_eq!(cached(&cache), [1]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn dirty() -> Result<(), Error> {
+        let mut cache: DagCache<usize, usize> = DagCache::new(dag()?, Invalidation::Descendants);
+        fill(&mut cache)?;
+        assert!(cache.take_dirty().is_empty());
+
+        // Marking 0 dirty propagates to its descendants.
+        cache.mark_dirty(0)?;
+        assert_eq!(cached(&cache), [3]);
+        assert_eq!(cache.take_dirty(), BTreeSet::from([0, 1, 2]));
+        assert!(cache.take_dirty().is_empty());
+        assert_eq!(cache.mark_dirty(4), Err(Error::VertexDoesNotExist));
+
+        // Mutations mark the vertices they invalidate dirty, and removed
+        // vertices are not returned.
+        cache.add_vertex(4);
+        cache.add_edge(4, 2)?;
+        cache.mark_dirty(3)?;
+        cache.remove_vertex(3)?;
+        assert_eq!(cache.take_dirty(), BTreeSet::from([2]));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn prune_invalidates_predecessors() -> Result<(), Error> {
+        // Add five nodes, and an edge (4, 0), (0, 2) and (1, 2), so pruning 1
+        // removes the edge (0, 2) out from under 0 and its ancestor 4.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for i in 0..5 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(4, 0)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        let mut cache: DagCache<usize, usize> = DagCache::new(dag, Invalidation::Ancestors);
+        fill(&mut cache)?;
+        cache.insert(4, 2)?;
+
+        cache.prune(1)?;
+        assert_eq!(cached(&cache), [3]);
+        assert_eq!(cache.get(&4), None);
+        assert_eq!(cache.dag().get_vertex_value(0), Some(&BTreeSet::new()));
+        assert_eq!(cache.take_dirty(), BTreeSet::from([0, 4]));
+        assert_eq!(cache.prune(1), Err(Error::VertexDoesNotExist));
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+mod set;
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::default::Default;
+
+use crate::dag::{
+    into_owned, AddEdge, AddVertex, Adjacent, BTreeDAG, OutDegree, RemoveEdge, RemoveVertex,
+    Vertices,
+};
+use crate::Error;
+pub use set::*;
+
+/// `CompactDAG` is a directed acyclic graph which stores each adjacency list
+/// in a `SmallSet`, so vertices with at most N edges do not allocate a
+/// `BTreeSet`. It is intended for graphs with a small typical out-degree, and
+/// converts to and from `BTreeDAG`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CompactDAG<T, const N: usize = 4>
+where
+    T: Ord,
+{
+    vertices: BTreeMap<T, SmallSet<T, N>>,
+}
+
+impl<T, const N: usize> CompactDAG<T, N>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        let vertices: BTreeMap<T, SmallSet<T, N>> = BTreeMap::new();
+        CompactDAG { vertices }
+    }
+
+    /// Returns the adjacency list of the vertex x, if it exists.
+    pub fn connections(&self, x: &T) -> Option<&SmallSet<T, N>> {
+        self.vertices.get(x)
+    }
+
+    /// Returns an error if x is reachable from y, as an edge (x, y) would
+    /// then introduce a cycle.
+    fn cyclic_relationship_exists(&self, x: &T, y: &T) -> Result<(), Error> {
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = Vec::new();
+        stack.push(
+            self.vertices
+                .get_key_value(y)
+                .ok_or(Error::VertexDoesNotExist)?
+                .0,
+        );
+        while let Some(v) = stack.pop() {
+            if v == x {
+                return Err(Error::EdgeExists);
+            }
+            if visited.insert(v) {
+                stack.extend(self.vertices[v].iter());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Default for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Vertices<T> for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    fn vertices(&self) -> BTreeSet<&T> {
+        self.vertices.keys().collect()
+    }
+}
+
+impl<T, const N: usize> AddVertex<T> for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.vertices
+            .insert(x, SmallSet::new())
+            .map(SmallSet::into_btree_set)
+    }
+}
+
+impl<T, const N: usize> AddEdge<T> for CompactDAG<T, N>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if self.vertices.contains_key(&x) {
+            self.cyclic_relationship_exists(&x, &y)?;
+            // We have already verified x is in vertices, so we can
+            // safely unwrap.
+            let adj_x = self.vertices.get_mut(&x).unwrap();
+            let previous_adj_x: BTreeSet<T> = adj_x.iter().cloned().collect();
+            adj_x.insert(y);
+            return Ok(previous_adj_x);
+        }
+        Err(Error::VertexDoesNotExist)
+    }
+}
+
+impl<T, const N: usize> RemoveEdge<T> for CompactDAG<T, N>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if self.vertices.contains_key(&y) {
+            if let Some(adj_x) = self.vertices.get_mut(&x) {
+                let previous_adj_x: BTreeSet<T> = adj_x.iter().cloned().collect();
+                adj_x.remove(&y);
+                return Ok(previous_adj_x);
+            }
+        }
+        Err(Error::VertexDoesNotExist)
+    }
+}
+
+impl<T, const N: usize> RemoveVertex<T> for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.vertices.remove(&x).ok_or(Error::VertexDoesNotExist)?;
+        // Remove any dangling edges to x.
+        for adj in self.vertices.values_mut() {
+            adj.remove(&x);
+        }
+        Ok(adj_x.into_btree_set())
+    }
+}
+
+impl<T, const N: usize> Adjacent<T> for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        if self.vertices.contains_key(&y) {
+            if let Some(adj_x) = self.vertices.get(&x) {
+                return Ok(adj_x.contains(&y));
+            }
+        }
+        Err(Error::VertexDoesNotExist)
+    }
+}
+
+impl<T, const N: usize> OutDegree<T> for CompactDAG<T, N>
+where
+    T: Ord,
+{
+    fn out_degree(&self, x: &T) -> Option<usize> {
+        self.vertices.get(x).map(SmallSet::len)
+    }
+}
+
+impl<T, const N: usize> From<BTreeDAG<T>> for CompactDAG<T, N>
+where
+    T: Ord + Clone,
+{
+    fn from(dag: BTreeDAG<T>) -> Self {
+        let vertices: BTreeMap<T, SmallSet<T, N>> = dag
+            .vertices
+            .into_iter()
+            .map(|(x, adj_x)| (x, into_owned(adj_x).into_iter().collect()))
+            .collect();
+        CompactDAG { vertices }
+    }
+}
+
+impl<T, const N: usize> From<CompactDAG<T, N>> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn from(dag: CompactDAG<T, N>) -> Self {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = dag
+            .vertices
+            .into_iter()
+            .map(|(x, adj_x)| (x, Arc::new(adj_x.into_btree_set())))
+            .collect();
+        BTreeDAG::from_vertices(vertices)
+    }
+}
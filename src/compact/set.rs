@@ -0,0 +1,194 @@
+use alloc::collections::{btree_set, BTreeSet};
+use core::fmt;
+use core::iter::FromIterator;
+use core::slice;
+
+/// `SmallSet` is an ordered set which stores up to N elements inline, and
+/// spills to a `BTreeSet` once it grows beyond N elements. A spilled set
+/// remains spilled, even if elements are later removed.
+#[derive(Clone)]
+pub struct SmallSet<T, const N: usize> {
+    repr: Repr<T, N>,
+}
+
+#[derive(Clone)]
+enum Repr<T, const N: usize> {
+    // The first len items are occupied, and kept in ascending order.
+    Inline { len: usize, items: [Option<T>; N] },
+    Spilled(BTreeSet<T>),
+}
+
+impl<T, const N: usize> SmallSet<T, N>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        SmallSet {
+            repr: Repr::Inline {
+                len: 0,
+                items: core::array::from_fn(|_| None),
+            },
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline { len, .. } => *len,
+            Repr::Spilled(set) => set.len(),
+        }
+    }
+
+    /// Returns true if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the elements are stored inline.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline { .. })
+    }
+
+    /// Returns true if the set contains x.
+    pub fn contains(&self, x: &T) -> bool {
+        match &self.repr {
+            Repr::Inline { len, items } => search(&items[..*len], x).is_ok(),
+            Repr::Spilled(set) => set.contains(x),
+        }
+    }
+
+    /// Adds x to the set, returning true if it was not already present.
+    pub fn insert(&mut self, x: T) -> bool {
+        match &mut self.repr {
+            Repr::Inline { len, items } => match search(&items[..*len], &x) {
+                Ok(_) => false,
+                Err(i) if *len < N => {
+                    items[*len] = Some(x);
+                    items[i..=*len].rotate_right(1);
+                    *len += 1;
+                    true
+                }
+                Err(_) => {
+                    let mut set: BTreeSet<T> = items.iter_mut().filter_map(Option::take).collect();
+                    set.insert(x);
+                    self.repr = Repr::Spilled(set);
+                    true
+                }
+            },
+            Repr::Spilled(set) => set.insert(x),
+        }
+    }
+
+    /// Removes x from the set, returning true if it was present.
+    pub fn remove(&mut self, x: &T) -> bool {
+        match &mut self.repr {
+            Repr::Inline { len, items } => match search(&items[..*len], x) {
+                Ok(i) => {
+                    items[i] = None;
+                    items[i..*len].rotate_left(1);
+                    *len -= 1;
+                    true
+                }
+                Err(_) => false,
+            },
+            Repr::Spilled(set) => set.remove(x),
+        }
+    }
+
+    /// Returns an iterator over the elements of the set, in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &self.repr {
+            Repr::Inline { len, items } => Iter::Inline(items[..*len].iter()),
+            Repr::Spilled(set) => Iter::Spilled(set.iter()),
+        }
+    }
+
+    /// Consumes the set, returning its elements as a `BTreeSet`.
+    pub fn into_btree_set(self) -> BTreeSet<T> {
+        match self.repr {
+            Repr::Inline { items, .. } => IntoIterator::into_iter(items).flatten().collect(),
+            Repr::Spilled(set) => set,
+        }
+    }
+}
+
+/// Binary searches the occupied inline items for x.
+fn search<T>(items: &[Option<T>], x: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    // Every occupied item is Some, so we can safely unwrap.
+    items.binary_search_by(|item| item.as_ref().unwrap().cmp(x))
+}
+
+impl<T, const N: usize> Default for SmallSet<T, N>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> PartialEq for SmallSet<T, N>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize> Eq for SmallSet<T, N> where T: Ord {}
+
+impl<T, const N: usize> fmt::Debug for SmallSet<T, N>
+where
+    T: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallSet<T, N>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set: SmallSet<T, N> = SmallSet::new();
+        for x in iter {
+            set.insert(x);
+        }
+        set
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallSet<T, N>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// `Iter` is an iterator over the elements of a `SmallSet`, in ascending
+/// order.
+pub enum Iter<'a, T> {
+    Inline(slice::Iter<'a, Option<T>>),
+    Spilled(btree_set::Iter<'a, T>),
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Inline(items) => items.next().and_then(Option::as_ref),
+            Iter::Spilled(set) => set.next(),
+        }
+    }
+}
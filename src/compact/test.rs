@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::compact::*;
+    use crate::dag::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn small_set() {
+        // Insert up to the inline capacity, out of order.
+        let mut set: SmallSet<usize, 2> = SmallSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(1));
+        assert!(set.insert(0));
+        assert!(!set.insert(1));
+        assert!(set.is_inline());
+        assert_eq!(set.iter().collect::<Vec<&usize>>(), [&0, &1]);
+
+        // Removing keeps the remaining elements ordered.
+        assert!(set.remove(&0));
+        assert!(!set.remove(&0));
+        assert!(set.insert(2));
+        assert_eq!(set.iter().collect::<Vec<&usize>>(), [&1, &2]);
+
+        // Growing beyond the inline capacity spills to a BTreeSet.
+        assert!(set.insert(0));
+        assert!(!set.is_inline());
+        assert!(set.contains(&0));
+        assert_eq!(set.len(), 3);
+
+        // Sets with equal elements are equal, however they are stored.
+        let inline: SmallSet<usize, 4> = set.iter().cloned().collect();
+        let spilled: SmallSet<usize, 2> = inline.iter().cloned().collect();
+        assert!(inline.is_inline());
+        assert_eq!(spilled, set);
+        assert_eq!(inline.into_btree_set(), set.into_btree_set());
+
+        // Test passed.
+    }
+
+    #[test]
+    fn compact_dag() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1), (0, 2) and (1, 2).
+        let mut dag: CompactDAG<usize, 1> = CompactDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        assert!(!dag.connections(&0).unwrap().is_inline());
+        assert!(dag.connections(&1).unwrap().is_inline());
+        assert_eq!(dag.out_degree(&0), Some(2));
+        assert!(dag.adjacent(0, 2)?);
+
+        // Cycles are rejected.
+        assert_eq!(dag.add_edge(2, 0), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(3, 0), Err(Error::VertexDoesNotExist));
+
+        // Converting to and from a BTreeDAG preserves the edges.
+        let btree_dag: BTreeDAG<usize> = BTreeDAG::from(dag.clone());
+        assert_eq!(btree_dag.connections(0), Some(&[1, 2].into()));
+        assert_eq!(CompactDAG::from(btree_dag), dag);
+
+        // Removing a vertex removes its incoming edges.
+        assert_eq!(dag.remove_edge(0, 1)?, BTreeSet::from([1, 2]));
+        assert_eq!(dag.remove_vertex(2)?, BTreeSet::new());
+        assert_eq!(dag.out_degree(&0), Some(0));
+        assert_eq!(dag.remove_vertex(2), Err(Error::VertexDoesNotExist));
+
+        // Test passed.
+        Ok(())
+    }
+}
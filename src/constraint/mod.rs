@@ -0,0 +1,177 @@
+mod test;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `ConstraintSet` is a schema for the edges of a dag. Each vertex is assigned a
+/// kind by a classifier function, and an edge (x, y) is allowed only if the pair
+/// of kinds (kind of x, kind of y) has been registered as allowed.
+pub struct ConstraintSet<T, K>
+where
+    K: Ord,
+{
+    classify: Box<dyn Fn(&T) -> K>,
+    allowed: BTreeSet<(K, K)>,
+}
+
+impl<T, K> ConstraintSet<T, K>
+where
+    K: Ord,
+{
+    /// Returns a constraint set which classifies vertices with the given
+    /// function, and allows no edges.
+    pub fn new(classify: impl Fn(&T) -> K + 'static) -> Self {
+        ConstraintSet {
+            classify: Box::new(classify),
+            allowed: BTreeSet::new(),
+        }
+    }
+
+    /// Allows edges from vertices of the kind x to vertices of the kind y,
+    /// returning false if they were already allowed.
+    pub fn allow(&mut self, x: K, y: K) -> bool {
+        self.allowed.insert((x, y))
+    }
+
+    /// Forbids edges from vertices of the kind x to vertices of the kind y,
+    /// returning false if they were not allowed.
+    pub fn forbid(&mut self, x: K, y: K) -> bool {
+        self.allowed.remove(&(x, y))
+    }
+
+    /// Returns the kind of the vertex x.
+    pub fn kind(&self, x: &T) -> K {
+        (self.classify)(x)
+    }
+
+    /// Returns true if an edge (x, y) is allowed.
+    pub fn is_allowed(&self, x: &T, y: &T) -> bool {
+        self.allowed.contains(&(self.kind(x), self.kind(y)))
+    }
+
+    /// Returns `Error::ConstraintViolated` if an edge (x, y) is not allowed.
+    pub fn check(&self, x: &T, y: &T) -> Result<(), Error> {
+        if !self.is_allowed(x, y) {
+            return Err(Error::ConstraintViolated);
+        }
+        Ok(())
+    }
+
+    /// Returns `Error::ConstraintViolated` if any edge of the dag is not
+    /// allowed.
+    pub fn validate(&self, dag: &BTreeDAG<T>) -> Result<(), Error>
+    where
+        T: Ord,
+    {
+        for (x, adj_x) in dag.vertices.iter() {
+            for y in adj_x.iter() {
+                self.check(x, y)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `ConstrainedDAG` wraps a `BTreeDAG`, enforcing a `ConstraintSet` on every edge
+/// added in addition to acyclicity.
+pub struct ConstrainedDAG<T, K>
+where
+    T: Ord,
+    K: Ord,
+{
+    dag: BTreeDAG<T>,
+    constraints: ConstraintSet<T, K>,
+}
+
+impl<T, K> ConstrainedDAG<T, K>
+where
+    T: Ord,
+    K: Ord,
+{
+    pub fn new(constraints: ConstraintSet<T, K>) -> Self {
+        ConstrainedDAG {
+            dag: BTreeDAG::new(),
+            constraints,
+        }
+    }
+
+    /// Wraps an existing dag, returning an error if any of its edges are not
+    /// allowed by the constraints.
+    pub fn from_dag(dag: BTreeDAG<T>, constraints: ConstraintSet<T, K>) -> Result<Self, Error> {
+        constraints.validate(&dag)?;
+        Ok(ConstrainedDAG { dag, constraints })
+    }
+
+    /// Returns the constrained dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the constraints enforced on the dag.
+    pub fn constraints(&self) -> &ConstraintSet<T, K> {
+        &self.constraints
+    }
+
+    /// Returns the dag, discarding the constraints.
+    pub fn into_dag(self) -> BTreeDAG<T> {
+        self.dag
+    }
+}
+
+impl<T, K> AddVertex<T> for ConstrainedDAG<T, K>
+where
+    T: Ord + Clone,
+    K: Ord,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T, K> AddEdge<T> for ConstrainedDAG<T, K>
+where
+    T: Ord + Clone,
+    K: Ord,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.constraints.check(&x, &y)?;
+        self.dag.add_edge(x, y)
+    }
+}
+
+impl<T, K> RemoveEdge<T> for ConstrainedDAG<T, K>
+where
+    T: Ord + Clone,
+    K: Ord,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.remove_edge(x, y)
+    }
+}
+
+impl<T, K> RemoveVertex<T> for ConstrainedDAG<T, K>
+where
+    T: Ord + Clone,
+    K: Ord,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.remove_vertex(x)
+    }
+}
+
+impl<T, K> Prune<T> for ConstrainedDAG<T, K>
+where
+    T: Ord + Clone,
+    K: Ord,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x)
+    }
+}
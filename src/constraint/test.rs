@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::constraint::*;
+    use crate::dag::*;
+    use crate::Error;
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+    enum Kind {
+        Task,
+        Artifact,
+    }
+
+    // Even nodes are tasks, and odd nodes are artifacts.
+    fn constraints() -> ConstraintSet<usize, Kind> {
+        let mut constraints = ConstraintSet::new(|x: &usize| {
+            if x.is_multiple_of(2) {
+                Kind::Task
+            } else {
+                Kind::Artifact
+            }
+        });
+        assert!(constraints.allow(Kind::Task, Kind::Artifact));
+        assert!(constraints.allow(Kind::Task, Kind::Task));
+        constraints
+    }
+
+    #[test]
+    fn constrained_edges() -> Result<(), Error> {
+        // Add three nodes.
+        let mut dag: ConstrainedDAG<usize, Kind> = ConstrainedDAG::new(constraints());
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        assert_eq!(dag.constraints().kind(&1), Kind::Artifact);
+
+        // Tasks may depend on tasks and artifacts, but artifacts may not
+        // depend on anything.
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        assert_eq!(dag.add_edge(1, 2), Err(Error::ConstraintViolated));
+        assert!(!dag.dag().adjacent(1, 2)?);
+
+        // Acyclicity is still enforced.
+        assert_eq!(dag.add_edge(2, 0), Err(Error::EdgeExists));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn validate() -> Result<(), Error> {
+        // Add two nodes, and an edge (1, 0).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(1, 0)?;
+
+        assert!(ConstrainedDAG::from_dag(dag.clone(), constraints()).is_err());
+
+        let mut constraints = constraints();
+        assert!(constraints.allow(Kind::Artifact, Kind::Task));
+        let dag = ConstrainedDAG::from_dag(dag, constraints)?;
+        assert!(dag.constraints().is_allowed(&1, &0));
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use crate::dag::{AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `CountedDAG` wraps a `BTreeDAG`, counting the references to each edge. Adding
+/// an edge increments its count, and removing an edge decrements it, so the
+/// edge is only removed from the dag once every producer which added it has
+/// removed it. Removing a vertex removes its edges regardless of their counts.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CountedDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    counts: BTreeMap<(T, T), usize>,
+}
+
+impl<T> CountedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        CountedDAG {
+            dag: BTreeDAG::new(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the counted dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Removes the counts of every edge which no longer exists.
+    fn retain_existing(&mut self) {
+        let dag = &self.dag;
+        self.counts
+            .retain(|(x, y), _| dag.vertices.get(x).is_some_and(|adj_x| adj_x.contains(y)));
+    }
+}
+
+impl<T> CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Returns the number of references to the edge (x, y), which is zero if
+    /// the edge does not exist.
+    pub fn edge_references(&self, x: &T, y: &T) -> usize {
+        self.counts
+            .get(&(x.clone(), y.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl<T> Default for CountedDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AddVertex<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        let adj_x = self.dag.add_vertex(x);
+        // Re-adding a vertex removes its edges.
+        if adj_x.as_ref().is_some_and(|adj_x| !adj_x.is_empty()) {
+            self.retain_existing();
+        }
+        adj_x
+    }
+}
+
+impl<T> AddEdge<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.add_edge(x.clone(), y.clone())?;
+        *self.counts.entry((x, y)).or_insert(0) += 1;
+        Ok(adj_x)
+    }
+}
+
+impl<T> RemoveEdge<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let edge = (x, y);
+        match self.counts.get_mut(&edge) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                // The edge has a count, so x exists.
+                Ok(BTreeSet::clone(&self.dag.vertices[&edge.0]))
+            }
+            _ => {
+                self.counts.remove(&edge);
+                self.dag.remove_edge(edge.0, edge.1)
+            }
+        }
+    }
+}
+
+impl<T> RemoveVertex<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_vertex(x)?;
+        self.retain_existing();
+        Ok(adj_x)
+    }
+}
+
+impl<T> Prune<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x)?;
+        self.retain_existing();
+        Ok(())
+    }
+}
+
+impl<T> Adjacent<T> for CountedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        self.dag.adjacent(x, y)
+    }
+}
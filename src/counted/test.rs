@@ -0,0 +1,40 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::counted::*;
+    use crate::dag::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn counted_edges() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) from two producers.
+        let mut dag: CountedDAG<usize> = CountedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 1)?;
+        assert_eq!(dag.edge_references(&0, &1), 2);
+        assert!(dag.add_edge(1, 0).is_err());
+        assert_eq!(dag.edge_references(&1, &0), 0);
+
+        // The edge remains until both producers remove it.
+        assert_eq!(dag.remove_edge(0, 1)?, BTreeSet::from([1]));
+        assert!(dag.adjacent(0, 1)?);
+        assert_eq!(dag.remove_edge(0, 1)?, BTreeSet::from([1]));
+        assert!(!dag.adjacent(0, 1)?);
+        assert_eq!(dag.edge_references(&0, &1), 0);
+
+        // Removing a vertex removes its edges regardless of their counts.
+        dag.add_edge(0, 2)?;
+        dag.add_edge(0, 2)?;
+        dag.remove_vertex(2)?;
+        dag.add_vertex(2);
+        assert_eq!(dag.edge_references(&0, &2), 0);
+        assert!(!dag.adjacent(0, 2)?);
+
+        // Test passed.
+        Ok(())
+    }
+}
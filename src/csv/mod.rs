@@ -0,0 +1,154 @@
+mod test;
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use core::fmt::Display;
+use core::str::FromStr;
+use std::io::{self, BufRead, Write};
+
+use crate::dag::{AddEdge, BTreeDAG};
+use crate::Error;
+
+/// The format of a delimited edge list: the delimiter between the columns, and
+/// whether the first line is a header. The default is comma separated with no
+/// header; tab separated lists use a delimiter of `'\t'`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            has_header: false,
+        }
+    }
+}
+
+/// Errors which may occur while reading an edge list: either the reader
+/// failed, or a line, numbered from 1, could not be added to the dag.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    Line { line: usize, error: Error },
+}
+
+#[cfg(feature = "fmt")]
+impl Display for CsvError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            CsvError::Io(err) => write!(f, "{}", err),
+            CsvError::Line { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(e: io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+/// `FromCsv` reads a dag from a delimited edge list, with one edge per line
+/// as two columns, x then y. A line with a single column adds an isolated
+/// vertex, and blank lines are skipped. Columns are trimmed and parsed with
+/// `FromStr`; quoting is not supported. An error names the line which was
+/// malformed, or whose edge introduced a cycle.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, Adjacent, CsvOptions, FromCsv};
+/// let csv = "source\ttarget\norigin\twaypoint\nwaypoint\tdestination\n";
+/// let options = CsvOptions { delimiter: '\t', has_header: true };
+/// let dag: BTreeDAG<String> = BTreeDAG::from_csv_reader(csv.as_bytes(), options).unwrap();
+///
+/// assert!(dag.adjacent(String::from("origin"), String::from("waypoint")).unwrap());
+/// ```
+pub trait FromCsv: Sized {
+    fn from_csv_reader(r: impl BufRead, options: CsvOptions) -> Result<Self, CsvError>;
+}
+
+/// `ToCsv` writes the dag as a delimited edge list which `FromCsv` reads back:
+/// one line per edge, and one line per vertex with no incident edges.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CsvOptions, ToCsv};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let mut csv: Vec<u8> = Vec::new();
+/// dag.to_csv_writer(&mut csv, CsvOptions::default()).unwrap();
+/// assert_eq!(csv, b"origin,destination\n");
+/// ```
+pub trait ToCsv {
+    fn to_csv_writer(&self, w: impl Write, options: CsvOptions) -> io::Result<()>;
+}
+
+impl<T> FromCsv for BTreeDAG<T>
+where
+    T: Ord + Clone + FromStr,
+{
+    fn from_csv_reader(r: impl BufRead, options: CsvOptions) -> Result<Self, CsvError> {
+        let mut dag: BTreeDAG<T> = BTreeDAG::new();
+        let skip = if options.has_header { 1 } else { 0 };
+        for (i, line) in r.lines().enumerate().skip(skip) {
+            let line = line?;
+            let at_line = |error: Error| CsvError::Line { line: i + 1, error };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut columns = line.split(options.delimiter).map(|column| {
+                column
+                    .trim()
+                    .parse::<T>()
+                    .map_err(|_| at_line(Error::ParseError))
+            });
+            // A non-blank line has at least one column, so we can safely
+            // unwrap.
+            let x: T = columns.next().unwrap()?;
+            let y: Option<T> = columns.next().transpose()?;
+            if columns.next().is_some() {
+                return Err(at_line(Error::ParseError));
+            }
+            dag.vertices
+                .entry(x.clone())
+                .or_insert_with(|| Arc::new(BTreeSet::new()));
+            if let Some(y) = y {
+                dag.vertices
+                    .entry(y.clone())
+                    .or_insert_with(|| Arc::new(BTreeSet::new()));
+                dag.add_edge(x, y).map_err(at_line)?;
+            }
+        }
+        Ok(dag)
+    }
+}
+
+impl<T> ToCsv for BTreeDAG<T>
+where
+    T: Ord + Display,
+{
+    fn to_csv_writer(&self, mut w: impl Write, options: CsvOptions) -> io::Result<()> {
+        let d = options.delimiter;
+        if options.has_header {
+            writeln!(w, "source{}target", d)?;
+        }
+        let targets: BTreeSet<&T> = self.vertices.values().flat_map(|adj| adj.iter()).collect();
+        for (x, adj_x) in self.vertices.iter() {
+            if adj_x.is_empty() && !targets.contains(x) {
+                writeln!(w, "{}", x)?;
+            }
+            for y in adj_x.iter() {
+                writeln!(w, "{}{}{}", x, d, y)?;
+            }
+        }
+        Ok(())
+    }
+}
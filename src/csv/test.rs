@@ -0,0 +1,62 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::csv::*;
+    use crate::dag::*;
+    use crate::Error;
+    use std::vec::Vec;
+
+    #[test]
+    fn csv() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // The written edge list reads back into an equal dag.
+        for options in [
+            CsvOptions::default(),
+            CsvOptions {
+                delimiter: '\t',
+                has_header: true,
+            },
+        ] {
+            let mut csv: Vec<u8> = Vec::new();
+            dag.to_csv_writer(&mut csv, options).unwrap();
+            let read: BTreeDAG<usize> = BTreeDAG::from_csv_reader(csv.as_slice(), options).unwrap();
+            assert_eq!(read, dag);
+        }
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn csv_errors() {
+        let read =
+            |csv: &str| BTreeDAG::<usize>::from_csv_reader(csv.as_bytes(), CsvOptions::default());
+        match read("0,1\n\n1,2\n2,0\n") {
+            Err(CsvError::Line { line, error }) => {
+                assert_eq!(line, 4);
+                assert_eq!(error, Error::EdgeExists);
+            }
+            _ => panic!("expected the line of the cycle"),
+        }
+        match read("0,1\n1,a\n") {
+            Err(CsvError::Line { line, error }) => {
+                assert_eq!(line, 2);
+                assert_eq!(error, Error::ParseError);
+            }
+            _ => panic!("expected the line of the malformed edge"),
+        }
+        assert!(matches!(
+            read("0,1,2\n"),
+            Err(CsvError::Line { line: 1, .. })
+        ));
+
+        // Test passed.
+    }
+}
@@ -1,4 +1,16 @@
-use alloc::collections::BTreeSet;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+
+use crate::frozen::FrozenDag;
+use crate::{ContextError, Error};
+
+use crate::dag::{
+    BTreeDAG, CancellationToken, Canonical, Coarsening, Cursor, DagView, DanglingEdges,
+    IntervalLabels, LayoutHints, MissingVertices, Path, Query, ReachabilityIndex, RepairReport,
+    Scheduler, SortedEdges, SortedVertices, TraversalOrder, TraverseAll, VertexEntry,
+};
 
 /// `Vertices` returns the set of the vertices which comprise the dag.
 ///
@@ -163,7 +175,9 @@ pub trait Adjacent<T> {
 }
 
 /// `Connections` lists all vertices y such that there is an edge from the vertex x to
-/// the vertex y. An error is thrown if x does not exist.
+/// the vertex y. An error is thrown if x does not exist. An edge (x, y) means x
+/// depends on y, so these are the dependencies of x; `Connections` is an alias for
+/// `Successors`, which should be preferred in new code.
 ///
 /// # Example
 ///
@@ -181,8 +195,9 @@ pub trait Connections<T> {
 }
 
 /// `Prune` remove vertex x and recursively remove all children
-/// of x. Prune will error if x does not exists, and by `BTreeDAG`'s
-/// contract, all recursive calls cannot error.
+/// of x. Prune will error if x does not exists. `BTreeDAG`'s prune is
+/// transactional: every vertex to remove is collected before any is removed,
+/// so on error the dag is unchanged.
 ///
 /// # Example
 ///
@@ -209,4 +224,1766 @@ pub trait Connections<T> {
 pub trait Prune<T> {
     type Error;
     fn prune(&mut self, x: T) -> Result<(), Self::Error>;
-}
\ No newline at end of file
+}
+/// `Entry` gets the given vertex's corresponding entry in the dag for in-place
+/// manipulation, mirroring `BTreeMap::entry`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, Entry, Adjacent};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("destination_A"));
+/// dag.add_vertex(String::from("destination_B"));
+///
+/// assert!(dag.entry(String::from("origin")).get().is_none());
+///
+/// let mut origin = dag.entry(String::from("origin"));
+/// origin.add_edge_to(String::from("destination_A")).unwrap();
+/// origin.add_edge_to(String::from("destination_B")).unwrap();
+/// assert_eq!(origin.or_insert().len(), 2);
+///
+/// assert!(dag.adjacent(String::from("origin"), String::from("destination_A")).unwrap());
+/// ```
+pub trait Entry<T>
+where
+    T: Ord,
+{
+    fn entry(&mut self, x: T) -> VertexEntry<'_, T>;
+}
+
+/// `DrainEdgesWhere` removes every edge (x, y) for which the predicate returns
+/// true, and returns the removed edges in a single pass over the dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, DrainEdgesWhere, Adjacent};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("soft_destination"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("soft_destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let drained = dag.drain_edges_where(|_, y| y.starts_with("soft"));
+/// assert_eq!(drained, vec![(String::from("origin"), String::from("soft_destination"))]);
+///
+/// assert!(!dag.adjacent(String::from("origin"), String::from("soft_destination")).unwrap());
+/// assert!(dag.adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// ```
+pub trait DrainEdgesWhere<T> {
+    fn drain_edges_where(&mut self, pred: impl FnMut(&T, &T) -> bool) -> Vec<(T, T)>;
+}
+
+/// `ShortestPath` returns a path with the fewest edges from the vertex x to the
+/// vertex y, or `None` if y is not reachable from x. Ties are broken by the
+/// ordering of the vertices. An error is thrown if either x, or y do not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, ShortestPath};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let path = dag
+///     .shortest_path(&String::from("origin"), &String::from("destination"))
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(path.len(), 3);
+/// assert!(path.contains(&String::from("waypoint")));
+///
+/// assert!(dag
+///     .shortest_path(&String::from("destination"), &String::from("origin"))
+///     .unwrap()
+///     .is_none());
+/// ```
+pub trait ShortestPath<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn shortest_path(&self, x: &T, y: &T) -> Result<Option<Path<'_, T>>, Self::Error>;
+}
+
+/// `ChainDecomposition` splits the dag into a minimum number of chains, such that
+/// every vertex belongs to exactly one chain. Within a chain, every vertex is
+/// reachable from the vertex before it, although the two need not be adjacent.
+/// By Dilworth's theorem the number of chains equals the size of the largest set
+/// of mutually unreachable vertices. Chains are ordered by their first vertex.
+///
+/// The decomposition is exact: it is a maximum matching over the reachability
+/// relation, which is materialized in full as one bitset per vertex, built in a
+/// single pass over a topological order. This takes V²/8 bytes of memory, and
+/// O(V · (V + E) / 64) time for the reachability, plus up to O(V · R) time for
+/// the matching, where R is the number of reachable pairs, so it is best suited
+/// to dags of up to some tens of thousands of vertices.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, ChainDecomposition};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination_A"));
+/// dag.add_vertex(String::from("destination_B"));
+/// dag.add_edge(String::from("origin"), String::from("destination_A"));
+/// dag.add_edge(String::from("origin"), String::from("destination_B"));
+///
+/// let chains = dag.chain_decomposition();
+/// assert_eq!(chains.len(), 2);
+/// assert_eq!(chains[0], vec![&String::from("destination_B")]);
+/// assert_eq!(chains[1], vec![&String::from("origin"), &String::from("destination_A")]);
+/// ```
+pub trait ChainDecomposition<T> {
+    fn chain_decomposition(&self) -> Vec<Vec<&T>>;
+}
+
+/// `FromEdges` constructs a dag from a list of edges (x, y), creating the vertices
+/// as they are encountered. The whole list is validated for acyclicity once,
+/// rather than once per edge, and an error is thrown if the edges contain a cycle.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, FromEdges, Adjacent, Vertices};
+/// use btree_dag::Error;
+/// let dag: BTreeDAG<String> = BTreeDAG::from_edges(vec![
+///     (String::from("origin"), String::from("waypoint")),
+///     (String::from("waypoint"), String::from("destination")),
+/// ])
+/// .unwrap();
+///
+/// assert_eq!(dag.vertices().len(), 3);
+/// assert!(dag.adjacent(String::from("origin"), String::from("waypoint")).unwrap());
+///
+/// let err: Error = BTreeDAG::from_edges(vec![
+///     (String::from("origin"), String::from("destination")),
+///     (String::from("destination"), String::from("origin")),
+/// ])
+/// .unwrap_err();
+/// assert_eq!(err, Error::EdgeExists);
+/// ```
+pub trait FromEdges<T>: Sized {
+    type Error;
+    fn from_edges(edges: impl IntoIterator<Item = (T, T)>) -> Result<Self, Self::Error>;
+}
+
+/// `FeedbackArcSet` suggests a small set of edges which, when dropped from the given
+/// list of edges, leaves the remaining edges acyclic. The set is computed with the
+/// greedy heuristic of Eades, Lin and Smyth, and so is not guaranteed to be minimum.
+/// Edges from a vertex to itself are always included.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, FeedbackArcSet, FromEdges};
+/// let edges = vec![
+///     (String::from("origin"), String::from("waypoint")),
+///     (String::from("waypoint"), String::from("destination")),
+///     (String::from("destination"), String::from("origin")),
+/// ];
+///
+/// let feedback = BTreeDAG::feedback_arc_set(edges.clone());
+/// assert_eq!(feedback.len(), 1);
+///
+/// let remaining = edges.into_iter().filter(|e| !feedback.contains(e));
+/// assert!(BTreeDAG::from_edges(remaining).is_ok());
+/// ```
+pub trait FeedbackArcSet<T> {
+    fn feedback_arc_set(edges: impl IntoIterator<Item = (T, T)>) -> BTreeSet<(T, T)>;
+}
+
+/// `TopologicalSort` returns every vertex of the dag ordered such that for every
+/// edge (x, y), x comes before y. Among the vertices which may come next, the
+/// least is always chosen, so the ordering is deterministic. An empty dag sorts
+/// to an empty list.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TopologicalSort};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// assert_eq!(
+///     dag.topological_sort(),
+///     vec![&String::from("origin"), &String::from("waypoint"), &String::from("destination")]
+/// );
+/// ```
+pub trait TopologicalSort<T> {
+    fn topological_sort(&self) -> Vec<&T>;
+}
+
+/// `IterSorted` iterates over every vertex of the dag, along with its adjacency
+/// list, in ascending order of the vertices. This ordering is guaranteed, and is
+/// part of the crate's stable API.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, IterSorted};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let vertices: Vec<&String> = dag.iter_sorted().map(|(v, _)| v).collect();
+/// assert_eq!(vertices, vec![&String::from("destination"), &String::from("origin")]);
+/// ```
+pub trait IterSorted<T>
+where
+    T: Ord,
+{
+    fn iter_sorted(&self) -> SortedVertices<'_, T>;
+}
+
+/// `EdgesSorted` iterates over every edge (x, y) of the dag in ascending
+/// lexicographic order of (x, y). This ordering is guaranteed, and is part of
+/// the crate's stable API.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, EdgesSorted};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination_A"));
+/// dag.add_vertex(String::from("destination_B"));
+/// dag.add_edge(String::from("origin"), String::from("destination_B"));
+/// dag.add_edge(String::from("origin"), String::from("destination_A"));
+///
+/// let edges: Vec<(&String, &String)> = dag.edges_sorted().collect();
+/// assert_eq!(edges[0], (&String::from("origin"), &String::from("destination_A")));
+/// assert_eq!(edges[1], (&String::from("origin"), &String::from("destination_B")));
+/// ```
+pub trait EdgesSorted<T>
+where
+    T: Ord,
+{
+    fn edges_sorted(&self) -> SortedEdges<'_, T>;
+}
+
+/// `ToCanonical` returns the canonical representation of the dag, which
+/// serializes identically for equal dags. See `Canonical`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Canonical, ToCanonical};
+/// use std::convert::TryFrom;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let canonical: Canonical<&String> = dag.to_canonical();
+/// assert_eq!(canonical.vertices, vec![&String::from("destination"), &String::from("origin")]);
+/// assert_eq!(canonical.edges, vec![(&String::from("origin"), &String::from("destination"))]);
+///
+/// let owned: Canonical<String> = Canonical {
+///     vertices: canonical.vertices.into_iter().cloned().collect(),
+///     edges: canonical.edges.into_iter().map(|(x, y)| (x.clone(), y.clone())).collect(),
+/// };
+/// assert_eq!(BTreeDAG::try_from(owned).unwrap(), dag);
+/// ```
+pub trait ToCanonical<T> {
+    fn to_canonical(&self) -> Canonical<&T>;
+}
+
+/// `OutDegree` returns the number of edges from the vertex x, or `None` if x does
+/// not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, OutDegree};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert_eq!(dag.out_degree(&String::from("origin")), Some(1));
+/// assert_eq!(dag.out_degree(&String::from("destination")), Some(0));
+/// assert_eq!(dag.out_degree(&String::from("waypoint")), None);
+/// ```
+pub trait OutDegree<T> {
+    fn out_degree(&self, x: &T) -> Option<usize>;
+}
+
+/// `MemoryFootprint` estimates the heap memory, in bytes, used by the dag's own
+/// structure, given `size_of::<T>()` and the number of vertices and edges. Heap
+/// memory owned by the vertices themselves (e.g. the contents of a `String`) is
+/// not included, and adjacency sets shared between vertices are counted once.
+/// The estimate assumes fully occupied B-tree nodes, so it is a lower bound on
+/// the actual usage of the standard library's B-tree.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, MemoryFootprint};
+/// let mut dag: BTreeDAG<u32> = BTreeDAG::new();
+/// assert_eq!(dag.memory_footprint_estimate(), 0);
+///
+/// dag.add_vertex(0);
+/// dag.add_vertex(1);
+/// let without_edge: usize = dag.memory_footprint_estimate();
+/// dag.add_edge(0, 1);
+/// assert!(dag.memory_footprint_estimate() > without_edge);
+/// assert!(dag.vertex_footprint_estimate(&0).unwrap() > dag.vertex_footprint_estimate(&1).unwrap());
+/// ```
+pub trait MemoryFootprint<T> {
+    fn memory_footprint_estimate(&self) -> usize;
+    fn vertex_footprint_estimate(&self, x: &T) -> Option<usize>;
+}
+
+/// `BuildReachabilityIndex` precomputes a `ReachabilityIndex` for the dag, which
+/// answers repeated reachability queries without searching the dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, BuildReachabilityIndex};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let index = dag.build_reachability_index();
+/// assert!(index.is_reachable(&String::from("origin"), &String::from("destination")).unwrap());
+/// assert!(!index.is_reachable(&String::from("destination"), &String::from("origin")).unwrap());
+/// ```
+pub trait BuildReachabilityIndex<T>
+where
+    T: Ord,
+{
+    fn build_reachability_index(&self) -> ReachabilityIndex<'_, T>;
+}
+
+/// `Successors` lists all vertices y such that there is an edge from the vertex x to
+/// the vertex y, i.e. the outgoing neighbours of x, or returns `None` if x does not
+/// exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Successors};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert!(dag.successors(&String::from("origin")).unwrap().contains(&String::from("destination")));
+/// assert!(dag.successors(&String::from("destination")).unwrap().is_empty());
+/// ```
+pub trait Successors<T> {
+    fn successors(&self, x: &T) -> Option<&BTreeSet<T>>;
+}
+
+/// `Predecessors` lists all vertices w such that there is an edge from the vertex w
+/// to the vertex x, i.e. the incoming neighbours of x, or returns `None` if x does
+/// not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Predecessors};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert!(dag.predecessors(&String::from("destination")).unwrap().contains(&String::from("origin")));
+/// assert!(dag.predecessors(&String::from("origin")).unwrap().is_empty());
+/// ```
+pub trait Predecessors<T> {
+    fn predecessors(&self, x: &T) -> Option<BTreeSet<&T>>;
+}
+
+/// `TryTopologicalSortWithBudget` sorts the vertices like `TopologicalSort`, but
+/// spends one operation per vertex and per edge, and returns `Error::Cancelled`
+/// instead of exceeding ops_limit operations.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Error, TryTopologicalSortWithBudget};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert_eq!(dag.try_topological_sort_with_budget(1), Err(Error::Cancelled));
+/// assert_eq!(dag.try_topological_sort_with_budget(4).unwrap().len(), 2);
+/// ```
+pub trait TryTopologicalSortWithBudget<T> {
+    type Error;
+    fn try_topological_sort_with_budget(&self, ops_limit: usize) -> Result<Vec<&T>, Self::Error>;
+}
+
+/// `TryDescendants` returns the set of vertices reachable from the vertex x,
+/// excluding x, and returns `Error::Cancelled` as soon as the token is cancelled.
+/// An error is returned if x does not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CancellationToken, Error, TryDescendants};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let token = CancellationToken::new();
+/// assert_eq!(dag.try_descendants(&String::from("origin"), &token).unwrap().len(), 1);
+/// token.clone().cancel();
+/// assert_eq!(dag.try_descendants(&String::from("origin"), &token), Err(Error::Cancelled));
+/// ```
+pub trait TryDescendants<T> {
+    type Error;
+    fn try_descendants(
+        &self,
+        x: &T,
+        token: &CancellationToken,
+    ) -> Result<BTreeSet<&T>, Self::Error>;
+}
+
+/// `Merge` returns the union of two dags: every vertex and every edge of either
+/// dag. An error is returned if the union contains a cycle.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, Merge, Vertices};
+/// let mut declared: BTreeDAG<String> = BTreeDAG::new();
+/// declared.add_vertex(String::from("origin"));
+/// declared.add_vertex(String::from("destination"));
+/// declared.add_edge(String::from("origin"), String::from("destination"));
+/// let mut observed: BTreeDAG<String> = BTreeDAG::new();
+/// observed.add_vertex(String::from("waypoint"));
+///
+/// let merged: BTreeDAG<String> = declared.merge(&observed).unwrap();
+/// assert_eq!(merged.vertices().len(), 3);
+/// assert!(merged.adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// ```
+pub trait Merge<T>: Sized {
+    type Error;
+    fn merge(&self, other: &Self) -> Result<Self, Self::Error>;
+}
+
+/// `Intersection` returns the dag of the vertices and edges which are in both
+/// dags.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Intersection};
+/// let mut declared: BTreeDAG<String> = BTreeDAG::new();
+/// declared.add_vertex(String::from("origin"));
+/// declared.add_vertex(String::from("destination"));
+/// declared.add_edge(String::from("origin"), String::from("destination"));
+/// let mut observed: BTreeDAG<String> = declared.clone();
+/// observed.add_vertex(String::from("waypoint"));
+///
+/// let shared: BTreeDAG<String> = declared.intersection(&observed);
+/// assert_eq!(shared, declared);
+/// ```
+pub trait Intersection<T> {
+    fn intersection(&self, other: &Self) -> Self;
+}
+
+/// `Difference` returns the dag of every vertex of this dag, and the edges of
+/// this dag which are not in the other dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, Difference, Vertices};
+/// let mut declared: BTreeDAG<String> = BTreeDAG::new();
+/// declared.add_vertex(String::from("origin"));
+/// declared.add_vertex(String::from("destination"));
+/// declared.add_edge(String::from("origin"), String::from("destination"));
+/// let mut observed: BTreeDAG<String> = BTreeDAG::new();
+/// observed.add_vertex(String::from("origin"));
+/// observed.add_vertex(String::from("destination"));
+///
+/// let undeclared: BTreeDAG<String> = declared.difference(&observed);
+/// assert!(undeclared.adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// assert!(!observed.difference(&declared).adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// assert_eq!(observed.difference(&declared).vertices().len(), 2);
+/// ```
+pub trait Difference<T> {
+    fn difference(&self, other: &Self) -> Self;
+}
+
+/// `ContractEdge` contracts the edge (x, y), merging the vertex y into the vertex x:
+/// the edges from y become edges from x, the edges to y become edges to x, and y is
+/// removed and returned. An error is returned if the edge does not exist, or if the
+/// contraction would introduce a cycle, which happens when there is another path
+/// from x to y.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, ContractEdge, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let removed = dag.contract_edge(String::from("origin"), String::from("waypoint")).unwrap();
+/// assert_eq!(removed, String::from("waypoint"));
+/// assert_eq!(dag.vertices().len(), 2);
+/// assert!(dag.adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// ```
+pub trait ContractEdge<T> {
+    type Error;
+    fn contract_edge(&mut self, x: T, y: T) -> Result<T, Self::Error>;
+}
+
+/// `Coarsen` summarizes the dag at up to the given number of levels. Each level
+/// contracts a set of disjoint chain edges of the previous level, i.e. edges which
+/// are the only edge from their first vertex and the only edge to their second
+/// vertex, merging the second vertex into the first, which roughly halves every
+/// chain. Contracting chain edges can never introduce a cycle. Fewer levels are
+/// returned if a level has no chain edges left to contract.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Coarsen, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let levels = dag.coarsen(4);
+/// assert_eq!(levels.len(), 2);
+/// assert_eq!(levels[1].dag.vertices().len(), 1);
+/// assert_eq!(levels[1].mapping[&String::from("destination")], String::from("origin"));
+/// ```
+pub trait Coarsen<T>
+where
+    T: Ord,
+{
+    fn coarsen(&self, levels: usize) -> Vec<Coarsening<T>>;
+}
+
+/// `VertexIndex` maps between the vertices and their positions in ascending order,
+/// for keeping parallel per-vertex arrays. The index of a vertex x only changes when
+/// a vertex less than x is added or removed. Both lookups take linear time in the
+/// index; `Freeze` provides faster lookups on an immutable snapshot.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, VertexIndex};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+///
+/// assert_eq!(dag.index_of(&String::from("origin")), Some(1));
+/// assert_eq!(dag.vertex_at(0), Some(&String::from("destination")));
+/// assert_eq!(dag.vertex_at(2), None);
+/// ```
+pub trait VertexIndex<T> {
+    fn index_of(&self, x: &T) -> Option<usize>;
+    fn vertex_at(&self, i: usize) -> Option<&T>;
+}
+
+/// `Freeze` returns an immutable `FrozenDag` snapshot of the dag, in a layout
+/// optimized for repeated traversals. The dag remains editable, and later
+/// mutations do not affect the snapshot.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Freeze, FrozenDag, VertexIndex};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let frozen: FrozenDag<String> = dag.freeze();
+/// let origin = frozen.index_of(&String::from("origin")).unwrap();
+/// let destination = frozen.index_of(&String::from("destination")).unwrap();
+/// assert!(frozen.is_reachable(origin, destination).unwrap());
+/// ```
+pub trait Freeze<T> {
+    fn freeze(&self) -> FrozenDag<T>;
+}
+
+/// `TraverseAllVertices` returns an iterator which visits every vertex exactly once,
+/// by a depth first search from every root in turn, skipping vertices which have
+/// already been visited from an earlier root.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TraverseAllVertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin_A"));
+/// dag.add_vertex(String::from("origin_B"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin_A"), String::from("destination"));
+/// dag.add_edge(String::from("origin_B"), String::from("destination"));
+///
+/// let visited: Vec<&String> = dag.traverse_all().collect();
+/// assert_eq!(visited, vec!["origin_A", "destination", "origin_B"]);
+/// ```
+pub trait TraverseAllVertices<T>
+where
+    T: Ord,
+{
+    fn traverse_all(&self) -> TraverseAll<'_, T>;
+}
+
+/// `DescendantsWithin` returns the vertices reachable from the vertex x by a path
+/// of at most depth edges, each with the length of its shortest such path. The
+/// vertex x itself is excluded, and the map is empty if x does not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, DescendantsWithin};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let within = dag.descendants_within(&String::from("origin"), 1);
+/// assert_eq!(within.len(), 1);
+/// assert_eq!(within[&String::from("waypoint")], 1);
+/// ```
+pub trait DescendantsWithin<T> {
+    fn descendants_within(&self, x: &T, depth: usize) -> BTreeMap<&T, usize>;
+}
+
+/// `TopologicalRanks` assigns each vertex its level: 0 for vertices with no incoming
+/// edges, and otherwise one more than the greatest level of the vertices with an
+/// edge to it, so every edge points to a greater level. The returned map acts as
+/// a cache, answering each lookup in logarithmic time; as it borrows the dag, it
+/// can not outlive a mutation.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TopologicalRanks};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let ranks = dag.topological_ranks();
+/// assert_eq!(ranks[&String::from("origin")], 0);
+/// assert_eq!(ranks[&String::from("destination")], 2);
+/// ```
+pub trait TopologicalRanks<T> {
+    fn topological_ranks(&self) -> BTreeMap<&T, usize>;
+}
+
+/// `CanonicalForm` returns a label-independent canonical form of the dag: the
+/// vertices are relabeled `0..n`, such that two dags have the same canonical form
+/// exactly when they are isomorphic, i.e. equal up to renaming their vertices. It
+/// is suitable for de-duplicating structurally identical graphs. The search for
+/// the form is fast for typical dags, but may take exponential time on
+/// adversarially symmetric ones.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CanonicalForm};
+/// let mut a: BTreeDAG<String> = BTreeDAG::new();
+/// a.add_vertex(String::from("origin"));
+/// a.add_vertex(String::from("destination"));
+/// a.add_edge(String::from("origin"), String::from("destination"));
+/// let mut b: BTreeDAG<String> = BTreeDAG::new();
+/// b.add_vertex(String::from("destination"));
+/// b.add_vertex(String::from("origin"));
+/// b.add_edge(String::from("destination"), String::from("origin"));
+///
+/// assert_eq!(a.canonical_form(), b.canonical_form());
+/// ```
+pub trait CanonicalForm<T> {
+    fn canonical_form(&self) -> Canonical<usize>;
+}
+
+/// `Schedule` constructs a `Scheduler`, which hands out the vertices as tasks once
+/// the vertices they depend on have been completed. With `scheduler_by`, the ready
+/// vertices are handed out in descending order of the given priority, e.g. the
+/// length of the longest path through each vertex for critical-path-first
+/// scheduling.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Schedule};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("binary"));
+/// dag.add_vertex(String::from("docs"));
+/// dag.add_vertex(String::from("library"));
+/// dag.add_edge(String::from("binary"), String::from("library"));
+///
+/// let mut scheduler = dag.scheduler_by(|task: &String| task.len());
+/// assert_eq!(scheduler.ready().collect::<Vec<&String>>(), vec!["library", "docs"]);
+/// let library = scheduler.pop().unwrap();
+/// scheduler.complete(library);
+/// assert_eq!(scheduler.pop().unwrap(), "binary");
+/// ```
+pub trait Schedule<T>
+where
+    T: Ord,
+{
+    fn scheduler(&self) -> Scheduler<'_, T, ()>;
+    fn scheduler_by<'a, P>(&'a self, priority: impl Fn(&T) -> P + 'a) -> Scheduler<'a, T, P>
+    where
+        P: Ord;
+}
+
+/// `EarliestStartTimes` returns the earliest time each vertex can start, given the
+/// duration of each vertex, when every vertex starts as soon as all the vertices it
+/// has an edge to have finished.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, EarliestStartTimes};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("binary"));
+/// dag.add_vertex(String::from("library"));
+/// dag.add_edge(String::from("binary"), String::from("library"));
+///
+/// let starts = dag.earliest_start_times(|task: &String| task.len() as u64);
+/// assert_eq!(starts[&String::from("library")], 0);
+/// assert_eq!(starts[&String::from("binary")], 7);
+/// ```
+pub trait EarliestStartTimes<T> {
+    fn earliest_start_times(&self, durations: impl Fn(&T) -> u64) -> BTreeMap<&T, u64>;
+}
+
+/// `Slack` returns how long each vertex can be delayed past its earliest start time
+/// without delaying the completion of every vertex, given the duration of each
+/// vertex. The vertices with no slack form the critical path.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Slack};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("binary"));
+/// dag.add_vertex(String::from("docs"));
+/// dag.add_vertex(String::from("library"));
+/// dag.add_edge(String::from("binary"), String::from("library"));
+///
+/// let slack = dag.slack(|task: &String| task.len() as u64);
+/// assert_eq!(slack[&String::from("library")], 0);
+/// assert_eq!(slack[&String::from("docs")], 9);
+/// ```
+pub trait Slack<T> {
+    fn slack(&self, durations: impl Fn(&T) -> u64) -> BTreeMap<&T, u64>;
+}
+
+/// `CountPaths` returns the number of distinct paths from the vertex x to the
+/// vertex y, where x has a single empty path to itself. The count saturates at
+/// `u128::MAX`, and is zero if either vertex does not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CountPaths};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// assert_eq!(dag.count_paths(&String::from("origin"), &String::from("destination")), 2);
+/// assert_eq!(dag.count_paths(&String::from("destination"), &String::from("origin")), 0);
+/// ```
+pub trait CountPaths<T> {
+    fn count_paths(&self, from: &T, to: &T) -> u128;
+}
+
+/// `FoldTopological` computes a value for each vertex from the values of its
+/// predecessors, the vertices with an edge to it, visiting vertices in topological
+/// order. A vertex with no predecessors takes the value of init, and any other
+/// vertex takes the value of combine over its predecessors' values, in ascending
+/// order of the predecessors.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, FoldTopological};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// // The number of hops from the origin.
+/// let hops = dag.fold_topological(|_| 0, |_, hops: Vec<&usize>| hops[0] + 1);
+/// assert_eq!(hops[&String::from("destination")], 2);
+/// ```
+pub trait FoldTopological<T> {
+    fn fold_topological<V>(
+        &self,
+        init: impl Fn(&T) -> V,
+        combine: impl Fn(&T, Vec<&V>) -> V,
+    ) -> BTreeMap<&T, V>;
+}
+
+/// `FoldReverseTopological` computes a value for each vertex from the values of
+/// the vertices it has an edge to, visiting vertices in reverse topological order.
+/// A vertex with no edges takes the value of init, and any other vertex takes the
+/// value of combine over its adjacent vertices' values, in ascending order of the
+/// adjacent vertices.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, FoldReverseTopological};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// // The number of hops to the destination.
+/// let hops = dag.fold_reverse_topological(|_| 0, |_, hops: Vec<&usize>| hops[0] + 1);
+/// assert_eq!(hops[&String::from("origin")], 2);
+/// ```
+pub trait FoldReverseTopological<T> {
+    fn fold_reverse_topological<V>(
+        &self,
+        init: impl Fn(&T) -> V,
+        combine: impl Fn(&T, Vec<&V>) -> V,
+    ) -> BTreeMap<&T, V>;
+}
+
+/// `BuildIntervalLabels` precomputes `IntervalLabels` for the dag, which answer
+/// repeated ancestor queries without walking the dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, BuildIntervalLabels};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let labels = dag.build_interval_labels();
+/// assert!(labels.is_ancestor(&String::from("origin"), &String::from("destination")).unwrap());
+/// assert!(!labels.is_ancestor(&String::from("destination"), &String::from("origin")).unwrap());
+/// ```
+pub trait BuildIntervalLabels<T>
+where
+    T: Ord,
+{
+    fn build_interval_labels(&self) -> IntervalLabels<'_, T>;
+}
+
+/// `Mount` adds every vertex and edge of the dag sub, with each vertex mapped into
+/// the dag's keyspace by prefix, then adds each of the boundary edges, which may
+/// connect the two. The dag is unchanged if an error is returned: if a mapped
+/// vertex already exists, if a boundary edge refers to a vertex which does not
+/// exist, or if the result contains a cycle.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, Mount, Vertices};
+/// let mut pipeline: BTreeDAG<String> = BTreeDAG::new();
+/// pipeline.add_vertex(String::from("origin"));
+/// let mut fragment: BTreeDAG<&str> = BTreeDAG::new();
+/// fragment.add_vertex("waypoint");
+/// fragment.add_vertex("destination");
+/// fragment.add_edge("waypoint", "destination");
+///
+/// pipeline
+///     .mount(
+///         fragment,
+///         |v| format!("fragment/{}", v),
+///         [(String::from("origin"), String::from("fragment/waypoint"))],
+///     )
+///     .unwrap();
+/// assert_eq!(pipeline.vertices().len(), 3);
+/// assert!(pipeline
+///     .adjacent(String::from("fragment/waypoint"), String::from("fragment/destination"))
+///     .unwrap());
+/// ```
+pub trait Mount<T> {
+    type Error;
+    fn mount<U>(
+        &mut self,
+        sub: BTreeDAG<U>,
+        prefix: impl Fn(U) -> T,
+        boundary: impl IntoIterator<Item = (T, T)>,
+    ) -> Result<(), Self::Error>
+    where
+        U: Ord + Clone;
+}
+
+/// `FindMatches` returns every match of the dag pattern within the dag: a mapping
+/// from each pattern vertex to a distinct vertex accepted by matcher, such that
+/// every edge of the pattern maps to an edge of the dag. The matches are
+/// returned in ascending order. Finding matches is exponential in the size of
+/// the pattern, so patterns should be small.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, FindMatches};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// // A pattern of two edges in a row.
+/// let mut pattern: BTreeDAG<u8> = BTreeDAG::new();
+/// pattern.add_vertex(0);
+/// pattern.add_vertex(1);
+/// pattern.add_vertex(2);
+/// pattern.add_edge(0, 1);
+/// pattern.add_edge(1, 2);
+///
+/// let matches = dag.find_matches(&pattern, |_, _| true);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0][&1], &String::from("waypoint"));
+/// ```
+pub trait FindMatches<T> {
+    fn find_matches<'a, P>(
+        &'a self,
+        pattern: &'a BTreeDAG<P>,
+        matcher: impl Fn(&P, &T) -> bool,
+    ) -> Vec<BTreeMap<&'a P, &'a T>>
+    where
+        P: Ord;
+}
+
+/// `QueryVertices` starts a `Query` of the dag, which selects vertices through a
+/// chain of steps instead of a bespoke traversal.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, QueryVertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let selected = dag
+///     .query()
+///     .from(&String::from("origin"))
+///     .out()
+///     .out()
+///     .filter(|v| v.starts_with("dest"))
+///     .collect();
+/// assert_eq!(selected.into_iter().collect::<Vec<_>>(), [&String::from("destination")]);
+/// ```
+pub trait QueryVertices<T>
+where
+    T: Ord,
+{
+    fn query(&self) -> Query<'_, T>;
+}
+
+/// `MergeConcurrent` merges another replica of the dag into this one: the result
+/// contains every vertex and edge of either, except for the edges dropped to
+/// break cycles, which are returned. Edges are added in descending order of
+/// priority, and then in ascending order, and any edge which would introduce a
+/// cycle is dropped. The result depends only on the union of the two replicas,
+/// so replicas merging each other's changes converge to the same dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, MergeConcurrent};
+/// let mut replica: BTreeDAG<String> = BTreeDAG::new();
+/// replica.add_vertex(String::from("origin"));
+/// replica.add_vertex(String::from("destination"));
+/// let mut other: BTreeDAG<String> = replica.clone();
+/// replica.add_edge(String::from("origin"), String::from("destination"));
+/// other.add_edge(String::from("destination"), String::from("origin"));
+///
+/// // Prefer edges from the origin.
+/// let dropped = replica.merge_concurrent(&other, |x, _| x == "origin");
+/// assert_eq!(
+///     dropped.into_iter().collect::<Vec<_>>(),
+///     [(String::from("destination"), String::from("origin"))]
+/// );
+/// assert!(replica.adjacent(String::from("origin"), String::from("destination")).unwrap());
+/// ```
+pub trait MergeConcurrent<T> {
+    fn merge_concurrent<P>(
+        &mut self,
+        other: &Self,
+        priority: impl Fn(&T, &T) -> P,
+    ) -> BTreeSet<(T, T)>
+    where
+        P: Ord;
+}
+
+/// `CollectUnreachable` removes every vertex which is not reachable from one of
+/// the roots, returning the removed vertices. Roots which do not exist are
+/// ignored. The edges of the removed vertices are removed with them, and no other
+/// edges change, as a reachable vertex has no edge to an unreachable vertex.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CollectUnreachable, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_vertex(String::from("orphan"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let collected = dag.collect_unreachable(&BTreeSet::from([String::from("origin")]));
+/// assert_eq!(collected, BTreeSet::from([String::from("orphan")]));
+/// assert_eq!(dag.vertices().len(), 2);
+/// ```
+pub trait CollectUnreachable<T> {
+    fn collect_unreachable(&mut self, roots: &BTreeSet<T>) -> BTreeSet<T>;
+}
+
+/// `View` borrows the dag as a `DagView` of the vertices satisfying vertex_pred
+/// and the edges satisfying edge_pred, answering read-only queries over the
+/// filtered subset without copying it.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, View};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let view = dag.view(|v| v != "waypoint", |_, _| true);
+/// assert!(!view.is_reachable(&String::from("origin"), &String::from("destination")));
+/// assert_eq!(view.topological_sort().len(), 2);
+/// ```
+pub trait View<T>
+where
+    T: Ord,
+{
+    fn view<'a>(
+        &'a self,
+        vertex_pred: impl Fn(&T) -> bool + 'a,
+        edge_pred: impl Fn(&T, &T) -> bool + 'a,
+    ) -> DagView<'a, T>;
+}
+
+/// `Explain` attaches the vertex or edge an `Error` occurred on, turning it into
+/// a `ContextError`. When a vertex of an edge does not exist, the context names
+/// the missing vertex rather than the edge.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Explain, ErrorContext};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+///
+/// let origin = String::from("origin");
+/// let destination = String::from("destination");
+/// let err = dag
+///     .add_edge(origin.clone(), destination.clone())
+///     .map_err(|e| dag.explain_edge(e, &origin, &destination))
+///     .unwrap_err();
+/// assert_eq!(err.context, ErrorContext::Vertex(destination));
+/// ```
+pub trait Explain<T> {
+    fn explain_vertex(&self, error: Error, x: &T) -> ContextError<T>;
+    fn explain_edge(&self, error: Error, x: &T, y: &T) -> ContextError<T>;
+}
+
+/// `TryMutate` performs the core mutations like `AddEdge`, `RemoveEdge`,
+/// `RemoveVertex` and `Prune`, but returns a `ContextError` naming the vertex
+/// or edge responsible for a failure. The context is attached as by `Explain`.
+///
+/// To migrate, replace `add_edge` with `try_add_edge`, and so on. A
+/// `ContextError` converts into an `Error`, so functions returning `Error` may
+/// keep using `?`, and gain the context once their own error type carries a
+/// `ContextError`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, TryMutate, Error, ErrorContext};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+///
+/// let err = dag
+///     .try_add_edge(String::from("origin"), String::from("destination"))
+///     .unwrap_err();
+/// assert_eq!(err.error, Error::VertexDoesNotExist);
+/// assert_eq!(err.context, ErrorContext::Vertex(String::from("destination")));
+/// ```
+pub trait TryMutate<T> {
+    fn try_add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, ContextError<T>>;
+    fn try_remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, ContextError<T>>;
+    fn try_remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, ContextError<T>>;
+    fn try_prune(&mut self, x: T) -> Result<(), ContextError<T>>;
+}
+
+/// `FindVertices` iterates over the vertices of the dag satisfying a predicate,
+/// in ascending order, and `find_edges` over the edges satisfying a predicate,
+/// in ascending order of (x, y).
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, FindVertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let found: Vec<&String> = dag.find_vertices(|v| v.contains('i')).collect();
+/// assert_eq!(found, [&String::from("destination"), &String::from("origin"), &String::from("waypoint")]);
+/// let found: Vec<(&String, &String)> = dag.find_edges(|_, y| y.starts_with("dest")).collect();
+/// assert_eq!(found, [(&String::from("waypoint"), &String::from("destination"))]);
+/// ```
+pub trait FindVertices<T> {
+    fn find_vertices<'a>(&'a self, pred: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+    fn find_edges<'a>(
+        &'a self,
+        pred: impl Fn(&T, &T) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a;
+}
+
+/// `VertexRange` iterates over the vertices of the dag within a range, and
+/// `connections_range` over the adjacent vertices of x within a range, both in
+/// ascending order. The ranges are answered by the ordered backing store, so
+/// only the vertices within them are visited; for example, every vertex with
+/// a given prefix of a string key.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, VertexRange};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint_A"));
+/// dag.add_vertex(String::from("waypoint_B"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_A"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_B"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let waypoints = String::from("waypoint_")..String::from("waypoint`");
+/// assert_eq!(dag.range(waypoints.clone()).count(), 2);
+/// let adjacent: Vec<&String> = dag
+///     .connections_range(&String::from("origin"), String::from("a")..String::from("e"))
+///     .unwrap()
+///     .collect();
+/// assert_eq!(adjacent, [&String::from("destination")]);
+/// ```
+pub trait VertexRange<T> {
+    fn range<'a, Q, R>(&'a self, range: R) -> impl Iterator<Item = &'a T>
+    where
+        T: Borrow<Q> + 'a,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>;
+    fn connections_range<'a, Q, R>(
+        &'a self,
+        x: &T,
+        range: R,
+    ) -> Option<impl Iterator<Item = &'a T>>
+    where
+        T: Borrow<Q> + 'a,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>;
+}
+
+/// `OrderedVertices` accesses the vertices of the dag by their order, mirroring
+/// `BTreeMap::first_key_value`. A root is a vertex with no incoming edges, and
+/// `pop_first_root` removes the least root, returning it along with its
+/// adjacency list.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, OrderedVertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// assert_eq!(dag.first_vertex(), Some(&String::from("destination")));
+/// assert_eq!(dag.last_vertex(), Some(&String::from("waypoint")));
+/// let (root, adj_root) = dag.pop_first_root().unwrap();
+/// assert_eq!(root, String::from("origin"));
+/// assert!(adj_root.contains("waypoint"));
+/// assert_eq!(dag.first_root(), Some(&String::from("waypoint")));
+/// ```
+pub trait OrderedVertices<T> {
+    fn first_vertex(&self) -> Option<&T>;
+    fn last_vertex(&self) -> Option<&T>;
+    fn first_root(&self) -> Option<&T>;
+    fn pop_first_root(&mut self) -> Option<(T, BTreeSet<T>)>;
+}
+
+/// `SplitOffRange` splits the dag in two at the vertex at, mirroring
+/// `BTreeMap::split_off`: the vertices greater than or equal to at are moved
+/// into the returned dag. Edges crossing the split, in either direction, are
+/// dropped and returned, so the two halves can later be joined back together.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, SplitOffRange, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let (upper, dropped) = dag.split_off_range(&String::from("p"));
+/// assert_eq!(dag.vertices().len(), 2);
+/// assert_eq!(upper.vertices().len(), 1);
+/// assert_eq!(dropped.len(), 2);
+/// ```
+pub trait SplitOffRange<T>
+where
+    T: Ord,
+{
+    fn split_off_range(&mut self, at: &T) -> (BTreeDAG<T>, BTreeSet<(T, T)>);
+}
+
+/// `Append` moves every vertex of other into the dag, leaving other empty,
+/// mirroring `BTreeMap::append`. The vertices of the two dags must be
+/// disjoint, which is validated in constant time when their key ranges do not
+/// overlap. Two disjoint dags have no edges between them, so their union is
+/// acyclic and no edge is cycle checked. If a vertex is in both dags,
+/// `Error::VertexExists` is returned and neither dag is changed.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Append, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("destination"));
+/// let mut shard: BTreeDAG<String> = BTreeDAG::new();
+/// shard.add_vertex(String::from("origin"));
+/// shard.add_vertex(String::from("waypoint"));
+/// shard.add_edge(String::from("origin"), String::from("waypoint"));
+///
+/// dag.append(&mut shard).unwrap();
+/// assert_eq!(dag.vertices().len(), 3);
+/// assert_eq!(shard.vertices().len(), 0);
+/// ```
+pub trait Append<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn append(&mut self, other: &mut BTreeDAG<T>) -> Result<(), Self::Error>;
+}
+
+/// `ConnectAcross` adds a batch of edges, typically between the partitions of
+/// dags joined with `append`. Rather than checking each edge for a cycle in
+/// turn, the whole batch is checked with a single depth first search from the
+/// sources of the new edges. The batch is added transactionally: if a vertex
+/// does not exist, `Error::VertexDoesNotExist` is returned, and if the edges
+/// would form a cycle, `Error::EdgeExists`, with the dag unchanged.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Adjacent, ConnectAcross};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+///
+/// dag.connect_across(vec![
+///     (String::from("origin"), String::from("waypoint")),
+///     (String::from("waypoint"), String::from("destination")),
+/// ])
+/// .unwrap();
+/// assert!(dag.adjacent(String::from("origin"), String::from("waypoint")).unwrap());
+/// ```
+pub trait ConnectAcross<T> {
+    type Error;
+    fn connect_across(
+        &mut self,
+        edges: impl IntoIterator<Item = (T, T)>,
+    ) -> Result<(), Self::Error>;
+}
+
+/// `FromAdjacency` constructs a dag from an adjacency list in the shape of the
+/// dag's own storage, validating that the edges contain no cycle. A vertex
+/// which is referenced by an edge but is not a key of the list is either
+/// rejected with `Error::VertexDoesNotExist`, or created with no edges,
+/// according to `MissingVertices`. `TryFrom<BTreeMap<T, BTreeSet<T>>>` rejects
+/// missing vertices.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, Error, FromAdjacency, MissingVertices, Vertices};
+/// use std::collections::{BTreeMap, BTreeSet};
+/// let mut adjacency: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+/// adjacency.insert(
+///     String::from("origin"),
+///     BTreeSet::from([String::from("destination")]),
+/// );
+///
+/// let err = BTreeDAG::from_adjacency(adjacency.clone(), MissingVertices::Reject).unwrap_err();
+/// assert_eq!(err, Error::VertexDoesNotExist);
+/// let dag = BTreeDAG::from_adjacency(adjacency, MissingVertices::Create).unwrap();
+/// assert_eq!(dag.vertices().len(), 2);
+/// ```
+pub trait FromAdjacency<T>: Sized {
+    type Error;
+    fn from_adjacency(
+        adjacency: BTreeMap<T, BTreeSet<T>>,
+        missing: MissingVertices,
+    ) -> Result<Self, Self::Error>;
+}
+
+/// `ToEdgeList` returns owned snapshots of the edges and vertices of the dag, in
+/// ascending order, for handing to other threads or across FFI boundaries
+/// without borrowing the dag.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, ToEdgeList};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert_eq!(
+///     dag.to_edge_list(),
+///     vec![(String::from("origin"), String::from("destination"))]
+/// );
+/// assert_eq!(dag.to_vertex_list().len(), 2);
+/// ```
+pub trait ToEdgeList<T> {
+    fn to_edge_list(&self) -> Vec<(T, T)>;
+    fn to_vertex_list(&self) -> Vec<T>;
+}
+
+/// `DegreeHistogram` counts the vertices of the dag by their degree: each entry
+/// maps a degree to the number of vertices with that many outgoing, or
+/// incoming, edges. Degrees with no vertices are omitted.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, DegreeHistogram};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let out_degrees = dag.out_degree_histogram();
+/// assert_eq!(out_degrees.get(&0), Some(&2));
+/// assert_eq!(out_degrees.get(&2), Some(&1));
+/// assert_eq!(dag.in_degree_histogram().get(&1), Some(&2));
+/// ```
+pub trait DegreeHistogram<T> {
+    fn out_degree_histogram(&self) -> BTreeMap<usize, usize>;
+    fn in_degree_histogram(&self) -> BTreeMap<usize, usize>;
+}
+
+/// `TopK` returns the k vertices of the dag with the most outgoing edges, or
+/// with the most descendants, along with their counts. Vertices are ordered by
+/// descending count, and then in ascending order. Descendant counts are
+/// memoized in reverse topological order as bitsets, over 4096 vertices at a
+/// time, so counting takes O(V · (V + E) / 64) time for V vertices and E edges,
+/// and at most 512 bytes per vertex, whatever k is.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TopK};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// assert_eq!(dag.top_k_by_out_degree(1), vec![(&String::from("origin"), 1)]);
+/// assert_eq!(dag.top_k_by_descendant_count(1), vec![(&String::from("origin"), 2)]);
+/// ```
+pub trait TopK<T> {
+    fn top_k_by_out_degree(&self, k: usize) -> Vec<(&T, usize)>;
+    fn top_k_by_descendant_count(&self, k: usize) -> Vec<(&T, usize)>;
+}
+
+/// `Impact` returns every vertex transitively affected by a change to any of the
+/// changed vertices: the descendants of each, deduplicated in a single
+/// traversal. A changed vertex is only included if it is a descendant of
+/// another, and changed vertices which do not exist are ignored.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Impact};
+/// use std::collections::BTreeSet;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let changed: BTreeSet<String> = BTreeSet::from([String::from("origin")]);
+/// assert_eq!(dag.impact(&changed).len(), 2);
+/// ```
+pub trait Impact<T> {
+    fn impact(&self, changed: &BTreeSet<T>) -> BTreeSet<&T>;
+}
+
+/// `MinimalCover` chooses a small set of roots, vertices with no incoming edges,
+/// which together reach every target: each target is either one of the roots
+/// or a descendant of one. Finding a minimum cover is NP-hard, so roots are
+/// chosen greedily, each time taking the root which reaches the most targets
+/// not yet covered, and then the least. An error is returned if a target does
+/// not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, MinimalCover};
+/// use std::collections::BTreeSet;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let targets = BTreeSet::from([String::from("waypoint"), String::from("destination")]);
+/// assert_eq!(dag.minimal_cover(&targets).unwrap(), BTreeSet::from([&String::from("origin")]));
+/// ```
+pub trait MinimalCover<T> {
+    type Error;
+    fn minimal_cover(&self, targets: &BTreeSet<T>) -> Result<BTreeSet<&T>, Self::Error>;
+}
+
+/// `SuggestLayout` computes `LayoutHints` for drawing the dag: a layer for each
+/// vertex by longest path layering, and a position within its layer by
+/// barycenter ordering.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, SuggestLayout};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let layout = dag.suggest_layout();
+/// assert_eq!(layout.layers().len(), 3);
+/// assert_eq!(layout.position(&String::from("destination")), Some((2, 0)));
+/// ```
+pub trait SuggestLayout<T>
+where
+    T: Ord,
+{
+    fn suggest_layout(&self) -> LayoutHints<'_, T>;
+}
+
+/// `InternAdjacency` shares a single copy of each distinct adjacency set between
+/// every vertex with that set, returning the number of sets which were
+/// replaced by a shared one. Adjacency sets are copied on write, so a vertex
+/// whose edges later change gets its own copy again; interning only needs to
+/// be repeated after the dag has changed substantially.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, InternAdjacency, MemoryFootprint};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin_A"));
+/// dag.add_vertex(String::from("origin_B"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin_A"), String::from("destination"));
+/// dag.add_edge(String::from("origin_B"), String::from("destination"));
+///
+/// let before: usize = dag.memory_footprint_estimate();
+/// assert_eq!(dag.intern_adjacency(), 1);
+/// assert!(dag.memory_footprint_estimate() < before);
+/// ```
+pub trait InternAdjacency<T> {
+    fn intern_adjacency(&mut self) -> usize;
+}
+
+/// `Navigate` positions a `Cursor` at the vertex x, or returns None if x does
+/// not exist. The cursor steps along outgoing and incoming edges by their index
+/// in ascending order, and can step back along the path it took.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Navigate};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let mut cursor = dag.cursor(&String::from("waypoint")).unwrap();
+/// assert_eq!(cursor.step_out(0), Some(&String::from("destination")));
+/// assert_eq!(cursor.back(), Some(&String::from("waypoint")));
+/// assert_eq!(cursor.step_in(0), Some(&String::from("origin")));
+/// assert_eq!(cursor.path().len(), 2);
+/// ```
+pub trait Navigate<T>
+where
+    T: Ord,
+{
+    fn cursor(&self, x: &T) -> Option<Cursor<'_, T>>;
+}
+
+/// `TraverseWith` traverses the dag visiting vertices in the given
+/// `TraversalOrder` wherever the traversal could visit several vertices next.
+/// `dfs_with` and `bfs_with` return the vertices reachable from x, including
+/// x, in depth first preorder and breadth first order, or None if x does not
+/// exist. `topological_sort_with` returns every vertex in topological order.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, KeyOrder, TopologicalSort, TraverseWith};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint_A"));
+/// dag.add_vertex(String::from("waypoint_B"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_A"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_B"));
+///
+/// assert_eq!(dag.topological_sort_with(KeyOrder), dag.topological_sort());
+/// // Visit the greater waypoint first.
+/// let bfs = dag.bfs_with(&String::from("origin"), |a: &String, b: &String| b.cmp(a));
+/// assert_eq!(bfs.unwrap()[1], &String::from("waypoint_B"));
+/// ```
+pub trait TraverseWith<T> {
+    fn dfs_with(&self, x: &T, order: impl TraversalOrder<T>) -> Option<Vec<&T>>;
+    fn bfs_with(&self, x: &T, order: impl TraversalOrder<T>) -> Option<Vec<&T>>;
+    fn topological_sort_with(&self, order: impl TraversalOrder<T>) -> Vec<&T>;
+}
+
+/// `Repair` fixes the adjacency list of a dag which was constructed without
+/// the checks of its mutations, such as by deserializing it, returning a
+/// report of the changes. Each edge pointing at a vertex which does not exist
+/// is repaired as dangling says, then a set of edges breaking every cycle is
+/// dropped, as suggested by `FeedbackArcSet`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, DanglingEdges, Repair};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// // A dag built through its mutations needs no repair.
+/// assert!(dag.repair(DanglingEdges::Drop).is_empty());
+/// assert!(dag.check_invariants().is_ok());
+/// ```
+pub trait Repair<T>
+where
+    T: Ord,
+{
+    fn repair(&mut self, dangling: DanglingEdges) -> RepairReport<T>;
+}
+
+/// `DistancesFrom` returns every vertex reachable from any of the sources, each
+/// with the length of its shortest path from the nearest source, in a single
+/// breadth first search. The sources have distance 0, and sources which do not
+/// exist are ignored.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, DistancesFrom};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let sources = BTreeSet::from([String::from("origin"), String::from("waypoint")]);
+/// let distances = dag.distances_from(&sources);
+/// assert_eq!(distances[&String::from("waypoint")], 0);
+/// assert_eq!(distances[&String::from("destination")], 1);
+/// ```
+pub trait DistancesFrom<T> {
+    fn distances_from(&self, sources: &BTreeSet<T>) -> BTreeMap<&T, usize>;
+}
+
+/// `KPaths` ranks the paths from the vertex x to the vertex y by their number of
+/// edges, returning up to k of the shortest, or of the longest, paths in
+/// order. Paths of equal length are ordered lexicographically by their
+/// vertices. No paths are returned if y is not reachable from x, and an error
+/// is thrown if either x, or y do not exist.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, KPaths};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let origin = String::from("origin");
+/// let destination = String::from("destination");
+/// let shortest = dag.k_shortest_paths(&origin, &destination, 2).unwrap();
+/// assert_eq!(shortest.len(), 2);
+/// assert_eq!(shortest[0].len(), 2);
+/// let longest = dag.k_longest_paths(&origin, &destination, 1).unwrap();
+/// assert!(longest[0].contains(&String::from("waypoint")));
+/// ```
+pub trait KPaths<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn k_shortest_paths(&self, x: &T, y: &T, k: usize) -> Result<Vec<Path<'_, T>>, Self::Error>;
+    fn k_longest_paths(&self, x: &T, y: &T, k: usize) -> Result<Vec<Path<'_, T>>, Self::Error>;
+}
+
+/// `Roots` returns the vertices with no incoming edges, which are the vertices
+/// every traversal of the whole dag starts from. An empty dag has no roots.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Roots};
+/// use std::collections::BTreeSet;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// assert!(dag.roots().is_empty());
+///
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint")).unwrap();
+///
+/// assert_eq!(
+///     dag.roots(),
+///     BTreeSet::from([&String::from("destination"), &String::from("origin")])
+/// );
+/// ```
+pub trait Roots<T> {
+    fn roots(&self) -> BTreeSet<&T>;
+}
+
+/// `CriticalPath` returns the path whose vertices take the longest in total,
+/// given the duration of each vertex, which bounds how soon every vertex can
+/// finish. Ties are broken towards the least vertices. An empty dag has no
+/// critical path, so `critical_path` throws `Error::EmptyGraph`, while
+/// `try_critical_path` returns `None`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, CriticalPath};
+/// use btree_dag::Error;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// assert_eq!(dag.critical_path(|_| 1).unwrap_err(), Error::EmptyGraph);
+/// assert!(dag.try_critical_path(|_| 1).is_none());
+///
+/// dag.add_vertex(String::from("binary"));
+/// dag.add_vertex(String::from("docs"));
+/// dag.add_vertex(String::from("library"));
+/// dag.add_edge(String::from("binary"), String::from("library")).unwrap();
+///
+/// let path = dag.critical_path(|task: &String| task.len() as u64).unwrap();
+/// assert_eq!(path.as_slice(), [&String::from("binary"), &String::from("library")]);
+/// ```
+pub trait CriticalPath<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn critical_path(&self, durations: impl Fn(&T) -> u64) -> Result<Path<'_, T>, Self::Error>;
+    fn try_critical_path(&self, durations: impl Fn(&T) -> u64) -> Option<Path<'_, T>>;
+}
+
+/// `TryAdjacent` is `Adjacent` for callers which treat a missing vertex as an
+/// expected outcome rather than an error: `None` is returned if x or y does not
+/// exist, and otherwise whether there is an edge (x, y).
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TryAdjacent};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination")).unwrap();
+///
+/// let x = String::from("origin");
+/// let y = String::from("destination");
+/// assert_eq!(dag.try_adjacent(&x, &y), Some(true));
+/// assert_eq!(dag.try_adjacent(&y, &x), Some(false));
+/// assert_eq!(dag.try_adjacent(&x, &String::from("waypoint")), None);
+/// ```
+pub trait TryAdjacent<T> {
+    fn try_adjacent(&self, x: &T, y: &T) -> Option<bool>;
+}
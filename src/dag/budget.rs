@@ -0,0 +1,73 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Error;
+
+/// `CancellationToken` is a shared flag which cancels the operations it is
+/// passed to, once `cancel` is called on any of its clones. A cancelled
+/// operation returns `Error::Cancelled`.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every operation using this token, or one of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// `Budget` bounds the work of a traversal, by a number of operations, a
+/// cancellation token, or both.
+pub(crate) struct Budget<'a> {
+    ops_remaining: Option<usize>,
+    token: Option<&'a CancellationToken>,
+}
+
+impl<'a> Budget<'a> {
+    pub(crate) fn unlimited() -> Self {
+        Budget {
+            ops_remaining: None,
+            token: None,
+        }
+    }
+
+    pub(crate) fn ops(limit: usize) -> Self {
+        Budget {
+            ops_remaining: Some(limit),
+            token: None,
+        }
+    }
+
+    pub(crate) fn token(token: &'a CancellationToken) -> Self {
+        Budget {
+            ops_remaining: None,
+            token: Some(token),
+        }
+    }
+
+    /// Spends one operation, returning `Error::Cancelled` if the budget is
+    /// exhausted or the token has been cancelled.
+    pub(crate) fn spend(&mut self) -> Result<(), Error> {
+        if let Some(ops) = self.ops_remaining.as_mut() {
+            if *ops == 0 {
+                return Err(Error::Cancelled);
+            }
+            *ops -= 1;
+        }
+        if self.token.is_some_and(CancellationToken::is_cancelled) {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+}
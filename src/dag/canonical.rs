@@ -0,0 +1,20 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// `Canonical` is the canonical representation of a `BTreeDAG`: every vertex in
+/// ascending order, followed by every edge in ascending lexicographic order. The
+/// representation depends only on the vertices and edges of the dag, so equal
+/// dags always serialize to identical output, and the ordering is part of the
+/// crate's stable API.
+///
+/// A `Canonical<&T>` borrowing from a dag is obtained through the `ToCanonical`
+/// trait, and an owned `Canonical<T>` is converted back into a dag with
+/// `TryFrom`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Canonical<V> {
+    pub vertices: Vec<V>,
+    pub edges: Vec<(V, V)>,
+}
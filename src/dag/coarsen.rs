@@ -0,0 +1,19 @@
+use alloc::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::BTreeDAG;
+
+/// `Coarsening` is one level of a coarsened dag: the condensed dag, and the
+/// mapping from each vertex of the original dag to the vertex of the
+/// condensed dag which it was merged into.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Coarsening<T>
+where
+    T: Ord,
+{
+    pub dag: BTreeDAG<T>,
+    pub mapping: BTreeMap<T, T>,
+}
@@ -0,0 +1,96 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+
+/// `Cursor` is positioned at a vertex of a `BTreeDAG`, and moves along its
+/// edges in either direction, remembering the path it took. The incoming edges
+/// of every vertex are indexed when the cursor is constructed, so stepping in
+/// either direction does not scan the dag. A cursor is constructed through the
+/// `Navigate` trait.
+pub struct Cursor<'a, T>
+where
+    T: Ord,
+{
+    dag: &'a BTreeDAG<T>,
+    predecessors: BTreeMap<&'a T, Vec<&'a T>>,
+    path: Vec<&'a T>,
+}
+
+impl<'a, T> Cursor<'a, T>
+where
+    T: Ord,
+{
+    /// The vertex x must exist.
+    pub(crate) fn new(dag: &'a BTreeDAG<T>, x: &'a T) -> Self {
+        let mut predecessors: BTreeMap<&'a T, Vec<&'a T>> = BTreeMap::new();
+        // The vertices are visited in ascending order, so each list of
+        // predecessors is sorted.
+        for (w, adj) in dag.vertices.iter() {
+            for v in adj.iter() {
+                predecessors.entry(v).or_default().push(w);
+            }
+        }
+        Cursor {
+            dag,
+            predecessors,
+            path: vec![x],
+        }
+    }
+
+    /// Returns the vertex the cursor is positioned at.
+    pub fn current(&self) -> &'a T {
+        // The path always holds at least the starting vertex.
+        self.path[self.path.len() - 1]
+    }
+
+    /// Returns the vertices the cursor has been positioned at, from the
+    /// starting vertex to the current one.
+    pub fn path(&self) -> &[&'a T] {
+        &self.path
+    }
+
+    /// Returns the vertices the current vertex has an edge to, in ascending
+    /// order.
+    pub fn out_neighbors(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.dag.vertices[self.current()].iter()
+    }
+
+    /// Returns the vertices which have an edge to the current vertex, in
+    /// ascending order.
+    pub fn in_neighbors(&self) -> &[&'a T] {
+        self.predecessors
+            .get(self.current())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Moves the cursor along the edge to the i-th vertex of `out_neighbors`,
+    /// returning it. If there is no such vertex, the cursor does not move and
+    /// None is returned.
+    pub fn step_out(&mut self, i: usize) -> Option<&'a T> {
+        let next: &'a T = self.out_neighbors().nth(i)?;
+        self.path.push(next);
+        Some(next)
+    }
+
+    /// Moves the cursor against the edge from the i-th vertex of
+    /// `in_neighbors`, returning it. If there is no such vertex, the cursor
+    /// does not move and None is returned.
+    pub fn step_in(&mut self, i: usize) -> Option<&'a T> {
+        let next: &'a T = *self.in_neighbors().get(i)?;
+        self.path.push(next);
+        Some(next)
+    }
+
+    /// Moves the cursor back to the vertex it was previously positioned at,
+    /// returning it. At the starting vertex, the cursor does not move and
+    /// None is returned.
+    pub fn back(&mut self) -> Option<&'a T> {
+        if self.path.len() == 1 {
+            return None;
+        }
+        self.path.pop();
+        Some(self.current())
+    }
+}
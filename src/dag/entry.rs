@@ -0,0 +1,69 @@
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `VertexEntry` is a view into a single vertex of a `BTreeDAG`, which may
+/// or may not exist yet. It is constructed through the `Entry` trait and
+/// mirrors `BTreeMap::entry`.
+pub struct VertexEntry<'a, T>
+where
+    T: Ord,
+{
+    dag: &'a mut BTreeDAG<T>,
+    key: T,
+}
+
+impl<'a, T> VertexEntry<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(dag: &'a mut BTreeDAG<T>, key: T) -> Self {
+        VertexEntry { dag, key }
+    }
+
+    /// Returns a reference to the key of this entry.
+    pub fn key(&self) -> &T {
+        &self.key
+    }
+
+    /// Returns the adjacency list of the vertex, if it exists.
+    pub fn get(&self) -> Option<&BTreeSet<T>> {
+        self.dag.vertices.get(&self.key).map(Arc::as_ref)
+    }
+
+    /// Inserts the vertex if it does not exist, and returns its adjacency list.
+    pub fn or_insert(self) -> &'a BTreeSet<T> {
+        self.dag.vertices.entry(self.key).or_default()
+    }
+}
+
+impl<'a, T> VertexEntry<'a, T>
+where
+    T: Ord + Clone,
+{
+    /// Adds an edge from the entry's vertex to the vertex y, inserting the
+    /// entry's vertex first if it does not exist. The vertex y must exist,
+    /// and the edge must not introduce a cycle.
+    pub fn add_edge_to(&mut self, y: T) -> Result<&BTreeSet<T>, Error> {
+        if !self.dag.vertices.contains_key(&self.key) {
+            // A vertex which does not exist yet cannot be reachable from y,
+            // so only y's existence needs to be verified.
+            if !self.dag.vertices.contains_key(&y) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            self.dag
+                .vertices
+                .insert(self.key.clone(), Arc::new(BTreeSet::new()));
+        } else {
+            self.dag.cyclic_relationship_exists(&self.key, &y)?;
+        }
+        // We have already verified the key is in vertices, so we can
+        // safely unwrap.
+        Arc::make_mut(self.dag.vertices.get_mut(&self.key).unwrap()).insert(y);
+        #[cfg(feature = "debug-invariants")]
+        self.dag.assert_invariants();
+        Ok(&self.dag.vertices[&self.key])
+    }
+}
@@ -0,0 +1,257 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::dag::{BTreeDAG, Canonical, ToCanonical};
+use crate::Error;
+
+/// The version of the serialization schema written by `Envelope`.
+pub const ENVELOPE_VERSION: u32 = 2;
+
+/// `Envelope` is the versioned serialization schema of a `BTreeDAG`: the schema
+/// version, every vertex in ascending order, and every edge in ascending
+/// order. Persisting an envelope rather than the dag itself decouples the
+/// stored data from the dag's internal representation.
+///
+/// An envelope deserializes from any supported version. Data written by the
+/// derived `Serialize` of `BTreeDAG`, which has no version and maps each
+/// vertex to its adjacency list, is version 1, and is migrated as it is read.
+/// Data of a newer version than `ENVELOPE_VERSION` is rejected. Either way,
+/// the deserialized envelope is of the current version.
+///
+/// Version 2 is read field by field in order, so it round-trips through
+/// formats which are not self-describing, such as bincode or postcard. Telling
+/// version 1 apart requires inspecting the data, so migrating it requires a
+/// self-describing format, such as JSON or CBOR.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, ToEnvelope, Envelope};
+/// use std::convert::TryFrom;
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// let envelope: Envelope<String> = dag.to_envelope();
+/// assert_eq!(envelope.version, 2);
+/// assert_eq!(BTreeDAG::try_from(envelope).unwrap(), dag);
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub vertices: Vec<T>,
+    pub edges: Vec<(T, T)>,
+}
+
+/// `ToEnvelope` copies the dag into an `Envelope` of the current version.
+pub trait ToEnvelope<T> {
+    fn to_envelope(&self) -> Envelope<T>;
+}
+
+impl<T> ToEnvelope<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn to_envelope(&self) -> Envelope<T> {
+        let canonical: Canonical<&T> = self.to_canonical();
+        Envelope {
+            version: ENVELOPE_VERSION,
+            vertices: canonical.vertices.into_iter().cloned().collect(),
+            edges: canonical
+                .edges
+                .into_iter()
+                .map(|(x, y)| (x.clone(), y.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Every vertex referenced by an edge must be listed in the vertices, and the
+/// edges must not contain a cycle.
+impl<T> TryFrom<Envelope<T>> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn try_from(envelope: Envelope<T>) -> Result<Self, Self::Error> {
+        BTreeDAG::try_from(Canonical {
+            vertices: envelope.vertices,
+            edges: envelope.edges,
+        })
+    }
+}
+
+/// The vertices of a serialized dag: a list in version 2, or a map from each
+/// vertex to its adjacency list in version 1.
+enum Vertices<T> {
+    List(Vec<T>),
+    Adjacency(BTreeMap<T, BTreeSet<T>>),
+}
+
+/// Deserializes the vertices of data whose version is unknown when they are
+/// read, which requires a self-describing format.
+struct VerticesSeed<T>(PhantomData<T>);
+
+impl<'de, T> DeserializeSeed<'de> for VerticesSeed<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    type Value = Vertices<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T> Visitor<'de> for VerticesSeed<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    type Value = Vertices<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of vertices, or a map of adjacency lists")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vertices: Vec<T> = Vec::new();
+        while let Some(v) = seq.next_element()? {
+            vertices.push(v);
+        }
+        Ok(Vertices::List(vertices))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut adjacency: BTreeMap<T, BTreeSet<T>> = BTreeMap::new();
+        while let Some((x, adj_x)) = map.next_entry()? {
+            adjacency.insert(x, adj_x);
+        }
+        Ok(Vertices::Adjacency(adjacency))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Version,
+    Vertices,
+    Edges,
+    #[serde(other)]
+    Other,
+}
+
+struct EnvelopeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for EnvelopeVisitor<T>
+where
+    T: Ord + Clone + Deserialize<'de>,
+{
+    type Value = Envelope<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a serialized BTreeDAG")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let version: u32 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if version != ENVELOPE_VERSION {
+            return Err(de::Error::custom("unsupported serialization version"));
+        }
+        let vertices: Vec<T> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let edges: Vec<(T, T)> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(Envelope {
+            version,
+            vertices,
+            edges,
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut version: Option<u32> = None;
+        let mut vertices: Option<Vertices<T>> = None;
+        let mut edges: Option<Vec<(T, T)>> = None;
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Version => version = Some(map.next_value()?),
+                // The version precedes the vertices in data written by an
+                // envelope, which are then read as a list.
+                Field::Vertices if version == Some(ENVELOPE_VERSION) => {
+                    vertices = Some(Vertices::List(map.next_value()?))
+                }
+                Field::Vertices => vertices = Some(map.next_value_seed(VerticesSeed(PhantomData))?),
+                Field::Edges => edges = Some(map.next_value()?),
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let version: u32 = version.unwrap_or(1);
+        if version > ENVELOPE_VERSION {
+            return Err(de::Error::custom("unsupported serialization version"));
+        }
+        match vertices.ok_or_else(|| de::Error::missing_field("vertices"))? {
+            Vertices::List(vertices) if version == ENVELOPE_VERSION => Ok(Envelope {
+                version,
+                vertices,
+                edges: edges.ok_or_else(|| de::Error::missing_field("edges"))?,
+            }),
+            Vertices::Adjacency(adjacency) if version == 1 => {
+                let edges: Vec<(T, T)> = adjacency
+                    .iter()
+                    .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x.clone(), y.clone())))
+                    .collect();
+                Ok(Envelope {
+                    version: ENVELOPE_VERSION,
+                    vertices: adjacency.into_keys().collect(),
+                    edges,
+                })
+            }
+            _ => Err(de::Error::custom(
+                "vertices do not match the serialization version",
+            )),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Envelope<T>
+where
+    T: Ord + Clone + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "Envelope",
+            &["version", "vertices", "edges"],
+            EnvelopeVisitor(PhantomData),
+        )
+    }
+}
@@ -0,0 +1,124 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `IntervalLabels` is a precomputed interval labeling of a `BTreeDAG`, which
+/// answers ancestor queries by searching the intervals of a single label
+/// instead of walking the dag. The labels borrow the dag, so they can not
+/// outlive a mutation; they must be rebuilt after the dag changes.
+///
+/// Every vertex is numbered in post order of a depth first search from each
+/// root in ascending order, and labelled with the intervals of the numbers of
+/// all vertices reachable from it. Vertices in a tree need a single interval,
+/// so the queries of a tree-like dag take near constant time.
+#[derive(Clone, Debug)]
+pub struct IntervalLabels<'a, T>
+where
+    T: Ord,
+{
+    ids: BTreeMap<&'a T, usize>,
+    post: Vec<usize>,
+    // The disjoint intervals reachable from each vertex, sorted by start.
+    intervals: Vec<Vec<(usize, usize)>>,
+}
+
+impl<'a, T> IntervalLabels<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(dag: &'a BTreeDAG<T>) -> Self {
+        let ids: BTreeMap<&T, usize> = dag
+            .vertices
+            .keys()
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        let successors: Vec<Vec<usize>> = dag
+            .vertices
+            .values()
+            .map(|adj| adj.iter().map(|y| ids[y]).collect())
+            .collect();
+        let mut in_degrees: Vec<usize> = vec![0; successors.len()];
+        for y in successors.iter().flatten() {
+            in_degrees[*y] += 1;
+        }
+
+        // Number the vertices in post order, where the subtree of each vertex
+        // in the search forest is numbered from its low number to its own.
+        let mut low: Vec<usize> = vec![0; successors.len()];
+        let mut post: Vec<usize> = vec![0; successors.len()];
+        let mut order: Vec<usize> = Vec::with_capacity(successors.len());
+        let mut visited: Vec<bool> = vec![false; successors.len()];
+        for root in (0..successors.len()).filter(|v| in_degrees[*v] == 0) {
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            visited[root] = true;
+            low[root] = order.len();
+            while let Some((v, i)) = stack.pop() {
+                match successors[v].get(i) {
+                    Some(w) => {
+                        stack.push((v, i + 1));
+                        if !visited[*w] {
+                            visited[*w] = true;
+                            low[*w] = order.len();
+                            stack.push((*w, 0));
+                        }
+                    }
+                    None => {
+                        post[v] = order.len();
+                        order.push(v);
+                    }
+                }
+            }
+        }
+
+        // Every vertex is numbered after all the vertices reachable from it,
+        // so visiting in post order merges the intervals of successors first.
+        let mut intervals: Vec<Vec<(usize, usize)>> = vec![Vec::new(); successors.len()];
+        for v in order.into_iter() {
+            let mut label: Vec<(usize, usize)> = vec![(low[v], post[v])];
+            for w in successors[v].iter() {
+                label.extend(intervals[*w].iter().copied());
+            }
+            intervals[v] = merge(label);
+        }
+        IntervalLabels {
+            ids,
+            post,
+            intervals,
+        }
+    }
+
+    /// Returns true if there is a path from x to y of at least one edge.
+    pub fn is_ancestor(&self, x: &T, y: &T) -> Result<bool, Error> {
+        let x = *self.ids.get(x).ok_or(Error::VertexDoesNotExist)?;
+        let y = *self.ids.get(y).ok_or(Error::VertexDoesNotExist)?;
+        let post = self.post[y];
+        let label = &self.intervals[x];
+        // The intervals are disjoint, so only the last one starting at or
+        // before the number can contain it.
+        let i = label.partition_point(|(start, _)| *start <= post);
+        Ok(x != y && i > 0 && label[i - 1].1 >= post)
+    }
+
+    /// Returns the total number of intervals in all labels, which bounds the
+    /// memory used by the labels.
+    pub fn label_len(&self) -> usize {
+        self.intervals.iter().map(Vec::len).sum()
+    }
+}
+
+/// Sorts the intervals and merges any which overlap or are adjacent.
+fn merge(mut intervals: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    intervals.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals.into_iter() {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
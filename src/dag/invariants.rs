@@ -0,0 +1,131 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "debug-invariants")]
+use alloc::format;
+#[cfg(feature = "debug-invariants")]
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "fmt")]
+use core::fmt::{self, Debug, Display, Formatter};
+
+use crate::dag::BTreeDAG;
+
+/// `InvariantViolation` describes how the adjacency list of a `BTreeDAG` fails
+/// to be a directed acyclic graph, as found by `check_invariants`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum InvariantViolation<'a, T> {
+    /// The edge (x, y) points at a vertex y which does not exist.
+    DanglingEdge(&'a T, &'a T),
+    /// The vertices form a cycle, in the order of its edges, starting from
+    /// the least vertex of the cycle.
+    Cycle(Vec<&'a T>),
+}
+
+#[cfg(feature = "fmt")]
+impl<'a, T> Display for InvariantViolation<'a, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::DanglingEdge(x, y) => write!(
+                f,
+                "BTreeDAG invariant violated: edge ({:?}, {:?}) points at a vertex which does not exist",
+                x, y
+            ),
+            InvariantViolation::Cycle(cycle) => {
+                write!(f, "BTreeDAG invariant violated: cycle {:?}", cycle)
+            }
+        }
+    }
+}
+
+impl<T> BTreeDAG<T>
+where
+    T: Ord,
+{
+    /// Checks that every edge of the dag points at a vertex of the dag, and
+    /// that the edges form no cycle, returning the first violation found.
+    /// The public mutations of the dag maintain these invariants, so a
+    /// violation indicates a bug in code with access to the adjacency list.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation<'_, T>> {
+        for (x, adj_x) in self.vertices.iter() {
+            if let Some(y) = adj_x.iter().find(|y| !self.vertices.contains_key(*y)) {
+                return Err(InvariantViolation::DanglingEdge(x, y));
+            }
+        }
+        // Repeatedly remove the vertices with no incoming edges (Kahn's
+        // algorithm). The vertices which remain are on or below a cycle.
+        let mut in_degrees: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        let mut sources: Vec<&T> = in_degrees
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(v, _)| *v)
+            .collect();
+        while let Some(v) = sources.pop() {
+            in_degrees.remove(v);
+            for adj in self.vertices[v].iter() {
+                let d = in_degrees.get_mut(adj).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    sources.push(adj);
+                }
+            }
+        }
+        let remaining: BTreeSet<&T> = in_degrees.keys().copied().collect();
+        let start: &T = match remaining.iter().next() {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        // Each remaining vertex has a remaining predecessor, so walking back
+        // through predecessors eventually repeats a vertex, closing a cycle.
+        let mut predecessors: BTreeMap<&T, &T> = BTreeMap::new();
+        for x in remaining.iter() {
+            for y in self.vertices[*x].iter().filter(|y| remaining.contains(y)) {
+                predecessors.entry(y).or_insert(x);
+            }
+        }
+        let mut walk: Vec<&T> = vec![start];
+        let mut v: &T = start;
+        let repeated = loop {
+            v = predecessors[v];
+            if let Some(i) = walk.iter().position(|w| *w == v) {
+                break i;
+            }
+            walk.push(v);
+        };
+        let mut cycle: Vec<&T> = walk.split_off(repeated);
+        // The walk followed edges backwards.
+        cycle.reverse();
+        let least: usize = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+        cycle.rotate_left(least);
+        Err(InvariantViolation::Cycle(cycle))
+    }
+
+    /// Panics with a report of the first violated invariant, if any. The
+    /// vertices may not implement `Debug`, so they are reported by their
+    /// index in ascending order.
+    #[cfg(feature = "debug-invariants")]
+    pub(crate) fn assert_invariants(&self) {
+        let index = |x: &T| -> usize { self.vertices.keys().position(|v| v == x).unwrap() };
+        let report: String = match self.check_invariants() {
+            Ok(()) => return,
+            Err(InvariantViolation::DanglingEdge(x, y)) => format!(
+                "edge {} of vertex {} points at a vertex which does not exist",
+                self.vertices[x].iter().position(|v| v == y).unwrap(),
+                index(x)
+            ),
+            Err(InvariantViolation::Cycle(cycle)) => format!(
+                "vertices {:?} form a cycle",
+                cycle.into_iter().map(index).collect::<Vec<usize>>()
+            ),
+        };
+        panic!(
+            "BTreeDAG invariant violated: {} (vertices are indexed from 0 in ascending order)",
+            report
+        );
+    }
+}
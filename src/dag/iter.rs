@@ -0,0 +1,129 @@
+use alloc::collections::{btree_map, btree_set, BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// `SortedVertices` iterates over the vertices of a `BTreeDAG` and their adjacency
+/// lists, in ascending order of the vertices. It is constructed through the
+/// `IterSorted` trait.
+pub struct SortedVertices<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) vertices: btree_map::Iter<'a, T, Arc<BTreeSet<T>>>,
+}
+
+impl<'a, T> Iterator for SortedVertices<'a, T>
+where
+    T: Ord,
+{
+    type Item = (&'a T, &'a BTreeSet<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.vertices.next().map(|(v, adj)| (v, adj.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.vertices.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SortedVertices<'a, T>
+where
+    T: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.vertices.next_back().map(|(v, adj)| (v, adj.as_ref()))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SortedVertices<'a, T> where T: Ord {}
+
+/// `SortedEdges` iterates over the edges (x, y) of a `BTreeDAG` in ascending
+/// lexicographic order of (x, y). It is constructed through the `EdgesSorted`
+/// trait.
+pub struct SortedEdges<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) vertices: btree_map::Iter<'a, T, Arc<BTreeSet<T>>>,
+    pub(crate) current: Option<(&'a T, btree_set::Iter<'a, T>)>,
+}
+
+impl<'a, T> Iterator for SortedEdges<'a, T>
+where
+    T: Ord,
+{
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((x, adj_x)) = self.current.as_mut() {
+                if let Some(y) = adj_x.next() {
+                    return Some((x, y));
+                }
+            }
+            let (x, adj_x) = self.vertices.next()?;
+            self.current = Some((x, adj_x.iter()));
+        }
+    }
+}
+
+/// `TraverseAll` iterates over every vertex of a `BTreeDAG` exactly once, in depth
+/// first preorder from each root in ascending order, and visiting the adjacent
+/// vertices of each vertex in ascending order. A vertex reachable from several
+/// roots is only visited from the first. It is constructed through the
+/// `TraverseAllVertices` trait.
+pub struct TraverseAll<'a, T>
+where
+    T: Ord,
+{
+    vertices: &'a BTreeMap<T, Arc<BTreeSet<T>>>,
+    roots: vec::IntoIter<&'a T>,
+    stack: Vec<&'a T>,
+    visited: BTreeSet<&'a T>,
+}
+
+impl<'a, T> TraverseAll<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(vertices: &'a BTreeMap<T, Arc<BTreeSet<T>>>) -> Self {
+        let targets: BTreeSet<&T> = vertices.values().flat_map(|adj| adj.iter()).collect();
+        let roots: Vec<&T> = vertices.keys().filter(|v| !targets.contains(v)).collect();
+        TraverseAll {
+            vertices,
+            roots: roots.into_iter(),
+            stack: Vec::new(),
+            visited: BTreeSet::new(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for TraverseAll<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let v = match self.stack.pop() {
+                Some(v) => v,
+                None => self.roots.next()?,
+            };
+            if self.visited.insert(v) {
+                // Push in descending order, so the least adjacent vertex
+                // is visited first.
+                let visited = &self.visited;
+                self.stack.extend(
+                    self.vertices[v]
+                        .iter()
+                        .rev()
+                        .filter(|adj| !visited.contains(adj)),
+                );
+                return Some(v);
+            }
+        }
+    }
+}
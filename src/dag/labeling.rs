@@ -0,0 +1,199 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::Canonical;
+
+/// Computes the canonical form of the dag with the given adjacency lists, by
+/// vertex index, as the least edge list over all orderings the search
+/// explores. The search individualizes vertices one at a time, refining the
+/// coloring of the vertices after each step, and skips branches which are
+/// known to be equivalent under an automorphism of the dag.
+pub(crate) fn canonical_form(successors: Vec<Vec<usize>>) -> Canonical<usize> {
+    let n = successors.len();
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (x, adj_x) in successors.iter().enumerate() {
+        for y in adj_x.iter() {
+            predecessors[*y].push(x);
+        }
+    }
+    let mut search = Search {
+        successors: &successors,
+        predecessors: &predecessors,
+        first: None,
+        first_path: Vec::new(),
+        best: None,
+        automorphisms: Vec::new(),
+    };
+    let mut colors: Vec<usize> = vec![0; n];
+    search.refine(&mut colors);
+    search.search(colors, &mut Vec::new());
+    let edges: Vec<(usize, usize)> = search.best.map(|(_, edges)| edges).unwrap_or_default();
+    Canonical {
+        vertices: (0..n).collect(),
+        edges,
+    }
+}
+
+/// A leaf of the search: a discrete coloring, and the edge list it induces.
+type Leaf = (Vec<usize>, Vec<(usize, usize)>);
+
+struct Search<'a> {
+    successors: &'a [Vec<usize>],
+    predecessors: &'a [Vec<usize>],
+    first: Option<Leaf>,
+    first_path: Vec<usize>,
+    best: Option<Leaf>,
+    automorphisms: Vec<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    /// Refines the coloring until it is equitable: vertices of a color have
+    /// the same colors of successors and predecessors. Colors are ranked by
+    /// the previous color first, so the order of the cells is preserved.
+    fn refine(&self, colors: &mut [usize]) {
+        let mut count = colors.iter().max().map_or(0, |c| c + 1);
+        loop {
+            let keys: Vec<(usize, Vec<usize>, Vec<usize>)> = (0..colors.len())
+                .map(|v| {
+                    let mut succ: Vec<usize> =
+                        self.successors[v].iter().map(|w| colors[*w]).collect();
+                    let mut pred: Vec<usize> =
+                        self.predecessors[v].iter().map(|w| colors[*w]).collect();
+                    succ.sort_unstable();
+                    pred.sort_unstable();
+                    (colors[v], succ, pred)
+                })
+                .collect();
+            let mut ranked: Vec<&(usize, Vec<usize>, Vec<usize>)> = keys.iter().collect();
+            ranked.sort();
+            ranked.dedup();
+            for (v, key) in keys.iter().enumerate() {
+                // Every key is ranked, so we can safely unwrap.
+                colors[v] = ranked.binary_search(&key).unwrap();
+            }
+            if ranked.len() == count {
+                return;
+            }
+            count = ranked.len();
+        }
+    }
+
+    /// Explores the orderings below the coloring, returning the depth to
+    /// backtrack to if this branch was found equivalent to the first.
+    fn search(&mut self, colors: Vec<usize>, prefix: &mut Vec<usize>) -> Option<usize> {
+        let n = colors.len();
+        let mut cell_sizes: Vec<usize> = vec![0; n];
+        for c in colors.iter() {
+            cell_sizes[*c] += 1;
+        }
+        let cell = match (0..n).find(|c| cell_sizes[*c] > 1) {
+            Some(cell) => cell,
+            None => return self.leaf(colors, prefix),
+        };
+        let mut explored: Vec<usize> = Vec::new();
+        for v in (0..n).filter(|v| colors[*v] == cell) {
+            if self.equivalent_to_explored(v, &explored, prefix) {
+                continue;
+            }
+            explored.push(v);
+            let mut child = colors.clone();
+            individualize(&mut child, v);
+            self.refine(&mut child);
+            prefix.push(v);
+            let backtrack = self.search(child, prefix);
+            prefix.pop();
+            if let Some(depth) = backtrack {
+                if depth < prefix.len() {
+                    return Some(depth);
+                }
+            }
+        }
+        None
+    }
+
+    fn leaf(&mut self, colors: Vec<usize>, prefix: &[usize]) -> Option<usize> {
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for (x, adj_x) in self.successors.iter().enumerate() {
+            edges.extend(adj_x.iter().map(|y| (colors[x], colors[*y])));
+        }
+        edges.sort_unstable();
+
+        let (first_colors, first_edges) = match self.first.as_ref() {
+            Some(first) => first,
+            None => {
+                self.first = Some((colors.clone(), edges.clone()));
+                self.first_path = prefix.to_vec();
+                self.best = Some((colors, edges));
+                return None;
+            }
+        };
+        if edges == *first_edges {
+            // The whole branch from where this path left the first path is
+            // equivalent to the first path's branch.
+            self.automorphisms.push(automorphism(&colors, first_colors));
+            let depth = prefix
+                .iter()
+                .zip(self.first_path.iter())
+                .position(|(v, w)| v != w)
+                .unwrap_or(prefix.len());
+            return Some(depth);
+        }
+        // The best leaf is always set with the first.
+        let (best_colors, best_edges) = self.best.as_ref().unwrap();
+        if edges == *best_edges {
+            self.automorphisms.push(automorphism(&colors, best_colors));
+        } else if edges < *best_edges {
+            self.best = Some((colors, edges));
+        }
+        None
+    }
+
+    /// Returns true if v is in the same orbit as an explored vertex, under
+    /// the automorphisms found so far which fix every vertex of the prefix.
+    fn equivalent_to_explored(&self, v: usize, explored: &[usize], prefix: &[usize]) -> bool {
+        if explored.is_empty() {
+            return false;
+        }
+        let n = self.successors.len();
+        let mut parents: Vec<usize> = (0..n).collect();
+        for gamma in self.automorphisms.iter() {
+            if prefix.iter().all(|s| gamma[*s] == *s) {
+                for (u, w) in gamma.iter().enumerate() {
+                    let (ru, rw) = (root(&mut parents, u), root(&mut parents, *w));
+                    parents[ru] = rw;
+                }
+            }
+        }
+        let rv = root(&mut parents, v);
+        explored.iter().any(|e| root(&mut parents, *e) == rv)
+    }
+}
+
+/// Gives the vertex v a color of its own, ranked before the rest of its cell.
+fn individualize(colors: &mut [usize], v: usize) {
+    let c = colors[v];
+    for (u, color) in colors.iter_mut().enumerate() {
+        if *color > c || (*color == c && u != v) {
+            *color += 1;
+        }
+    }
+}
+
+/// Returns the automorphism mapping each vertex to the vertex of the other
+/// leaf at the same position.
+fn automorphism(colors: &[usize], other: &[usize]) -> Vec<usize> {
+    let mut at_position: Vec<usize> = vec![0; other.len()];
+    for (w, c) in other.iter().enumerate() {
+        at_position[*c] = w;
+    }
+    colors.iter().map(|c| at_position[*c]).collect()
+}
+
+/// Returns the root of u in the union-find forest, halving paths on the way.
+fn root(parents: &mut [usize], mut u: usize) -> usize {
+    while parents[u] != u {
+        parents[u] = parents[parents[u]];
+        u = parents[u];
+    }
+    u
+}
@@ -0,0 +1,110 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::{index_adjacency, BTreeDAG, TopologicalSort};
+
+// The number of alternating downward and upward barycenter sweeps.
+const SWEEPS: usize = 4;
+
+/// `LayoutHints` suggests coordinates for drawing a `BTreeDAG` in layers, as
+/// constructed through the `SuggestLayout` trait. Every edge (x, y) points
+/// from a lower layer to a higher one: each root is in layer 0, and every
+/// other vertex one layer past the furthest vertex with an edge to it (longest
+/// path layering). Within each layer, vertices are ordered to reduce edge
+/// crossings by repeatedly sorting them by the mean position of their
+/// neighbours (barycenter ordering), sweeping down and then up the layers.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LayoutHints<'a, T>
+where
+    T: Ord,
+{
+    layers: Vec<Vec<&'a T>>,
+    positions: BTreeMap<&'a T, (usize, usize)>,
+}
+
+impl<'a, T> LayoutHints<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(dag: &'a BTreeDAG<T>) -> Self {
+        let vertices: Vec<&T> = dag.vertices.keys().collect();
+        let successors: Vec<Vec<usize>> = index_adjacency(dag);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        for (x, adj_x) in successors.iter().enumerate() {
+            for &y in adj_x.iter() {
+                predecessors[y].push(x);
+            }
+        }
+        let mut layer_of: Vec<usize> = vec![0; vertices.len()];
+        for v in dag.topological_sort() {
+            // Every sorted vertex is a vertex, so we can safely unwrap.
+            let x: usize = vertices.binary_search(&v).unwrap();
+            for &y in successors[x].iter() {
+                layer_of[y] = layer_of[y].max(layer_of[x] + 1);
+            }
+        }
+        let layer_count: usize = layer_of.iter().max().map_or(0, |l| l + 1);
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+        for (x, &l) in layer_of.iter().enumerate() {
+            layers[l].push(x);
+        }
+
+        let mut position: Vec<usize> = vec![0; vertices.len()];
+        for layer in layers.iter() {
+            for (p, &x) in layer.iter().enumerate() {
+                position[x] = p;
+            }
+        }
+        for sweep in 0..SWEEPS {
+            let (neighbours, order): (&Vec<Vec<usize>>, Vec<usize>) = if sweep % 2 == 0 {
+                (&predecessors, (1..layer_count).collect())
+            } else {
+                (
+                    &successors,
+                    (0..layer_count.saturating_sub(1)).rev().collect(),
+                )
+            };
+            for l in order {
+                // Vertices with no neighbours keep their current position.
+                let barycenter = |x: usize| -> (usize, usize) {
+                    let adj = &neighbours[x];
+                    if adj.is_empty() {
+                        return (position[x], 1);
+                    }
+                    (adj.iter().map(|&y| position[y]).sum(), adj.len())
+                };
+                let mut keyed: Vec<((usize, usize), usize)> =
+                    layers[l].iter().map(|&x| (barycenter(x), x)).collect();
+                // Compare the means a / b and c / d exactly, as a * d and c * b.
+                keyed.sort_by(|((a, b), x), ((c, d), y)| (a * d).cmp(&(c * b)).then(x.cmp(y)));
+                layers[l] = keyed.into_iter().map(|(_, x)| x).collect();
+                for (p, &x) in layers[l].iter().enumerate() {
+                    position[x] = p;
+                }
+            }
+        }
+
+        let layers: Vec<Vec<&T>> = layers
+            .into_iter()
+            .map(|layer| layer.into_iter().map(|x| vertices[x]).collect())
+            .collect();
+        let positions: BTreeMap<&T, (usize, usize)> = layers
+            .iter()
+            .enumerate()
+            .flat_map(|(l, layer)| layer.iter().enumerate().map(move |(p, &v)| (v, (l, p))))
+            .collect();
+        LayoutHints { layers, positions }
+    }
+
+    /// Returns the vertices of each layer, in their suggested order.
+    pub fn layers(&self) -> &[Vec<&'a T>] {
+        &self.layers
+    }
+
+    /// Returns the suggested (layer, position within the layer) of the vertex
+    /// x, or None if x is not a vertex.
+    pub fn position(&self, x: &T) -> Option<(usize, usize)> {
+        self.positions.get(x).copied()
+    }
+}
@@ -0,0 +1,152 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Finds every embedding of the pattern dag into the target dag, both given by
+/// their adjacency lists by vertex index, in ascending order. An embedding maps
+/// each pattern vertex to a distinct target vertex accepted by matches, such
+/// that every pattern edge maps to a target edge. Each embedding is returned as
+/// the target vertex of each pattern vertex, and the embeddings are returned in
+/// lexicographic order.
+pub(crate) fn find_matches(
+    pattern: &[Vec<usize>],
+    target: &[Vec<usize>],
+    matches: impl Fn(usize, usize) -> bool,
+) -> Vec<Vec<usize>> {
+    let search = Search {
+        pattern: Graph::new(pattern),
+        target: Graph::new(target),
+        order: order(pattern),
+        matches,
+    };
+    let mut found: Vec<Vec<usize>> = Vec::new();
+    let mut mapping: Vec<Option<usize>> = vec![None; pattern.len()];
+    let mut used: Vec<bool> = vec![false; target.len()];
+    search.extend(0, &mut mapping, &mut used, &mut found);
+    found.sort_unstable();
+    found
+}
+
+/// The adjacency lists of a dag in both directions, each sorted ascending.
+struct Graph<'a> {
+    successors: &'a [Vec<usize>],
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(successors: &'a [Vec<usize>]) -> Self {
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); successors.len()];
+        for (x, adj_x) in successors.iter().enumerate() {
+            for y in adj_x.iter() {
+                predecessors[*y].push(x);
+            }
+        }
+        Graph {
+            successors,
+            predecessors,
+        }
+    }
+
+    fn has_edge(&self, x: usize, y: usize) -> bool {
+        self.successors[x].binary_search(&y).is_ok()
+    }
+}
+
+/// Orders the pattern vertices so that each, after the first of its component,
+/// is adjacent to an earlier vertex wherever possible, which constrains its
+/// candidates to the neighbours of an already mapped vertex.
+fn order(pattern: &[Vec<usize>]) -> Vec<usize> {
+    let graph = Graph::new(pattern);
+    let degree = |v: usize| graph.successors[v].len() + graph.predecessors[v].len();
+    let mut order: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut ordered: Vec<bool> = vec![false; pattern.len()];
+    let mut links: Vec<usize> = vec![0; pattern.len()];
+    while order.len() < pattern.len() {
+        // Pick the vertex with the most links to ordered vertices, breaking
+        // ties by degree and then by index.
+        let next = (0..pattern.len())
+            .filter(|v| !ordered[*v])
+            .max_by_key(|v| (links[*v], degree(*v), core::cmp::Reverse(*v)))
+            .unwrap();
+        ordered[next] = true;
+        order.push(next);
+        for w in graph.successors[next]
+            .iter()
+            .chain(graph.predecessors[next].iter())
+        {
+            links[*w] += 1;
+        }
+    }
+    order
+}
+
+struct Search<'a, F> {
+    pattern: Graph<'a>,
+    target: Graph<'a>,
+    order: Vec<usize>,
+    matches: F,
+}
+
+impl<'a, F> Search<'a, F>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    fn extend(
+        &self,
+        depth: usize,
+        mapping: &mut Vec<Option<usize>>,
+        used: &mut Vec<bool>,
+        found: &mut Vec<Vec<usize>>,
+    ) {
+        let p = match self.order.get(depth) {
+            Some(p) => *p,
+            None => {
+                found.push(mapping.iter().map(|t| t.unwrap()).collect());
+                return;
+            }
+        };
+        // Candidates are restricted to the neighbours of the image of an
+        // already mapped neighbour, if there is one.
+        let all: Vec<usize>;
+        let candidates: &[usize] = match self.pattern.predecessors[p]
+            .iter()
+            .find_map(|q| mapping[*q].map(|t| &self.target.successors[t]))
+            .or_else(|| {
+                self.pattern.successors[p]
+                    .iter()
+                    .find_map(|q| mapping[*q].map(|t| &self.target.predecessors[t]))
+            }) {
+            Some(candidates) => candidates,
+            None => {
+                all = (0..self.target.successors.len()).collect();
+                &all
+            }
+        };
+        for t in candidates.iter().copied() {
+            if used[t] || !self.feasible(p, t, mapping) {
+                continue;
+            }
+            mapping[p] = Some(t);
+            used[t] = true;
+            self.extend(depth + 1, mapping, used, found);
+            mapping[p] = None;
+            used[t] = false;
+        }
+    }
+
+    /// Returns true if the pattern vertex p may map to the target vertex t,
+    /// given the vertices mapped so far.
+    fn feasible(&self, p: usize, t: usize, mapping: &[Option<usize>]) -> bool {
+        if self.target.successors[t].len() < self.pattern.successors[p].len()
+            || self.target.predecessors[t].len() < self.pattern.predecessors[p].len()
+            || !(self.matches)(p, t)
+        {
+            return false;
+        }
+        self.pattern.successors[p]
+            .iter()
+            .all(|q| mapping[*q].is_none_or(|u| self.target.has_edge(t, u)))
+            && self.pattern.predecessors[p]
+                .iter()
+                .all(|q| mapping[*q].is_none_or(|u| self.target.has_edge(u, t)))
+    }
+}
@@ -1,54 +1,252 @@
 mod api;
+mod budget;
+mod canonical;
+mod coarsen;
+mod cursor;
+mod entry;
+#[cfg(feature = "serde")]
+mod envelope;
+mod interval;
+mod invariants;
+mod iter;
+mod labeling;
+mod layout;
+mod matching;
+mod mutation;
+mod order;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod path;
+mod progress;
+mod query;
+mod reachability;
+mod scheduler;
 mod test;
+mod view;
 
-use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::boxed::Box;
+use alloc::collections::{btree_map, BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::convert::TryFrom;
 use core::default::Default;
+use core::mem::size_of;
+use core::ops::RangeBounds;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::Error;
+use crate::frozen::FrozenDag;
+use crate::{ContextError, Error, ErrorContext};
 pub use api::*;
+pub use budget::*;
+pub use canonical::*;
+pub use coarsen::*;
+pub use cursor::*;
+pub use entry::*;
+#[cfg(feature = "serde")]
+pub use envelope::*;
+pub use interval::*;
+pub use invariants::*;
+pub use iter::*;
+pub use layout::*;
+pub use mutation::*;
+pub use order::*;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
+pub use path::*;
+pub use progress::*;
+pub use query::*;
+pub use reachability::*;
+pub use scheduler::*;
+pub use view::*;
 
 /// `BTreeDAG` is an implementation of a directed acyclic graph (abstract data structure)
-/// which utilizes `BTreeMap` for the vertex adjacency list.
-#[derive(PartialEq, Eq, Clone, Debug)]
+/// which utilizes `BTreeMap` for the vertex adjacency list. Each adjacency set is
+/// shared behind an `Arc` and copied on write, so cloning a `BTreeDAG` does not
+/// copy the adjacency sets until they are mutated.
+///
+/// The maximum depth of a traversal is a setting of the dag rather than part of
+/// its value, so it is neither compared nor serialized.
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BTreeDAG<T>
 where
     T: Ord,
 {
-    vertices: BTreeMap<T, BTreeSet<T>>,
+    pub(crate) vertices: BTreeMap<T, Arc<BTreeSet<T>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) max_traversal_depth: Option<usize>,
+}
+
+impl<T> PartialEq for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.vertices == other.vertices
+    }
 }
 
+impl<T> Eq for BTreeDAG<T> where T: Ord {}
+
 impl<T> BTreeDAG<T>
 where
     T: Ord,
 {
     pub fn new() -> Self {
-        let vertices: BTreeMap<T, BTreeSet<T>> = BTreeMap::new();
-        BTreeDAG { vertices }
+        BTreeDAG::from_vertices(BTreeMap::new())
+    }
+
+    /// Constructs a dag with no traversal depth limit from an adjacency list,
+    /// which must already be acyclic.
+    pub(crate) fn from_vertices(vertices: BTreeMap<T, Arc<BTreeSet<T>>>) -> Self {
+        BTreeDAG {
+            vertices,
+            max_traversal_depth: None,
+        }
+    }
+
+    /// Constructs a dag derived from this one from an adjacency list, which
+    /// must already be acyclic, carrying over the maximum traversal depth.
+    pub(crate) fn derive(&self, vertices: BTreeMap<T, Arc<BTreeSet<T>>>) -> Self {
+        BTreeDAG {
+            vertices,
+            max_traversal_depth: self.max_traversal_depth,
+        }
+    }
+
+    /// Returns the maximum depth of a traversal, or None if it is unlimited,
+    /// which is the default.
+    pub fn max_traversal_depth(&self) -> Option<usize> {
+        self.max_traversal_depth
+    }
+
+    /// Sets the maximum depth of the cycle check of `add_edge`, which is the
+    /// only traversal a mutation makes. A cycle check which would go deeper
+    /// returns `Error::DepthLimitExceeded` rather than continuing, bounding the
+    /// work done on adversarially deep dags. The cycle check, like the
+    /// traversals of `chain_decomposition` and of the conversion to a
+    /// `DenseDAG`, is iterative, so none of them overflows the stack
+    /// regardless of the setting.
+    ///
+    /// The setting is carried over to the dags derived from this one, such as
+    /// by `merge`, `intersection`, `difference`, `split_off_range` and
+    /// `freeze` followed by `thaw`, but not to dags built from scratch.
+    pub fn set_max_traversal_depth(&mut self, depth: Option<usize>) {
+        self.max_traversal_depth = depth;
     }
 
     fn cyclic_relationship_exists(&self, x: &T, y: &T) -> Result<(), Error> {
-        if let Some(adj_y) = self.vertices.get(y) {
-            // If y has adjacent vertices, then have we need to
-            // check if x exists in these adjacent vertices;
-            if !adj_y.contains(x) {
-                // if it does not, then recurse. Making sure x
-                // is not adjacent to any of y's adjacent vertices.
-                for adj in adj_y {
-                    self.cyclic_relationship_exists(x, adj)?;
-                }
-                // If no error has been thrown by this line, then
-                // we must not have found x in any of the adjacency lists.
-                return Ok(());
-            }
+        // If y does not exist, then the edge can not be added.
+        let adj_y = self.vertices.get(y).ok_or(Error::VertexDoesNotExist)?;
+        if x == y {
             return Err(Error::EdgeExists);
         }
-        // If y has no adjacent vertices, then we can be sure there
-        // no circular relationship.
-        Err(Error::VertexDoesNotExist)
+        // Nothing is reachable from a vertex with no adjacent vertices, and
+        // an existing edge can not close a cycle in an acyclic dag, so
+        // neither needs a search.
+        if adj_y.is_empty() || self.vertices.get(x).is_some_and(|adj_x| adj_x.contains(y)) {
+            return Ok(());
+        }
+        // Search depth first from y for x, where each entry on the stack
+        // is a vertex reachable from y, and its depth.
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<(&T, usize)> = adj_y.iter().map(|v| (v, 1)).collect();
+        while let Some((v, depth)) = stack.pop() {
+            if v == x {
+                return Err(Error::EdgeExists);
+            }
+            if self.max_traversal_depth.is_some_and(|max| depth > max) {
+                return Err(Error::DepthLimitExceeded);
+            }
+            if visited.insert(v) {
+                stack.extend(self.vertices[v].iter().map(|w| (w, depth + 1)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a snapshot of the dag. The snapshot shares the adjacency
+    /// sets with the dag, and each set is only copied when it is next mutated, so
+    /// taking a snapshot copies the vertices but not their edges.
+    pub fn snapshot(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.clone()
+    }
+
+    /// Returns the adjacency list backing the dag. Each adjacency set is
+    /// shared behind an `Arc`, as it is stored, so no set is copied.
+    pub fn as_map(&self) -> &BTreeMap<T, Arc<BTreeSet<T>>> {
+        &self.vertices
+    }
+
+    /// Returns the adjacency list backing the dag, consuming the dag. Only
+    /// the adjacency sets still shared with a snapshot are copied.
+    pub fn into_inner(self) -> BTreeMap<T, BTreeSet<T>>
+    where
+        T: Clone,
+    {
+        self.vertices
+            .into_iter()
+            .map(|(v, adj)| (v, into_owned(adj)))
+            .collect()
+    }
+
+    /// Returns the set of vertices reachable from x, excluding x.
+    pub(crate) fn descendants_of<'a>(&'a self, x: &T) -> BTreeSet<&'a T> {
+        let mut descendants: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = Vec::new();
+        if let Some(adj_x) = self.vertices.get(x) {
+            stack.extend(adj_x.iter());
+        }
+        while let Some(v) = stack.pop() {
+            if descendants.insert(v) {
+                stack.extend(self.vertices[v].iter());
+            }
+        }
+        descendants
+    }
+
+    /// Returns the set of vertices from which x is reachable, excluding x.
+    pub(crate) fn ancestors_of<'a>(&'a self, x: &T) -> BTreeSet<&'a T> {
+        self.ancestors_of_all(core::iter::once(x))
+    }
+
+    /// Returns the set of vertices from which any of the vertices xs is
+    /// reachable along at least one edge, in a single traversal.
+    pub(crate) fn ancestors_of_all<'a, 'b>(
+        &'a self,
+        xs: impl IntoIterator<Item = &'b T>,
+    ) -> BTreeSet<&'a T>
+    where
+        T: 'b,
+    {
+        let mut predecessors: BTreeMap<&T, Vec<&T>> = BTreeMap::new();
+        for (v, adj) in self.vertices.iter() {
+            for y in adj.iter() {
+                predecessors.entry(y).or_default().push(v);
+            }
+        }
+        let mut ancestors: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = xs
+            .into_iter()
+            .filter_map(|x| predecessors.get(x))
+            .flatten()
+            .copied()
+            .collect();
+        while let Some(v) = stack.pop() {
+            if ancestors.insert(v) {
+                if let Some(xs) = predecessors.get(v) {
+                    stack.extend(xs.iter());
+                }
+            }
+        }
+        ancestors
     }
 }
 
@@ -70,12 +268,21 @@ where
     }
 }
 
+/// Adding a vertex which is there replaces its adjacency list with an empty
+/// one, and returns the previous list. The list is moved out rather than
+/// copied, so vertices need not be `Clone`. Only a clone of the dag, which
+/// requires `T: Clone`, can share the list; if one still does, the clone keeps
+/// the list and an empty list is returned in its place.
 impl<T> AddVertex<T> for BTreeDAG<T>
 where
     T: Ord,
 {
     fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
-        self.vertices.insert(x, BTreeSet::new())
+        checked_mutation!(self, {
+            self.vertices
+                .insert(x, Arc::new(BTreeSet::new()))
+                .map(|adj_x| Arc::try_unwrap(adj_x).unwrap_or_default())
+        })
     }
 }
 
@@ -86,15 +293,22 @@ where
 {
     type Error = Error;
     fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
-        if let Some(adj_x) = self.vertices.get(&x) {
-            self.cyclic_relationship_exists(&x, &y)?;
-            // Add y to x's adjacency list.
-            let mut adj_x: BTreeSet<T> = adj_x.clone();
-            adj_x.insert(y);
+        checked_mutation!(self, {
+            if self.vertices.contains_key(&x) {
+                let _span = debug_span!("add_edge", vertices = self.vertices.len());
+                let checked = self.cyclic_relationship_exists(&x, &y);
+                debug_event!(acyclic = checked.is_ok(), "checked for cycle");
+                checked?;
+                // Add y to x's adjacency list. We have already verified x is
+                // in vertices, so we can safely unwrap.
+                let adj_x = self.vertices.get_mut(&x).unwrap();
+                let previous_adj_x: BTreeSet<T> = BTreeSet::clone(adj_x);
+                Arc::make_mut(adj_x).insert(y);
 
-            return Ok(self.vertices.insert(x, adj_x).unwrap());
-        }
-        Err(Error::VertexDoesNotExist)
+                return Ok(previous_adj_x);
+            }
+            Err(Error::VertexDoesNotExist)
+        })
     }
 }
 
@@ -103,7 +317,7 @@ where
     T: Ord,
 {
     fn get_vertex_value(&self, v: T) -> Option<&BTreeSet<T>> {
-        self.vertices.get(&v)
+        self.vertices.get(&v).map(Arc::as_ref)
     }
 }
 
@@ -115,18 +329,20 @@ where
 {
     type Error = Error;
     fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
-        if self.vertices.get(&y).is_some() {
-            if let Some(adj_x) = self.vertices.get(&x) {
-                // Remove y from x's adjacency list.
-                let mut updated_adj_x = adj_x.clone();
-                updated_adj_x.remove(&y);
-
-                // Update vertices. Since we have already verified x is in vertices,
-                // we can safely unwrap.
-                return Ok(self.vertices.insert(x, updated_adj_x).unwrap());
+        checked_mutation!(self, {
+            if self.vertices.contains_key(&y) {
+                if let Some(adj_x) = self.vertices.get_mut(&x) {
+                    let previous_adj_x: BTreeSet<T> = BTreeSet::clone(adj_x);
+                    // Remove y from x's adjacency list, only copying the list
+                    // if it is shared and actually changes.
+                    if adj_x.contains(&y) {
+                        Arc::make_mut(adj_x).remove(&y);
+                    }
+                    return Ok(previous_adj_x);
+                }
             }
-        }
-        Err(Error::VertexDoesNotExist)
+            Err(Error::VertexDoesNotExist)
+        })
     }
 }
 
@@ -137,22 +353,36 @@ where
 {
     type Error = Error;
     fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
-        // The remove edge method will error with EdgeDoesNotExist on the
-        // first iteration is in fact x does not exist.
-        self.vertices
-            .clone()
-            .into_iter()
-            .filter(|v| -> bool { v.1.contains(&x) })
-            .try_for_each(|v| -> Result<(), Self::Error> {
-                self.remove_edge(v.0, x.clone())?;
-                Ok(())
-            })?;
-        // At this point, no other vertices should point to x,
-        // and so x can be removed.
+        checked_mutation!(self, {
+            // If no other vertex points to x, the remove edge method is never
+            // called, so the existence of x must be verified up front.
+            if !self.vertices.contains_key(&x) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            let _span = debug_span!("remove_vertex", vertices = self.vertices.len());
+            let incoming: Vec<T> = self
+                .vertices
+                .iter()
+                .filter(|v| -> bool { v.1.contains(&x) })
+                .map(|v| v.0.clone())
+                .collect();
+            debug_event!(
+                incoming_edges = incoming.len(),
+                "scanned for incoming edges"
+            );
+            incoming
+                .into_iter()
+                .try_for_each(|v| -> Result<(), Self::Error> {
+                    self.remove_edge(v, x.clone())?;
+                    Ok(())
+                })?;
+            // At this point, no other vertices should point to x,
+            // and so x can be removed.
 
-        // We can be sure that if there has not been an error thrown by now,
-        // then x definitely exists in then vertices, so it is safe to unwrap.
-        Ok(self.vertices.remove(&x).unwrap())
+            // We have already verified x exists in the vertices, so it is
+            // safe to unwrap.
+            Ok(into_owned(self.vertices.remove(&x).unwrap()))
+        })
     }
 }
 
@@ -162,7 +392,7 @@ where
 {
     type Error = Error;
     fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
-        if self.vertices.get(&y).is_some() {
+        if self.vertices.contains_key(&y) {
             if let Some(adj_x) = self.vertices.get(&x) {
                 if adj_x.contains(&y) {
                     return Ok(true);
@@ -179,17 +409,2091 @@ where
     T: Ord,
 {
     fn connections(&self, x: T) -> Option<&BTreeSet<T>> {
-        self.vertices.get(&x)
+        self.successors(&x)
     }
 }
 
-impl<T> Prune<T> for BTreeDAG<T> where T: Ord + Clone {
+impl<T> Prune<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
     type Error = Error;
     fn prune(&mut self, x: T) -> Result<(), Self::Error> {
-        let child_vertices = self.remove_vertex(x)?;
-        for vertex in child_vertices {
-            self.prune(vertex)?;
+        checked_mutation!(self, {
+            let _span = debug_span!("prune", vertices = self.vertices.len());
+            if !self.vertices.contains_key(&x) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            // Collect x and every descendant of x before mutating, so the dag
+            // is either fully pruned or, on error, unchanged.
+            let mut pruned: BTreeSet<T> = self.descendants_of(&x).into_iter().cloned().collect();
+            pruned.insert(x);
+            debug_event!(pruned = pruned.len(), "collected descendants");
+            self.vertices.retain(|v, _| !pruned.contains(v));
+            for adj in self.vertices.values_mut() {
+                // Only copy the adjacency lists which actually change.
+                if adj.iter().any(|v| pruned.contains(v)) {
+                    Arc::make_mut(adj).retain(|v| !pruned.contains(v));
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<T> Entry<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn entry(&mut self, x: T) -> VertexEntry<'_, T> {
+        VertexEntry::new(self, x)
+    }
+}
+
+/// Removing edges can never introduce a cycle, so no checks are required.
+impl<T> DrainEdgesWhere<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn drain_edges_where(&mut self, mut pred: impl FnMut(&T, &T) -> bool) -> Vec<(T, T)> {
+        checked_mutation!(self, {
+            let mut drained_edges: Vec<(T, T)> = Vec::new();
+            for (x, adj_x) in self.vertices.iter_mut() {
+                let drained: Vec<T> = adj_x.iter().filter(|y| pred(x, y)).cloned().collect();
+                // Only copy x's adjacency list if it is shared and actually
+                // changes.
+                if !drained.is_empty() {
+                    let adj_x = Arc::make_mut(adj_x);
+                    for y in drained.iter() {
+                        adj_x.remove(y);
+                    }
+                }
+                drained_edges.extend(drained.into_iter().map(|y| (x.clone(), y)));
+            }
+            drained_edges
+        })
+    }
+}
+
+impl<T> ShortestPath<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn shortest_path(&self, x: &T, y: &T) -> Result<Option<Path<'_, T>>, Self::Error> {
+        if !self.vertices.contains_key(y) {
+            return Err(Error::VertexDoesNotExist);
         }
-        Ok(())
+        let (x, _) = self
+            .vertices
+            .get_key_value(x)
+            .ok_or(Error::VertexDoesNotExist)?;
+
+        // Breadth first search from x, recording the vertex each
+        // vertex was first discovered from.
+        let mut parents: BTreeMap<&T, &T> = BTreeMap::new();
+        let mut queue: VecDeque<&T> = VecDeque::new();
+        queue.push_back(x);
+        while let Some(v) = queue.pop_front() {
+            if v == y {
+                // Walk the discovered parents back to x.
+                let mut vertices: Vec<&T> = Vec::new();
+                let mut current = v;
+                vertices.push(current);
+                while let Some(parent) = parents.get(current) {
+                    current = parent;
+                    vertices.push(current);
+                }
+                vertices.reverse();
+                return Ok(Some(Path::new_unchecked(vertices)));
+            }
+            for adj in self.vertices[v].iter() {
+                if adj != x && !parents.contains_key(adj) {
+                    parents.insert(adj, v);
+                    queue.push_back(adj);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<T> ChainDecomposition<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn chain_decomposition(&self) -> Vec<Vec<&T>> {
+        // Index the vertices by their ordering, and compute the vertices
+        // reachable from each one as a bitset, built from the bitsets of its
+        // adjacent vertices in reverse topological order.
+        let vertices: Vec<&T> = self.vertices.keys().collect();
+        let adjacency: Vec<Vec<usize>> = index_adjacency(self);
+        let words: usize = vertices.len().div_ceil(64);
+        let mut reachable: Vec<Vec<u64>> = vec![Vec::new(); vertices.len()];
+        // Every sorted vertex is a vertex, so we can safely unwrap.
+        for v in self.topological_sort().into_iter().rev() {
+            let i = vertices.binary_search(&v).unwrap();
+            let mut bits: Vec<u64> = vec![0; words];
+            for &j in adjacency[i].iter() {
+                bits[j / 64] |= 1 << (j % 64);
+                for (word, adj_word) in bits.iter_mut().zip(reachable[j].iter()) {
+                    *word |= adj_word;
+                }
+            }
+            reachable[i] = bits;
+        }
+
+        // A minimum chain cover corresponds to a maximum matching in the
+        // bipartite graph of the reachability relation, where matching u
+        // to v places v directly after u in a chain.
+        let mut matched_to: Vec<Option<usize>> = vec![None; vertices.len()];
+        for u in 0..vertices.len() {
+            let mut visited: Vec<bool> = vec![false; vertices.len()];
+            augment(u, &reachable, &mut matched_to, &mut visited);
+        }
+
+        let mut next: Vec<Option<usize>> = vec![None; vertices.len()];
+        for (v, u) in matched_to.iter().enumerate() {
+            if let Some(u) = u {
+                next[*u] = Some(v);
+            }
+        }
+
+        // Every vertex which does not follow another starts a chain.
+        matched_to
+            .iter()
+            .enumerate()
+            .filter(|(_, u)| u.is_none())
+            .map(|(start, _)| {
+                let mut chain: Vec<&T> = vec![vertices[start]];
+                let mut current = start;
+                while let Some(v) = next[current] {
+                    chain.push(vertices[v]);
+                    current = v;
+                }
+                chain
+            })
+            .collect()
+    }
+}
+
+/// Attempts to find an augmenting path from u in the bipartite matching,
+/// returning true if the matching was extended. The vertices reachable from
+/// each vertex are given as bitsets. The search is depth first, with an
+/// explicit stack, since a path may be as long as the dag is deep.
+fn augment(
+    u: usize,
+    reachable: &[Vec<u64>],
+    matched_to: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    // Each frame is a vertex on the left, and the index from which to search
+    // for the next vertex it may be matched to. The vertex on the right through which each frame but
+    // the first was reached is held in path.
+    let mut stack: Vec<(usize, usize)> = vec![(u, 0)];
+    let mut path: Vec<usize> = Vec::new();
+    while let Some((u, next)) = stack.last_mut() {
+        let u = *u;
+        let v = match next_bit(&reachable[u], *next) {
+            Some(v) => v,
+            None => {
+                stack.pop();
+                path.pop();
+                continue;
+            }
+        };
+        *next = v + 1;
+        if visited[v] {
+            continue;
+        }
+        visited[v] = true;
+        path.push(v);
+        match matched_to[v] {
+            Some(w) => stack.push((w, 0)),
+            None => {
+                // Rematch every vertex on the path to the vertex it reached,
+                // freeing the vertex matched before for the frame below.
+                for ((u, _), v) in stack.iter().zip(path.iter()) {
+                    matched_to[*v] = Some(*u);
+                }
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Returns the index of the first bit set in bits at or after from.
+fn next_bit(bits: &[u64], from: usize) -> Option<usize> {
+    let mut word = from / 64;
+    let mut masked = bits.get(word)? & (u64::MAX << (from % 64));
+    loop {
+        if masked != 0 {
+            return Some(word * 64 + masked.trailing_zeros() as usize);
+        }
+        word += 1;
+        masked = *bits.get(word)?;
+    }
+}
+
+impl<T> FromEdges<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn from_edges(edges: impl IntoIterator<Item = (T, T)>) -> Result<Self, Self::Error> {
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = BTreeMap::new();
+        for (x, y) in edges {
+            vertices.entry(y.clone()).or_default();
+            Arc::make_mut(vertices.entry(x).or_default()).insert(y);
+        }
+        if !is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+/// Returns true if the adjacency list contains no cycles, by repeatedly
+/// removing vertices with no incoming edges (Kahn's algorithm). Every
+/// vertex referenced by an edge must be a key of the adjacency list.
+pub(crate) fn is_acyclic<T>(vertices: &BTreeMap<T, Arc<BTreeSet<T>>>) -> bool
+where
+    T: Ord,
+{
+    let mut in_degrees: BTreeMap<&T, usize> = vertices.keys().map(|v| (v, 0)).collect();
+    for adj in vertices.values().flat_map(|adj| adj.iter()) {
+        *in_degrees.get_mut(adj).unwrap() += 1;
+    }
+    let mut sources: Vec<&T> = in_degrees
+        .iter()
+        .filter(|(_, d)| **d == 0)
+        .map(|(v, _)| *v)
+        .collect();
+    let mut removed: usize = 0;
+    while let Some(v) = sources.pop() {
+        removed += 1;
+        for adj in vertices[v].iter() {
+            let d = in_degrees.get_mut(adj).unwrap();
+            *d -= 1;
+            if *d == 0 {
+                sources.push(adj);
+            }
+        }
+    }
+    removed == vertices.len()
+}
+
+impl<T> FeedbackArcSet<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn feedback_arc_set(edges: impl IntoIterator<Item = (T, T)>) -> BTreeSet<(T, T)> {
+        let mut feedback: BTreeSet<(T, T)> = BTreeSet::new();
+        let mut arcs: BTreeSet<(T, T)> = BTreeSet::new();
+        for (x, y) in edges {
+            if x == y {
+                feedback.insert((x, y));
+            } else {
+                arcs.insert((x, y));
+            }
+        }
+
+        let mut successors: BTreeMap<&T, BTreeSet<&T>> = BTreeMap::new();
+        let mut predecessors: BTreeMap<&T, BTreeSet<&T>> = BTreeMap::new();
+        for (x, y) in arcs.iter() {
+            successors.entry(x).or_default().insert(y);
+            successors.entry(y).or_default();
+            predecessors.entry(y).or_default().insert(x);
+            predecessors.entry(x).or_default();
+        }
+
+        // Build an ordering of the vertices by peeling sinks to the end and
+        // sources to the start. When neither remains, the vertex with the
+        // greatest excess of outgoing over incoming edges is placed next.
+        let mut start: Vec<&T> = Vec::new();
+        let mut end: Vec<&T> = Vec::new();
+        while !successors.is_empty() {
+            let v = if let Some(v) = successors.iter().find(|(_, adj)| adj.is_empty()) {
+                end.push(v.0);
+                *v.0
+            } else if let Some(v) = predecessors.iter().find(|(_, adj)| adj.is_empty()) {
+                start.push(v.0);
+                *v.0
+            } else {
+                // We have already verified successors is not empty, so we
+                // can safely unwrap.
+                let v = *successors
+                    .keys()
+                    .max_by_key(|v| {
+                        (successors[*v].len() as isize) - (predecessors[*v].len() as isize)
+                    })
+                    .unwrap();
+                start.push(v);
+                v
+            };
+            for adj in successors.remove(v).unwrap() {
+                predecessors.get_mut(adj).unwrap().remove(v);
+            }
+            for adj in predecessors.remove(v).unwrap() {
+                successors.get_mut(adj).unwrap().remove(v);
+            }
+        }
+
+        // Every edge pointing backwards in the ordering is a feedback edge.
+        let position: BTreeMap<&T, usize> = start
+            .into_iter()
+            .chain(end.into_iter().rev())
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        for (x, y) in arcs.iter() {
+            if position[x] > position[y] {
+                feedback.insert((x.clone(), y.clone()));
+            }
+        }
+        feedback
+    }
+}
+
+/// Takes ownership of a shared adjacency list, copying it only if it is
+/// still shared.
+pub(crate) fn into_owned<T>(adj: Arc<BTreeSet<T>>) -> BTreeSet<T>
+where
+    T: Ord + Clone,
+{
+    Arc::try_unwrap(adj).unwrap_or_else(|adj| BTreeSet::clone(&adj))
+}
+
+impl<T> TopologicalSort<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn topological_sort(&self) -> Vec<&T> {
+        // An unlimited budget can never be exhausted, so we can safely
+        // unwrap.
+        self.topological_sort_within(&mut Budget::unlimited())
+            .unwrap()
+    }
+}
+
+impl<T> TryTopologicalSortWithBudget<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn try_topological_sort_with_budget(&self, ops_limit: usize) -> Result<Vec<&T>, Self::Error> {
+        self.topological_sort_within(&mut Budget::ops(ops_limit))
+    }
+}
+
+impl<T> TryDescendants<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn try_descendants(
+        &self,
+        x: &T,
+        token: &CancellationToken,
+    ) -> Result<BTreeSet<&T>, Self::Error> {
+        let mut budget = Budget::token(token);
+        let mut descendants: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = Vec::new();
+        stack.extend(
+            self.vertices
+                .get(x)
+                .ok_or(Error::VertexDoesNotExist)?
+                .iter(),
+        );
+        while let Some(v) = stack.pop() {
+            budget.spend()?;
+            if descendants.insert(v) {
+                stack.extend(self.vertices[v].iter());
+            }
+        }
+        Ok(descendants)
+    }
+}
+
+impl<T> BTreeDAG<T>
+where
+    T: Ord,
+{
+    /// Sorts the vertices topologically, spending one operation of the
+    /// budget per vertex and per edge.
+    fn topological_sort_within(&self, budget: &mut Budget<'_>) -> Result<Vec<&T>, Error> {
+        let _span = debug_span!("topological_sort", vertices = self.vertices.len());
+        let mut in_degrees: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            budget.spend()?;
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        // Kahn's algorithm, always removing the least vertex with no
+        // remaining incoming edges.
+        let mut ready: BTreeSet<&T> = in_degrees
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(v, _)| *v)
+            .collect();
+        let mut sorted: Vec<&T> = Vec::with_capacity(self.vertices.len());
+        while let Some(v) = ready.pop_first() {
+            budget.spend()?;
+            sorted.push(v);
+            for adj in self.vertices[v].iter() {
+                budget.spend()?;
+                let d = in_degrees.get_mut(adj).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    ready.insert(adj);
+                }
+            }
+        }
+        Ok(sorted)
+    }
+}
+
+impl<T> IterSorted<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn iter_sorted(&self) -> SortedVertices<'_, T> {
+        SortedVertices {
+            vertices: self.vertices.iter(),
+        }
+    }
+}
+
+impl<T> EdgesSorted<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn edges_sorted(&self) -> SortedEdges<'_, T> {
+        SortedEdges {
+            vertices: self.vertices.iter(),
+            current: None,
+        }
+    }
+}
+
+impl<T> ToCanonical<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn to_canonical(&self) -> Canonical<&T> {
+        Canonical {
+            vertices: self.vertices.keys().collect(),
+            edges: self.edges_sorted().collect(),
+        }
+    }
+}
+
+/// Every vertex referenced by an edge must be listed in the vertices, and the
+/// edges must not contain a cycle.
+impl<T> TryFrom<Canonical<T>> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn try_from(canonical: Canonical<T>) -> Result<Self, Self::Error> {
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = canonical
+            .vertices
+            .into_iter()
+            .map(|v| (v, Arc::new(BTreeSet::new())))
+            .collect();
+        for (x, y) in canonical.edges {
+            if !vertices.contains_key(&y) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            match vertices.get_mut(&x) {
+                Some(adj_x) => Arc::make_mut(adj_x).insert(y),
+                None => return Err(Error::VertexDoesNotExist),
+            };
+        }
+        if !is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+impl<T> OutDegree<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn out_degree(&self, x: &T) -> Option<usize> {
+        self.vertices.get(x).map(|adj_x| adj_x.len())
+    }
+}
+
+/// The maximum number of elements in a node of the standard library's B-tree.
+const BTREE_NODE_CAPACITY: usize = 11;
+
+/// Estimates the heap memory used by a B-tree of len elements of the given
+/// size, assuming fully occupied leaf nodes. Each node is estimated to also
+/// hold a parent pointer, and its index and length.
+fn btree_footprint_estimate(len: usize, element_size: usize) -> usize {
+    let nodes = len.div_ceil(BTREE_NODE_CAPACITY);
+    nodes * (BTREE_NODE_CAPACITY * element_size + size_of::<usize>() * 2)
+}
+
+/// Estimates the heap memory used by a shared adjacency set: the reference
+/// counts, the set itself, and the set's B-tree.
+fn adjacency_footprint_estimate<T>(adj: &Arc<BTreeSet<T>>) -> usize {
+    size_of::<usize>() * 2
+        + size_of::<BTreeSet<T>>()
+        + btree_footprint_estimate(adj.len(), size_of::<T>())
+}
+
+impl<T> MemoryFootprint<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn memory_footprint_estimate(&self) -> usize {
+        let vertices = btree_footprint_estimate(
+            self.vertices.len(),
+            size_of::<T>() + size_of::<Arc<BTreeSet<T>>>(),
+        );
+        let mut counted: BTreeSet<*const BTreeSet<T>> = BTreeSet::new();
+        let adjacency: usize = self
+            .vertices
+            .values()
+            .filter(|adj| counted.insert(Arc::as_ptr(adj)))
+            .map(adjacency_footprint_estimate)
+            .sum();
+        vertices + adjacency
+    }
+
+    fn vertex_footprint_estimate(&self, x: &T) -> Option<usize> {
+        self.vertices.get(x).map(adjacency_footprint_estimate)
+    }
+}
+
+impl<T> BuildReachabilityIndex<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn build_reachability_index(&self) -> ReachabilityIndex<'_, T> {
+        ReachabilityIndex::new(self)
+    }
+}
+
+impl<T> Successors<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn successors(&self, x: &T) -> Option<&BTreeSet<T>> {
+        self.vertices.get(x).map(Arc::as_ref)
+    }
+}
+
+/// Only outgoing edges are stored, so every adjacency list is scanned for x.
+impl<T> Predecessors<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn predecessors(&self, x: &T) -> Option<BTreeSet<&T>> {
+        if !self.vertices.contains_key(x) {
+            return None;
+        }
+        Some(
+            self.vertices
+                .iter()
+                .filter(|(_, adj)| adj.contains(x))
+                .map(|(w, _)| w)
+                .collect(),
+        )
+    }
+}
+
+impl<T> Merge<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn merge(&self, other: &Self) -> Result<Self, Self::Error> {
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self.vertices.clone();
+        for (x, adj_x) in other.vertices.iter() {
+            match vertices.get_mut(x) {
+                Some(merged) => {
+                    // Only copy the adjacency list if the other dag adds
+                    // an edge to it.
+                    if !adj_x.is_subset(merged) {
+                        Arc::make_mut(merged).extend(adj_x.iter().cloned());
+                    }
+                }
+                None => {
+                    vertices.insert(x.clone(), Arc::clone(adj_x));
+                }
+            }
+        }
+        if !is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(self.derive(vertices))
+    }
+}
+
+/// The intersection is a subgraph of both dags, so it is acyclic.
+impl<T> Intersection<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn intersection(&self, other: &Self) -> Self {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self
+            .vertices
+            .iter()
+            .filter_map(|(x, adj_x)| {
+                let other_adj_x = other.vertices.get(x)?;
+                let adj = if Arc::ptr_eq(adj_x, other_adj_x) {
+                    Arc::clone(adj_x)
+                } else {
+                    Arc::new(adj_x.intersection(other_adj_x).cloned().collect())
+                };
+                Some((x.clone(), adj))
+            })
+            .collect();
+        self.derive(vertices)
+    }
+}
+
+/// The difference is a subgraph of this dag, so it is acyclic.
+impl<T> Difference<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn difference(&self, other: &Self) -> Self {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self
+            .vertices
+            .iter()
+            .map(|(x, adj_x)| {
+                let adj = match other.vertices.get(x) {
+                    Some(other_adj_x) => Arc::new(adj_x.difference(other_adj_x).cloned().collect()),
+                    None => Arc::clone(adj_x),
+                };
+                (x.clone(), adj)
+            })
+            .collect();
+        self.derive(vertices)
+    }
+}
+
+impl<T> ContractEdge<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn contract_edge(&mut self, x: T, y: T) -> Result<T, Self::Error> {
+        checked_mutation!(self, {
+            let adj_x = self.vertices.get(&x).ok_or(Error::VertexDoesNotExist)?;
+            if !self.vertices.contains_key(&y) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            if !adj_x.contains(&y) {
+                return Err(Error::EdgeDoesNotExist);
+            }
+            // Any other path from x to y would become a cycle through x.
+            let mut visited: BTreeSet<&T> = BTreeSet::new();
+            let mut stack: Vec<&T> = adj_x.iter().filter(|v| **v != y).collect();
+            while let Some(v) = stack.pop() {
+                if *v == y {
+                    return Err(Error::EdgeExists);
+                }
+                if visited.insert(v) {
+                    stack.extend(self.vertices[v].iter());
+                }
+            }
+
+            // We have already verified y exists, so we can safely unwrap.
+            let (y, adj_y) = self.vertices.remove_entry(&y).unwrap();
+            for (w, adj_w) in self.vertices.iter_mut() {
+                if adj_w.contains(&y) {
+                    let adj_w = Arc::make_mut(adj_w);
+                    adj_w.remove(&y);
+                    if *w != x {
+                        adj_w.insert(x.clone());
+                    }
+                }
+            }
+            // We have already verified x exists, so we can safely unwrap.
+            Arc::make_mut(self.vertices.get_mut(&x).unwrap()).extend(into_owned(adj_y));
+            Ok(y)
+        })
+    }
+}
+
+impl<T> Coarsen<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn coarsen(&self, levels: usize) -> Vec<Coarsening<T>> {
+        let mut coarsenings: Vec<Coarsening<T>> = Vec::new();
+        let mut dag: BTreeDAG<T> = self.clone();
+        let mut mapping: BTreeMap<T, T> = self
+            .vertices
+            .keys()
+            .map(|v| (v.clone(), v.clone()))
+            .collect();
+        for _ in 0..levels {
+            let merged: BTreeMap<T, T> = dag.chain_edges();
+            if merged.is_empty() {
+                break;
+            }
+            // Each chain edge (x, y) is the only edge from x and the only
+            // edge to y, so x simply takes over the edges of y.
+            for (y, x) in merged.iter() {
+                // Both vertices exist, as the edge does.
+                let adj_y = dag.vertices.remove(y).unwrap();
+                dag.vertices.insert(x.clone(), adj_y);
+            }
+            for representative in mapping.values_mut() {
+                if let Some(x) = merged.get(representative) {
+                    *representative = x.clone();
+                }
+            }
+            coarsenings.push(Coarsening {
+                dag: dag.clone(),
+                mapping: mapping.clone(),
+            });
+        }
+        coarsenings
+    }
+}
+
+impl<T> BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Returns a set of disjoint chain edges, i.e. edges (x, y) where the
+    /// edge is the only edge from x and the only edge to y, as a map from
+    /// y to x.
+    fn chain_edges(&self) -> BTreeMap<T, T> {
+        let mut in_degrees: BTreeMap<&T, usize> = BTreeMap::new();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.entry(adj).or_default() += 1;
+        }
+        let mut matched: BTreeSet<&T> = BTreeSet::new();
+        let mut merged: BTreeMap<T, T> = BTreeMap::new();
+        for (x, adj_x) in self.vertices.iter() {
+            if adj_x.len() != 1 || matched.contains(x) {
+                continue;
+            }
+            // We have already verified x has exactly one adjacent vertex.
+            let y = adj_x.iter().next().unwrap();
+            if in_degrees[y] == 1 && !matched.contains(y) {
+                matched.insert(x);
+                matched.insert(y);
+                merged.insert(y.clone(), x.clone());
+            }
+        }
+        merged
+    }
+}
+
+impl<T> VertexIndex<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn index_of(&self, x: &T) -> Option<usize> {
+        if !self.vertices.contains_key(x) {
+            return None;
+        }
+        Some(self.vertices.range(..x).count())
+    }
+
+    fn vertex_at(&self, i: usize) -> Option<&T> {
+        self.vertices.keys().nth(i)
+    }
+}
+
+impl<T> Freeze<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn freeze(&self) -> FrozenDag<T> {
+        let mut frozen = FrozenDag::new(
+            self.vertices
+                .iter()
+                .map(|(x, adj_x)| (x.clone(), adj_x.iter())),
+        );
+        frozen.max_traversal_depth = self.max_traversal_depth;
+        frozen
+    }
+}
+
+impl<T> TraverseAllVertices<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn traverse_all(&self) -> TraverseAll<'_, T> {
+        TraverseAll::new(&self.vertices)
+    }
+}
+
+impl<T> DescendantsWithin<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn descendants_within(&self, x: &T, depth: usize) -> BTreeMap<&T, usize> {
+        let mut distances: BTreeMap<&T, usize> = BTreeMap::new();
+        let mut frontier: Vec<&T> = match self.vertices.get_key_value(x) {
+            Some((x, _)) => vec![x],
+            None => return distances,
+        };
+        // Breadth first search, one level at a time, so each vertex is first
+        // found at its shortest distance.
+        for distance in 1..=depth {
+            let mut next: Vec<&T> = Vec::new();
+            for v in frontier {
+                for adj in self.vertices[v].iter() {
+                    if adj != x && !distances.contains_key(adj) {
+                        distances.insert(adj, distance);
+                        next.push(adj);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        distances
+    }
+}
+
+impl<T> TopologicalRanks<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn topological_ranks(&self) -> BTreeMap<&T, usize> {
+        let mut ranks: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        // Every vertex's rank is final once all vertices with an edge to it
+        // have been visited, which a topological order guarantees.
+        for v in self.topological_sort() {
+            let rank = ranks[v];
+            for adj in self.vertices[v].iter() {
+                let adj_rank = ranks.get_mut(adj).unwrap();
+                *adj_rank = (*adj_rank).max(rank + 1);
+            }
+        }
+        ranks
+    }
+}
+
+impl<T> CanonicalForm<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn canonical_form(&self) -> Canonical<usize> {
+        let index: BTreeMap<&T, usize> = self
+            .vertices
+            .keys()
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        labeling::canonical_form(
+            self.vertices
+                .values()
+                .map(|adj| adj.iter().map(|y| index[y]).collect())
+                .collect(),
+        )
+    }
+}
+
+impl<T> Schedule<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn scheduler(&self) -> Scheduler<'_, T, ()> {
+        Scheduler::new(self, Box::new(|_| ()))
+    }
+
+    fn scheduler_by<'a, P>(&'a self, priority: impl Fn(&T) -> P + 'a) -> Scheduler<'a, T, P>
+    where
+        P: Ord,
+    {
+        Scheduler::new(self, Box::new(priority))
+    }
+}
+
+/// The vertices are visited in reverse topological order, so every vertex is
+/// visited after the vertices it depends on.
+impl<T> EarliestStartTimes<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn earliest_start_times(&self, durations: impl Fn(&T) -> u64) -> BTreeMap<&T, u64> {
+        let mut starts: BTreeMap<&T, u64> = BTreeMap::new();
+        for v in self.topological_sort().into_iter().rev() {
+            let start = self.vertices[v]
+                .iter()
+                .map(|y| starts[y] + durations(y))
+                .max()
+                .unwrap_or(0);
+            starts.insert(v, start);
+        }
+        starts
+    }
+}
+
+/// The latest start times are found by a backward pass in topological order,
+/// so every vertex is visited after the vertices depending on it.
+impl<T> Slack<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn slack(&self, durations: impl Fn(&T) -> u64) -> BTreeMap<&T, u64> {
+        let starts: BTreeMap<&T, u64> = self.earliest_start_times(&durations);
+        let end: u64 = starts
+            .iter()
+            .map(|(v, s)| s + durations(v))
+            .max()
+            .unwrap_or(0);
+        // The latest time each vertex must finish by.
+        let mut finishes: BTreeMap<&T, u64> = self.vertices.keys().map(|v| (v, end)).collect();
+        let mut slack: BTreeMap<&T, u64> = BTreeMap::new();
+        for v in self.topological_sort() {
+            let latest_start = finishes[v] - durations(v);
+            for y in self.vertices[v].iter() {
+                let finish = finishes.get_mut(y).unwrap();
+                *finish = (*finish).min(latest_start);
+            }
+            slack.insert(v, latest_start - starts[v]);
+        }
+        slack
+    }
+}
+
+impl<T> CountPaths<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn count_paths(&self, from: &T, to: &T) -> u128 {
+        if !self.vertices.contains_key(from) || !self.vertices.contains_key(to) {
+            return 0;
+        }
+        let mut counts: BTreeMap<&T, u128> = BTreeMap::new();
+        // Every vertex's count is final once all vertices it has an edge to
+        // have been visited, which a reverse topological order guarantees.
+        for v in self.topological_sort().into_iter().rev() {
+            let count = if v == to {
+                1
+            } else {
+                self.vertices[v]
+                    .iter()
+                    .fold(0u128, |count: u128, adj| count.saturating_add(counts[adj]))
+            };
+            if v == from {
+                return count;
+            }
+            counts.insert(v, count);
+        }
+        0
+    }
+}
+
+impl<T> FoldTopological<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn fold_topological<V>(
+        &self,
+        init: impl Fn(&T) -> V,
+        combine: impl Fn(&T, Vec<&V>) -> V,
+    ) -> BTreeMap<&T, V> {
+        let mut predecessors: BTreeMap<&T, Vec<&T>> = BTreeMap::new();
+        for (x, adj) in self.vertices.iter() {
+            for y in adj.iter() {
+                predecessors.entry(y).or_default().push(x);
+            }
+        }
+        let mut values: BTreeMap<&T, V> = BTreeMap::new();
+        // Every vertex's predecessors have been visited before it, which a
+        // topological order guarantees.
+        for v in self.topological_sort() {
+            let value = match predecessors.get(v) {
+                Some(xs) => combine(v, xs.iter().map(|x| &values[x]).collect()),
+                None => init(v),
+            };
+            values.insert(v, value);
+        }
+        values
+    }
+}
+
+impl<T> FoldReverseTopological<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn fold_reverse_topological<V>(
+        &self,
+        init: impl Fn(&T) -> V,
+        combine: impl Fn(&T, Vec<&V>) -> V,
+    ) -> BTreeMap<&T, V> {
+        let mut values: BTreeMap<&T, V> = BTreeMap::new();
+        // Every vertex's adjacent vertices have been visited before it, which
+        // a reverse topological order guarantees.
+        for v in self.topological_sort().into_iter().rev() {
+            let adj = &self.vertices[v];
+            let value = if adj.is_empty() {
+                init(v)
+            } else {
+                combine(v, adj.iter().map(|y| &values[y]).collect())
+            };
+            values.insert(v, value);
+        }
+        values
+    }
+}
+
+impl<T> BuildIntervalLabels<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn build_interval_labels(&self) -> IntervalLabels<'_, T> {
+        IntervalLabels::new(self)
+    }
+}
+
+impl<T> Mount<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn mount<U>(
+        &mut self,
+        sub: BTreeDAG<U>,
+        prefix: impl Fn(U) -> T,
+        boundary: impl IntoIterator<Item = (T, T)>,
+    ) -> Result<(), Self::Error>
+    where
+        U: Ord + Clone,
+    {
+        checked_mutation!(self, {
+            // Build the result separately, so the dag is unchanged on error.
+            let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self.vertices.clone();
+            for (u, adj_u) in sub.vertices.into_iter() {
+                let adj: BTreeSet<T> = into_owned(adj_u).into_iter().map(&prefix).collect();
+                if vertices.insert(prefix(u), Arc::new(adj)).is_some() {
+                    return Err(Error::VertexExists);
+                }
+            }
+            for (x, y) in boundary.into_iter() {
+                if !vertices.contains_key(&y) {
+                    return Err(Error::VertexDoesNotExist);
+                }
+                let adj_x = vertices.get_mut(&x).ok_or(Error::VertexDoesNotExist)?;
+                Arc::make_mut(adj_x).insert(y);
+            }
+            if !is_acyclic(&vertices) {
+                return Err(Error::EdgeExists);
+            }
+            self.vertices = vertices;
+            Ok(())
+        })
+    }
+}
+
+impl<T> FindMatches<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn find_matches<'a, P>(
+        &'a self,
+        pattern: &'a BTreeDAG<P>,
+        matcher: impl Fn(&P, &T) -> bool,
+    ) -> Vec<BTreeMap<&'a P, &'a T>>
+    where
+        P: Ord,
+    {
+        let pattern_vertices: Vec<&P> = pattern.vertices.keys().collect();
+        let vertices: Vec<&T> = self.vertices.keys().collect();
+        matching::find_matches(&index_adjacency(pattern), &index_adjacency(self), |p, t| {
+            matcher(pattern_vertices[p], vertices[t])
+        })
+        .into_iter()
+        .map(|mapping| {
+            mapping
+                .into_iter()
+                .enumerate()
+                .map(|(p, t)| (pattern_vertices[p], vertices[t]))
+                .collect()
+        })
+        .collect()
+    }
+}
+
+/// Returns the adjacency lists of the dag by vertex index, in ascending order.
+pub(crate) fn index_adjacency<T>(dag: &BTreeDAG<T>) -> Vec<Vec<usize>>
+where
+    T: Ord,
+{
+    let vertices: Vec<&T> = dag.vertices.keys().collect();
+    dag.vertices
+        .values()
+        // Every adjacent vertex is a vertex, so we can safely unwrap.
+        .map(|adj| {
+            adj.iter()
+                .map(|y| vertices.binary_search(&y).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+impl<T> QueryVertices<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn query(&self) -> Query<'_, T> {
+        Query::new(self)
+    }
+}
+
+impl<T> MergeConcurrent<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn merge_concurrent<P>(
+        &mut self,
+        other: &Self,
+        priority: impl Fn(&T, &T) -> P,
+    ) -> BTreeSet<(T, T)>
+    where
+        P: Ord,
+    {
+        checked_mutation!(self, {
+            let union: BTreeSet<(&T, &T)> =
+                self.edges_sorted().chain(other.edges_sorted()).collect();
+            let mut edges: Vec<(core::cmp::Reverse<P>, &T, &T)> = union
+                .into_iter()
+                .map(|(x, y)| (core::cmp::Reverse(priority(x, y)), x, y))
+                .collect();
+            edges.sort_unstable();
+            // The merged dag is built without a traversal depth limit, so no
+            // edge is dropped for exceeding it, and then takes on this dag's.
+            let mut merged: BTreeDAG<T> = BTreeDAG::new();
+            for x in self.vertices.keys().chain(other.vertices.keys()) {
+                merged
+                    .vertices
+                    .entry(x.clone())
+                    .or_insert_with(|| Arc::new(BTreeSet::new()));
+            }
+            let mut dropped: BTreeSet<(T, T)> = BTreeSet::new();
+            for (_, x, y) in edges.into_iter() {
+                // Both vertices exist, so the only possible error is a cycle.
+                if merged.add_edge(x.clone(), y.clone()).is_err() {
+                    dropped.insert((x.clone(), y.clone()));
+                }
+            }
+            *self = self.derive(merged.vertices);
+            dropped
+        })
+    }
+}
+
+impl<T> CollectUnreachable<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn collect_unreachable(&mut self, roots: &BTreeSet<T>) -> BTreeSet<T> {
+        checked_mutation!(self, {
+            let mut reachable: BTreeSet<T> = BTreeSet::new();
+            let mut stack: Vec<&T> = roots
+                .iter()
+                .filter_map(|x| self.vertices.get_key_value(x).map(|(x, _)| x))
+                .collect();
+            while let Some(v) = stack.pop() {
+                if reachable.insert(v.clone()) {
+                    stack.extend(self.vertices[v].iter());
+                }
+            }
+            let mut unreachable: BTreeSet<T> = BTreeSet::new();
+            self.vertices.retain(|x, _| {
+                if reachable.contains(x) {
+                    return true;
+                }
+                unreachable.insert(x.clone());
+                false
+            });
+            unreachable
+        })
+    }
+}
+
+impl<T> View<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn view<'a>(
+        &'a self,
+        vertex_pred: impl Fn(&T) -> bool + 'a,
+        edge_pred: impl Fn(&T, &T) -> bool + 'a,
+    ) -> DagView<'a, T> {
+        DagView::new(self, Box::new(vertex_pred), Box::new(edge_pred))
+    }
+}
+
+impl<T> Explain<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn explain_vertex(&self, error: Error, x: &T) -> ContextError<T> {
+        ContextError {
+            error,
+            context: ErrorContext::Vertex(x.clone()),
+        }
+    }
+
+    fn explain_edge(&self, error: Error, x: &T, y: &T) -> ContextError<T> {
+        let context = match error {
+            Error::VertexDoesNotExist if !self.vertices.contains_key(x) => {
+                ErrorContext::Vertex(x.clone())
+            }
+            Error::VertexDoesNotExist if !self.vertices.contains_key(y) => {
+                ErrorContext::Vertex(y.clone())
+            }
+            _ => ErrorContext::Edge(x.clone(), y.clone()),
+        };
+        ContextError { error, context }
+    }
+}
+
+impl<T> TryMutate<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn try_add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, ContextError<T>> {
+        self.add_edge(x.clone(), y.clone())
+            .map_err(|e| self.explain_edge(e, &x, &y))
+    }
+
+    fn try_remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, ContextError<T>> {
+        self.remove_edge(x.clone(), y.clone())
+            .map_err(|e| self.explain_edge(e, &x, &y))
+    }
+
+    fn try_remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, ContextError<T>> {
+        self.remove_vertex(x.clone())
+            .map_err(|e| self.explain_vertex(e, &x))
+    }
+
+    fn try_prune(&mut self, x: T) -> Result<(), ContextError<T>> {
+        self.prune(x.clone())
+            .map_err(|e| self.explain_vertex(e, &x))
+    }
+}
+
+impl<T> FindVertices<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn find_vertices<'a>(&'a self, pred: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.vertices.keys().filter(move |v| pred(v))
+    }
+
+    fn find_edges<'a>(
+        &'a self,
+        pred: impl Fn(&T, &T) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a,
+    {
+        self.vertices
+            .iter()
+            .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x, y)))
+            .filter(move |(x, y)| pred(x, y))
+    }
+}
+
+impl<T> VertexRange<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn range<'a, Q, R>(&'a self, range: R) -> impl Iterator<Item = &'a T>
+    where
+        T: Borrow<Q> + 'a,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        self.vertices.range(range).map(|(v, _)| v)
+    }
+
+    fn connections_range<'a, Q, R>(&'a self, x: &T, range: R) -> Option<impl Iterator<Item = &'a T>>
+    where
+        T: Borrow<Q> + 'a,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Some(self.vertices.get::<T>(x)?.range(range))
+    }
+}
+
+impl<T> OrderedVertices<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn first_vertex(&self) -> Option<&T> {
+        self.vertices.keys().next()
+    }
+
+    fn last_vertex(&self) -> Option<&T> {
+        self.vertices.keys().next_back()
+    }
+
+    fn first_root(&self) -> Option<&T> {
+        let targets: BTreeSet<&T> = self.vertices.values().flat_map(|adj| adj.iter()).collect();
+        self.vertices.keys().find(|v| !targets.contains(v))
+    }
+
+    fn pop_first_root(&mut self) -> Option<(T, BTreeSet<T>)> {
+        checked_mutation!(self, {
+            let root: T = self.first_root()?.clone();
+            // A root has no incoming edges, so it can be removed without
+            // touching any other adjacency list.
+            self.vertices
+                .remove_entry(&root)
+                .map(|(v, adj)| (v, into_owned(adj)))
+        })
+    }
+}
+
+impl<T> SplitOffRange<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn split_off_range(&mut self, at: &T) -> (BTreeDAG<T>, BTreeSet<(T, T)>) {
+        checked_mutation!(self, {
+            let upper_vertices = self.vertices.split_off(at);
+            let mut upper: BTreeDAG<T> = self.derive(upper_vertices);
+            let mut dropped: BTreeSet<(T, T)> = BTreeSet::new();
+            // Edges from the lower half point at vertices less than at, and edges
+            // from the upper half at vertices greater than or equal to at.
+            for (x, adj_x) in self.vertices.iter_mut() {
+                if adj_x.range(at..).next().is_some() {
+                    let crossing: BTreeSet<T> = Arc::make_mut(adj_x).split_off(at);
+                    dropped.extend(crossing.into_iter().map(|y| (x.clone(), y)));
+                }
+            }
+            for (x, adj_x) in upper.vertices.iter_mut() {
+                if adj_x.range(..at).next().is_some() {
+                    let adj_x = Arc::make_mut(adj_x);
+                    let kept: BTreeSet<T> = adj_x.split_off(at);
+                    let crossing: BTreeSet<T> = core::mem::replace(adj_x, kept);
+                    dropped.extend(crossing.into_iter().map(|y| (x.clone(), y)));
+                }
+            }
+            (upper, dropped)
+        })
+    }
+}
+
+impl<T> Append<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn append(&mut self, other: &mut BTreeDAG<T>) -> Result<(), Self::Error> {
+        checked_mutation!(self, {
+            let ranges_overlap = match (
+                self.vertices.keys().next(),
+                self.vertices.keys().next_back(),
+            ) {
+                (Some(first), Some(last)) => other
+                    .vertices
+                    .keys()
+                    .next()
+                    .zip(other.vertices.keys().next_back())
+                    .is_some_and(|(other_first, other_last)| {
+                        other_first <= last && first <= other_last
+                    }),
+                _ => false,
+            };
+            // Only if the key ranges overlap must each vertex be checked.
+            if ranges_overlap && other.vertices.keys().any(|v| self.vertices.contains_key(v)) {
+                return Err(Error::VertexExists);
+            }
+            self.vertices.append(&mut other.vertices);
+            Ok(())
+        })
+    }
+}
+
+impl<T> ConnectAcross<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn connect_across(
+        &mut self,
+        edges: impl IntoIterator<Item = (T, T)>,
+    ) -> Result<(), Self::Error> {
+        checked_mutation!(self, {
+            let edges: Vec<(T, T)> = edges.into_iter().collect();
+            if edges
+                .iter()
+                .any(|(x, y)| !self.vertices.contains_key(x) || !self.vertices.contains_key(y))
+            {
+                return Err(Error::VertexDoesNotExist);
+            }
+            let mut added: BTreeMap<&T, Vec<&T>> = BTreeMap::new();
+            for (x, y) in edges.iter() {
+                added.entry(x).or_default().push(y);
+            }
+            // Every new cycle would pass through the source of a new edge, so a
+            // single depth first search from the sources finds it. A vertex is in
+            // progress while it is on the stack, and visited once it is reached.
+            let successors = |v: &T| {
+                self.vertices[v]
+                    .iter()
+                    .chain(added.get(v).into_iter().flatten().copied())
+            };
+            let mut visited: BTreeSet<&T> = BTreeSet::new();
+            let mut in_progress: BTreeSet<&T> = BTreeSet::new();
+            for source in added.keys() {
+                if visited.contains(source) {
+                    continue;
+                }
+                let mut stack = vec![(*source, successors(source))];
+                in_progress.insert(source);
+                while let Some((v, adj_v)) = stack.last_mut() {
+                    match adj_v.next() {
+                        Some(w) => {
+                            if in_progress.contains(w) {
+                                return Err(Error::EdgeExists);
+                            }
+                            if visited.insert(w) {
+                                in_progress.insert(w);
+                                stack.push((w, successors(w)));
+                            }
+                        }
+                        None => {
+                            in_progress.remove(*v);
+                            visited.insert(*v);
+                            stack.pop();
+                        }
+                    }
+                }
+            }
+            for (x, y) in edges {
+                // We have already verified x is in vertices, so we can safely
+                // unwrap.
+                Arc::make_mut(self.vertices.get_mut(&x).unwrap()).insert(y);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// How `FromAdjacency` treats a vertex which is referenced by an edge, but is
+/// not a key of the adjacency list.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MissingVertices {
+    Reject,
+    Create,
+}
+
+impl<T> FromAdjacency<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn from_adjacency(
+        adjacency: BTreeMap<T, BTreeSet<T>>,
+        missing: MissingVertices,
+    ) -> Result<Self, Self::Error> {
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = BTreeMap::new();
+        let mut referenced: BTreeSet<T> = BTreeSet::new();
+        for (x, adj_x) in adjacency {
+            referenced.extend(adj_x.iter().cloned());
+            vertices.insert(x, Arc::new(adj_x));
+        }
+        for y in referenced {
+            if let btree_map::Entry::Vacant(entry) = vertices.entry(y) {
+                if missing == MissingVertices::Reject {
+                    return Err(Error::VertexDoesNotExist);
+                }
+                entry.insert(Arc::new(BTreeSet::new()));
+            }
+        }
+        if !is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+/// Every vertex referenced by an edge must be a key of the adjacency list, and
+/// the edges must not contain a cycle.
+impl<T> TryFrom<BTreeMap<T, BTreeSet<T>>> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn try_from(adjacency: BTreeMap<T, BTreeSet<T>>) -> Result<Self, Self::Error> {
+        Self::from_adjacency(adjacency, MissingVertices::Reject)
+    }
+}
+
+impl<T> ToEdgeList<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn to_edge_list(&self) -> Vec<(T, T)> {
+        self.vertices
+            .iter()
+            .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x.clone(), y.clone())))
+            .collect()
+    }
+
+    fn to_vertex_list(&self) -> Vec<T> {
+        self.vertices.keys().cloned().collect()
+    }
+}
+
+impl<T> DegreeHistogram<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn out_degree_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+        for adj in self.vertices.values() {
+            *histogram.entry(adj.len()).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    fn in_degree_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut in_degrees: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+        for d in in_degrees.into_values() {
+            *histogram.entry(d).or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+/// Returns the k vertices with the greatest counts, by descending count and
+/// then in ascending order.
+fn top_k<T>(mut counts: Vec<(&T, usize)>, k: usize) -> Vec<(&T, usize)>
+where
+    T: Ord,
+{
+    counts.sort_by(|(x, a), (y, b)| b.cmp(a).then_with(|| x.cmp(y)));
+    counts.truncate(k);
+    counts
+}
+
+impl<T> TopK<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn top_k_by_out_degree(&self, k: usize) -> Vec<(&T, usize)> {
+        top_k(
+            self.vertices
+                .iter()
+                .map(|(v, adj)| (v, adj.len()))
+                .collect(),
+            k,
+        )
+    }
+
+    fn top_k_by_descendant_count(&self, k: usize) -> Vec<(&T, usize)> {
+        // The number of vertices whose membership in each set of descendants
+        // is tracked at once, bounding the bitsets to 64 words per vertex.
+        const CHUNK: usize = 4096;
+        let vertices: Vec<&T> = self.vertices.keys().collect();
+        let adjacency: Vec<Vec<usize>> = index_adjacency(self);
+        // Every sorted vertex is a vertex, so we can safely unwrap.
+        let order: Vec<usize> = self
+            .topological_sort()
+            .into_iter()
+            .rev()
+            .map(|v| vertices.binary_search(&v).unwrap())
+            .collect();
+        let mut counts: Vec<usize> = vec![0; vertices.len()];
+        for start in (0..vertices.len()).step_by(CHUNK) {
+            let chunk = start..(start + CHUNK).min(vertices.len());
+            let words: usize = chunk.len().div_ceil(64);
+            // The descendants of each vertex within the chunk as a bitset,
+            // built from the descendants of its adjacent vertices in reverse
+            // topological order.
+            let mut descendants: Vec<Vec<u64>> = vec![Vec::new(); vertices.len()];
+            for &i in order.iter() {
+                let mut bits: Vec<u64> = vec![0; words];
+                for &j in adjacency[i].iter() {
+                    if chunk.contains(&j) {
+                        bits[(j - start) / 64] |= 1 << ((j - start) % 64);
+                    }
+                    for (word, adj_word) in bits.iter_mut().zip(descendants[j].iter()) {
+                        *word |= adj_word;
+                    }
+                }
+                counts[i] += bits.iter().map(|w| w.count_ones() as usize).sum::<usize>();
+                descendants[i] = bits;
+            }
+        }
+        top_k(vertices.into_iter().zip(counts).collect(), k)
+    }
+}
+
+impl<T> Impact<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn impact(&self, changed: &BTreeSet<T>) -> BTreeSet<&T> {
+        let mut affected: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = changed
+            .iter()
+            .filter_map(|x| self.vertices.get(x))
+            .flat_map(|adj_x| adj_x.iter())
+            .collect();
+        while let Some(v) = stack.pop() {
+            if affected.insert(v) {
+                stack.extend(self.vertices[v].iter());
+            }
+        }
+        affected
+    }
+}
+
+impl<T> MinimalCover<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn minimal_cover(&self, targets: &BTreeSet<T>) -> Result<BTreeSet<&T>, Self::Error> {
+        let mut predecessors: BTreeMap<&T, Vec<&T>> = BTreeMap::new();
+        for (x, adj_x) in self.vertices.iter() {
+            for y in adj_x.iter() {
+                predecessors.entry(y).or_default().push(x);
+            }
+        }
+        // The targets reached by each root, found by searching backwards from
+        // each target.
+        let mut reached: BTreeMap<&T, BTreeSet<&T>> = BTreeMap::new();
+        for target in targets.iter() {
+            let (target, _) = self
+                .vertices
+                .get_key_value(target)
+                .ok_or(Error::VertexDoesNotExist)?;
+            let mut visited: BTreeSet<&T> = BTreeSet::new();
+            let mut stack: Vec<&T> = vec![target];
+            while let Some(v) = stack.pop() {
+                if visited.insert(v) {
+                    match predecessors.get(v) {
+                        Some(xs) => stack.extend(xs.iter()),
+                        None => {
+                            reached.entry(v).or_default().insert(target);
+                        }
+                    }
+                }
+            }
+        }
+        let mut cover: BTreeSet<&T> = BTreeSet::new();
+        let mut uncovered: usize = targets.len();
+        while uncovered > 0 {
+            // Every target is reached by a root, so while any target is
+            // uncovered some root reaches it, and we can safely unwrap.
+            let (root, covered) = reached
+                .iter()
+                .max_by(|(x, a), (y, b)| a.len().cmp(&b.len()).then_with(|| y.cmp(x)))
+                .map(|(root, covered)| (*root, covered.clone()))
+                .unwrap();
+            cover.insert(root);
+            uncovered -= covered.len();
+            reached.remove(root);
+            for other in reached.values_mut() {
+                other.retain(|t| !covered.contains(t));
+            }
+        }
+        Ok(cover)
+    }
+}
+
+impl<T> SuggestLayout<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn suggest_layout(&self) -> LayoutHints<'_, T> {
+        LayoutHints::new(self)
+    }
+}
+
+impl<T> InternAdjacency<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn intern_adjacency(&mut self) -> usize {
+        checked_mutation!(self, {
+            // Shared sets compare by their contents, so the first vertex with
+            // each distinct set provides the copy shared by the others.
+            let mut interned: BTreeSet<Arc<BTreeSet<T>>> = BTreeSet::new();
+            let mut replaced: usize = 0;
+            for adj in self.vertices.values_mut() {
+                match interned.get(adj) {
+                    Some(shared) => {
+                        if !Arc::ptr_eq(shared, adj) {
+                            *adj = Arc::clone(shared);
+                            replaced += 1;
+                        }
+                    }
+                    None => {
+                        interned.insert(Arc::clone(adj));
+                    }
+                }
+            }
+            replaced
+        })
+    }
+}
+
+impl<T> Navigate<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn cursor(&self, x: &T) -> Option<Cursor<'_, T>> {
+        let (x, _) = self.vertices.get_key_value(x)?;
+        Some(Cursor::new(self, x))
+    }
+}
+
+impl<T> TraverseWith<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn dfs_with(&self, x: &T, order: impl TraversalOrder<T>) -> Option<Vec<&T>> {
+        let (x, _) = self.vertices.get_key_value(x)?;
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut preorder: Vec<&T> = Vec::new();
+        let mut stack: Vec<&T> = vec![x];
+        while let Some(v) = stack.pop() {
+            if visited.insert(v) {
+                preorder.push(v);
+                let mut adj: Vec<&T> = self.vertices[v]
+                    .iter()
+                    .filter(|w| !visited.contains(w))
+                    .collect();
+                order::sort_by_order(&mut adj, &order);
+                // Push in reverse, so the first adjacent vertex is visited
+                // first.
+                stack.extend(adj.into_iter().rev());
+            }
+        }
+        Some(preorder)
+    }
+
+    fn bfs_with(&self, x: &T, order: impl TraversalOrder<T>) -> Option<Vec<&T>> {
+        let (x, _) = self.vertices.get_key_value(x)?;
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        visited.insert(x);
+        let mut queue: VecDeque<&T> = VecDeque::new();
+        queue.push_back(x);
+        let mut levels: Vec<&T> = Vec::new();
+        while let Some(v) = queue.pop_front() {
+            levels.push(v);
+            let mut adj: Vec<&T> = self.vertices[v]
+                .iter()
+                .filter(|w| !visited.contains(w))
+                .collect();
+            order::sort_by_order(&mut adj, &order);
+            for w in adj {
+                visited.insert(w);
+                queue.push_back(w);
+            }
+        }
+        Some(levels)
+    }
+
+    fn topological_sort_with(&self, order: impl TraversalOrder<T>) -> Vec<&T> {
+        let mut in_degrees: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        // Kahn's algorithm, always removing the ready vertex which the order
+        // visits first.
+        let mut ready: BinaryHeap<Ready<'_, '_, T, _>> = in_degrees
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(v, _)| Ready {
+                vertex: *v,
+                order: &order,
+            })
+            .collect();
+        let mut sorted: Vec<&T> = Vec::with_capacity(self.vertices.len());
+        while let Some(Ready { vertex, .. }) = ready.pop() {
+            sorted.push(vertex);
+            for adj in self.vertices[vertex].iter() {
+                let d = in_degrees.get_mut(adj).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    ready.push(Ready {
+                        vertex: adj,
+                        order: &order,
+                    });
+                }
+            }
+        }
+        sorted
+    }
+}
+
+/// How `Repair` treats an edge pointing at a vertex which does not exist.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DanglingEdges {
+    /// Create the vertex the edge points at.
+    Create,
+    /// Drop the edge.
+    Drop,
+}
+
+/// `RepairReport` lists the changes `Repair` made to a dag.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct RepairReport<T>
+where
+    T: Ord,
+{
+    /// The vertices created for dangling edges.
+    pub created_vertices: BTreeSet<T>,
+    /// The edges dropped for dangling, or to break cycles.
+    pub dropped_edges: BTreeSet<(T, T)>,
+}
+
+impl<T> RepairReport<T>
+where
+    T: Ord,
+{
+    /// Returns true if the dag needed no repair.
+    pub fn is_empty(&self) -> bool {
+        self.created_vertices.is_empty() && self.dropped_edges.is_empty()
+    }
+}
+
+impl<T> Repair<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn repair(&mut self, dangling: DanglingEdges) -> RepairReport<T> {
+        checked_mutation!(self, {
+            let mut report: RepairReport<T> = RepairReport {
+                created_vertices: BTreeSet::new(),
+                dropped_edges: BTreeSet::new(),
+            };
+            let missing: Vec<(T, T)> = self
+                .vertices
+                .iter()
+                .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x, y)))
+                .filter(|(_, y)| !self.vertices.contains_key(*y))
+                .map(|(x, y)| (x.clone(), y.clone()))
+                .collect();
+            for (x, y) in missing {
+                match dangling {
+                    DanglingEdges::Create => {
+                        self.vertices.entry(y.clone()).or_default();
+                        report.created_vertices.insert(y);
+                    }
+                    DanglingEdges::Drop => {
+                        // The edge was found on x, so x exists.
+                        Arc::make_mut(self.vertices.get_mut(&x).unwrap()).remove(&y);
+                        report.dropped_edges.insert((x, y));
+                    }
+                }
+            }
+            if !is_acyclic(&self.vertices) {
+                let edges: Vec<(T, T)> = self
+                    .vertices
+                    .iter()
+                    .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x.clone(), y.clone())))
+                    .collect();
+                for (x, y) in BTreeDAG::feedback_arc_set(edges) {
+                    Arc::make_mut(self.vertices.get_mut(&x).unwrap()).remove(&y);
+                    report.dropped_edges.insert((x, y));
+                }
+            }
+            report
+        })
+    }
+}
+
+impl<T> DistancesFrom<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn distances_from(&self, sources: &BTreeSet<T>) -> BTreeMap<&T, usize> {
+        let mut distances: BTreeMap<&T, usize> = BTreeMap::new();
+        let mut queue: VecDeque<&T> = VecDeque::new();
+        for (x, _) in sources
+            .iter()
+            .filter_map(|x| self.vertices.get_key_value(x))
+        {
+            distances.insert(x, 0);
+            queue.push_back(x);
+        }
+        // A single breadth first search from every source at once, so each
+        // vertex is first found at its shortest distance from any source.
+        while let Some(v) = queue.pop_front() {
+            let distance: usize = distances[v] + 1;
+            for adj in self.vertices[v].iter() {
+                if let btree_map::Entry::Vacant(entry) = distances.entry(adj) {
+                    entry.insert(distance);
+                    queue.push_back(adj);
+                }
+            }
+        }
+        distances
+    }
+}
+
+/// For each vertex, its best paths to the target, each as its number of
+/// vertices and the next vertex with the position of the rest of the path
+/// among the best paths of the next vertex.
+type BestPaths<'a, T> = BTreeMap<&'a T, Vec<(usize, Option<(&'a T, usize)>)>>;
+
+impl<T> BTreeDAG<T>
+where
+    T: Ord,
+{
+    /// Returns the k shortest, or longest, paths from x to y, by dynamic
+    /// programming over the vertices reachable from x in reverse topological
+    /// order. Only the k best paths from each vertex can extend to one of the
+    /// k best paths from x.
+    fn k_paths(&self, x: &T, y: &T, k: usize, longest: bool) -> Result<Vec<Path<'_, T>>, Error> {
+        if !self.vertices.contains_key(y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let (x, _) = self
+            .vertices
+            .get_key_value(x)
+            .ok_or(Error::VertexDoesNotExist)?;
+        let mut reachable: BTreeSet<&T> = self.descendants_of(x);
+        reachable.insert(x);
+        let mut best: BestPaths<'_, T> = BTreeMap::new();
+        for v in self.topological_sort().into_iter().rev() {
+            if !reachable.contains(v) {
+                continue;
+            }
+            let mut paths: Vec<(usize, Option<(&T, usize)>)> = if v == y {
+                vec![(1, None)]
+            } else {
+                self.vertices[v]
+                    .iter()
+                    .filter_map(|w| best.get(w).map(|paths_w| (w, paths_w)))
+                    .flat_map(|(w, paths_w)| {
+                        paths_w
+                            .iter()
+                            .enumerate()
+                            .map(move |(i, (len, _))| (len + 1, Some((w, i))))
+                    })
+                    .collect()
+            };
+            // Ordering equal length paths by their next vertex, then by their
+            // position among its paths, orders them lexicographically.
+            if longest {
+                paths.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+            } else {
+                paths.sort();
+            }
+            paths.truncate(k);
+            if !paths.is_empty() {
+                best.insert(v, paths);
+            }
+        }
+        let paths_x = match best.get(x) {
+            Some(paths_x) => paths_x,
+            None => return Ok(Vec::new()),
+        };
+        Ok(paths_x
+            .iter()
+            .map(|(_, next)| {
+                let mut vertices: Vec<&T> = vec![x];
+                let mut next = *next;
+                while let Some((w, i)) = next {
+                    vertices.push(w);
+                    next = best[w][i].1;
+                }
+                Path::new_unchecked(vertices)
+            })
+            .collect())
+    }
+}
+
+impl<T> KPaths<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn k_shortest_paths(&self, x: &T, y: &T, k: usize) -> Result<Vec<Path<'_, T>>, Self::Error> {
+        self.k_paths(x, y, k, false)
+    }
+
+    fn k_longest_paths(&self, x: &T, y: &T, k: usize) -> Result<Vec<Path<'_, T>>, Self::Error> {
+        self.k_paths(x, y, k, true)
+    }
+}
+
+impl<T> Roots<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn roots(&self) -> BTreeSet<&T> {
+        let mut roots: BTreeSet<&T> = self.vertices.keys().collect();
+        for y in self.vertices.values().flat_map(|adj| adj.iter()) {
+            roots.remove(y);
+        }
+        roots
+    }
+}
+
+/// The longest totals are found by a pass in reverse topological order, so
+/// the total of every successor is known before its predecessors.
+impl<T> CriticalPath<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn critical_path(&self, durations: impl Fn(&T) -> u64) -> Result<Path<'_, T>, Self::Error> {
+        self.try_critical_path(durations).ok_or(Error::EmptyGraph)
+    }
+
+    fn try_critical_path(&self, durations: impl Fn(&T) -> u64) -> Option<Path<'_, T>> {
+        // The total duration of the longest path starting at each vertex.
+        let mut totals: BTreeMap<&T, u64> = BTreeMap::new();
+        for v in self.topological_sort().into_iter().rev() {
+            let rest = self.vertices[v]
+                .iter()
+                .map(|y| totals[y])
+                .max()
+                .unwrap_or(0);
+            totals.insert(v, durations(v) + rest);
+        }
+        // Among the longest, prefer the least vertex.
+        let longest = |a: &&T, b: &&T| totals[*a].cmp(&totals[*b]).then_with(|| b.cmp(a));
+        let mut path: Vec<&T> = Vec::new();
+        let mut next: Option<&T> = self.vertices.keys().max_by(longest);
+        while let Some(v) = next {
+            path.push(v);
+            next = self.vertices[v].iter().max_by(longest);
+        }
+        if path.is_empty() {
+            return None;
+        }
+        Some(Path::new_unchecked(path))
+    }
+}
+
+impl<T> TryAdjacent<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn try_adjacent(&self, x: &T, y: &T) -> Option<bool> {
+        if !self.vertices.contains_key(y) {
+            return None;
+        }
+        self.vertices.get(x).map(|adj_x| adj_x.contains(y))
     }
 }
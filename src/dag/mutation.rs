@@ -0,0 +1,101 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Merge, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `Mutation` is a record of a single mutating call on a `BTreeDAG`, which can
+/// be applied to a dag to repeat the call.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Mutation<T> {
+    AddVertex(T),
+    AddEdge(T, T),
+    RemoveEdge(T, T),
+    RemoveVertex(T),
+    Prune(T),
+}
+
+impl<T> Mutation<T>
+where
+    T: Ord + Clone,
+{
+    /// Applies the mutation to the dag, returning any error the
+    /// corresponding call returns.
+    pub fn apply(self, dag: &mut BTreeDAG<T>) -> Result<(), Error> {
+        match self {
+            Mutation::AddVertex(x) => {
+                dag.add_vertex(x);
+            }
+            Mutation::AddEdge(x, y) => {
+                dag.add_edge(x, y)?;
+            }
+            Mutation::RemoveEdge(x, y) => {
+                dag.remove_edge(x, y)?;
+            }
+            Mutation::RemoveVertex(x) => {
+                dag.remove_vertex(x)?;
+            }
+            Mutation::Prune(x) => {
+                dag.prune(x)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `MutationOp` is an operation of a generated sequence of operations on a
+/// `BTreeDAG`, such as those of the fuzz targets. Beyond the calls recorded by
+/// `Mutation`, it can merge another dag into the dag.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MutationOp<T>
+where
+    T: Ord,
+{
+    AddVertex(T),
+    AddEdge(T, T),
+    RemoveEdge(T, T),
+    RemoveVertex(T),
+    Prune(T),
+    Merge(BTreeDAG<T>),
+}
+
+impl<T> MutationOp<T>
+where
+    T: Ord + Clone,
+{
+    /// Applies the operation to the dag, returning any error the
+    /// corresponding call returns. A failed operation leaves the dag
+    /// unchanged.
+    pub fn apply(self, dag: &mut BTreeDAG<T>) -> Result<(), Error> {
+        match self {
+            MutationOp::AddVertex(x) => Mutation::AddVertex(x).apply(dag),
+            MutationOp::AddEdge(x, y) => Mutation::AddEdge(x, y).apply(dag),
+            MutationOp::RemoveEdge(x, y) => Mutation::RemoveEdge(x, y).apply(dag),
+            MutationOp::RemoveVertex(x) => Mutation::RemoveVertex(x).apply(dag),
+            MutationOp::Prune(x) => Mutation::Prune(x).apply(dag),
+            MutationOp::Merge(other) => {
+                let mut merged: BTreeDAG<T> = dag.merge(&other)?;
+                merged.max_traversal_depth = dag.max_traversal_depth;
+                *dag = merged;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T> From<Mutation<T>> for MutationOp<T>
+where
+    T: Ord,
+{
+    fn from(mutation: Mutation<T>) -> Self {
+        match mutation {
+            Mutation::AddVertex(x) => MutationOp::AddVertex(x),
+            Mutation::AddEdge(x, y) => MutationOp::AddEdge(x, y),
+            Mutation::RemoveEdge(x, y) => MutationOp::RemoveEdge(x, y),
+            Mutation::RemoveVertex(x) => MutationOp::RemoveVertex(x),
+            Mutation::Prune(x) => MutationOp::Prune(x),
+        }
+    }
+}
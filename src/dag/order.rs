@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// `TraversalOrder` decides which of two vertices a traversal visits first,
+/// where the traversal could visit either, such as the adjacent vertices of a
+/// vertex in a depth or breadth first search, or the vertices with no
+/// remaining incoming edges in a topological sort. Vertices which compare
+/// equal are visited in ascending order, so every traversal stays
+/// deterministic.
+///
+/// `KeyOrder` visits the lesser vertex first, like the traversals which take
+/// no order, and any function `Fn(&T, &T) -> Ordering` is an order.
+pub trait TraversalOrder<T> {
+    fn cmp(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// `KeyOrder` visits vertices in ascending order.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct KeyOrder;
+
+impl<T> TraversalOrder<T> for KeyOrder
+where
+    T: Ord,
+{
+    fn cmp(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T, F> TraversalOrder<T> for F
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn cmp(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Compares the vertices by the order, then in ascending order.
+pub(crate) fn compare<T>(order: &impl TraversalOrder<T>, a: &T, b: &T) -> Ordering
+where
+    T: Ord,
+{
+    order.cmp(a, b).then_with(|| a.cmp(b))
+}
+
+/// Sorts the vertices in the order they should be visited.
+pub(crate) fn sort_by_order<T>(vertices: &mut Vec<&T>, order: &impl TraversalOrder<T>)
+where
+    T: Ord,
+{
+    vertices.sort_by(|a, b| compare(order, *a, *b));
+}
+
+/// `Ready` is a vertex in a max heap of vertices, where the vertex which
+/// should be visited first is the greatest.
+pub(crate) struct Ready<'a, 'o, T, O> {
+    pub(crate) vertex: &'a T,
+    pub(crate) order: &'o O,
+}
+
+impl<'a, 'o, T, O> Ord for Ready<'a, 'o, T, O>
+where
+    T: Ord,
+    O: TraversalOrder<T>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(self.order, other.vertex, self.vertex)
+    }
+}
+
+impl<'a, 'o, T, O> PartialOrd for Ready<'a, 'o, T, O>
+where
+    T: Ord,
+    O: TraversalOrder<T>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, 'o, T, O> PartialEq for Ready<'a, 'o, T, O>
+where
+    T: Ord,
+    O: TraversalOrder<T>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, 'o, T, O> Eq for Ready<'a, 'o, T, O>
+where
+    T: Ord,
+    O: TraversalOrder<T>,
+{
+}
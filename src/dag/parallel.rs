@@ -0,0 +1,118 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `ParFromEdges` constructs a dag from a list of edges (x, y) like `FromEdges`,
+/// but builds the adjacency lists and validates them for acyclicity on the
+/// rayon thread pool, which pays off for large imports.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, ParFromEdges, Vertices};
+/// use btree_dag::Error;
+/// let dag: BTreeDAG<String> = BTreeDAG::par_from_edges(vec![
+///     (String::from("origin"), String::from("waypoint")),
+///     (String::from("waypoint"), String::from("destination")),
+/// ])
+/// .unwrap();
+/// assert_eq!(dag.vertices().len(), 3);
+///
+/// let err: Error = BTreeDAG::par_from_edges(vec![
+///     (String::from("origin"), String::from("destination")),
+///     (String::from("destination"), String::from("origin")),
+/// ])
+/// .unwrap_err();
+/// assert_eq!(err, Error::EdgeExists);
+/// ```
+pub trait ParFromEdges<T>: Sized {
+    type Error;
+    fn par_from_edges(edges: impl IntoIterator<Item = (T, T)>) -> Result<Self, Self::Error>;
+}
+
+impl<T> ParFromEdges<T> for BTreeDAG<T>
+where
+    T: Ord + Clone + Send + Sync,
+{
+    type Error = Error;
+    fn par_from_edges(edges: impl IntoIterator<Item = (T, T)>) -> Result<Self, Self::Error> {
+        // Sort the edges, so the adjacency list of each vertex is a run of
+        // edges, and each set can be built from sorted vertices.
+        let mut edges: Vec<(T, T)> = edges.into_iter().collect();
+        edges.par_sort_unstable();
+        edges.dedup();
+        let mut keys: Vec<T> = edges
+            .par_iter()
+            .flat_map_iter(|(x, y)| [x.clone(), y.clone()])
+            .collect();
+        keys.par_sort_unstable();
+        keys.dedup();
+        let runs: Vec<(&T, BTreeSet<T>)> = edges
+            .par_chunk_by(|a, b| a.0 == b.0)
+            .map(|run| (&run[0].0, run.iter().map(|(_, y)| y.clone()).collect()))
+            .collect();
+        let mut runs = runs.into_iter().peekable();
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = keys
+            .into_iter()
+            .map(|v| {
+                let adj: BTreeSet<T> = match runs.peek() {
+                    Some((x, _)) if **x == v => runs.next().unwrap().1,
+                    _ => BTreeSet::new(),
+                };
+                (v, Arc::new(adj))
+            })
+            .collect();
+        if !par_is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+/// Returns true if the adjacency list contains no cycles, like `is_acyclic`.
+/// The vertices are indexed and their in-degrees counted in parallel, then
+/// every vertex with no remaining incoming edges is removed at once, level by
+/// level, until no such vertex remains.
+pub(crate) fn par_is_acyclic<T>(vertices: &BTreeMap<T, Arc<BTreeSet<T>>>) -> bool
+where
+    T: Ord + Send + Sync,
+{
+    let keys: Vec<&T> = vertices.keys().collect();
+    let adjacency: Vec<&Arc<BTreeSet<T>>> = vertices.values().collect();
+    // Every adjacent vertex is a vertex, so we can safely unwrap.
+    let adjacency: Vec<Vec<usize>> = adjacency
+        .par_iter()
+        .map(|adj| {
+            adj.iter()
+                .map(|y| keys.binary_search(&y).unwrap())
+                .collect()
+        })
+        .collect();
+    let in_degrees: Vec<AtomicUsize> = (0..keys.len()).map(|_| AtomicUsize::new(0)).collect();
+    adjacency.par_iter().flatten().for_each(|y| {
+        in_degrees[*y].fetch_add(1, Ordering::Relaxed);
+    });
+    let mut level: Vec<usize> = (0..keys.len())
+        .into_par_iter()
+        .filter(|v| in_degrees[*v].load(Ordering::Relaxed) == 0)
+        .collect();
+    let mut removed: usize = 0;
+    while !level.is_empty() {
+        removed += level.len();
+        // Exactly one decrement takes each in-degree to zero, so each vertex
+        // joins exactly one level.
+        level = level
+            .par_iter()
+            .flat_map_iter(|v| adjacency[*v].iter())
+            .filter(|y| in_degrees[**y].fetch_sub(1, Ordering::AcqRel) == 1)
+            .copied()
+            .collect();
+    }
+    removed == keys.len()
+}
@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+use core::slice::Iter;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `Path` is a sequence of vertices of a `BTreeDAG` in which every pair of
+/// consecutive vertices is an edge of the dag. A `Path` can only be
+/// constructed by validating it against the dag it borrows from.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Path<'a, T>
+where
+    T: Ord,
+{
+    vertices: Vec<&'a T>,
+}
+
+impl<'a, T> Path<'a, T>
+where
+    T: Ord,
+{
+    /// Validates the sequence of vertices against the dag. An error is
+    /// returned if any vertex does not exist, or if any pair of consecutive
+    /// vertices is not an edge.
+    pub fn new(dag: &'a BTreeDAG<T>, vertices: Vec<&'a T>) -> Result<Self, Error> {
+        for v in vertices.iter() {
+            if !dag.vertices.contains_key(*v) {
+                return Err(Error::VertexDoesNotExist);
+            }
+        }
+        for pair in vertices.windows(2) {
+            // We have already verified every vertex exists, so we can
+            // safely unwrap.
+            if !dag.vertices.get(pair[0]).unwrap().contains(pair[1]) {
+                return Err(Error::EdgeDoesNotExist);
+            }
+        }
+        Ok(Path { vertices })
+    }
+
+    /// Constructs a path the caller has already verified to be valid.
+    pub(crate) fn new_unchecked(vertices: Vec<&'a T>) -> Self {
+        Path { vertices }
+    }
+
+    /// Returns the number of vertices in the path.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns true if the path contains no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Returns an iterator over the vertices of the path, in order.
+    pub fn iter(&self) -> Iter<'_, &'a T> {
+        self.vertices.iter()
+    }
+
+    /// Returns true if the vertex x is on the path.
+    pub fn contains(&self, x: &T) -> bool {
+        self.vertices.contains(&x)
+    }
+
+    /// Returns the first vertex of the path, if any.
+    pub fn source(&self) -> Option<&'a T> {
+        self.vertices.first().copied()
+    }
+
+    /// Returns the last vertex of the path, if any.
+    pub fn target(&self) -> Option<&'a T> {
+        self.vertices.last().copied()
+    }
+
+    /// Returns the vertices of the path as a slice.
+    pub fn as_slice(&self) -> &[&'a T] {
+        &self.vertices
+    }
+
+    /// Consumes the path, returning its vertices.
+    pub fn into_vec(self) -> Vec<&'a T> {
+        self.vertices
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b Path<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'b &'a T;
+    type IntoIter = Iter<'b, &'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vertices.iter()
+    }
+}
@@ -0,0 +1,221 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::dag::{is_acyclic, BTreeDAG, TopologicalSort};
+use crate::Error;
+
+/// `Progress` reports how far a long operation has come: `processed` of
+/// `total` units of work are done. The units depend on the operation, and
+/// are documented alongside it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Progress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// `Reporter` calls the progress callback of an operation about once per
+/// percent of the work, and once more when the work is done, so the
+/// callback does not dominate the cost of the operation.
+struct Reporter<F> {
+    progress: F,
+    processed: usize,
+    total: usize,
+    step: usize,
+}
+
+impl<F> Reporter<F>
+where
+    F: FnMut(Progress),
+{
+    fn new(total: usize, progress: F) -> Self {
+        Reporter {
+            progress,
+            processed: 0,
+            total,
+            step: (total / 100).max(1),
+        }
+    }
+
+    fn advance(&mut self) {
+        self.processed += 1;
+        if self.processed.is_multiple_of(self.step) || self.processed == self.total {
+            (self.progress)(Progress {
+                processed: self.processed,
+                total: self.total,
+            });
+        }
+    }
+}
+
+/// `FromEdgesWithProgress` constructs a dag from a list of edges (x, y) like
+/// `FromEdges`, reporting the number of edges inserted so far to the progress
+/// callback.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, FromEdgesWithProgress, Progress, Vertices};
+/// let mut reports: Vec<Progress> = Vec::new();
+/// let dag: BTreeDAG<String> = BTreeDAG::from_edges_with_progress(
+///     vec![
+///         (String::from("origin"), String::from("waypoint")),
+///         (String::from("waypoint"), String::from("destination")),
+///     ],
+///     |progress| reports.push(progress),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(dag.vertices().len(), 3);
+/// assert_eq!(reports.last(), Some(&Progress { processed: 2, total: 2 }));
+/// ```
+pub trait FromEdgesWithProgress<T>: Sized {
+    type Error;
+    fn from_edges_with_progress(
+        edges: impl IntoIterator<Item = (T, T)>,
+        progress: impl FnMut(Progress),
+    ) -> Result<Self, Self::Error>;
+}
+
+/// `TransitiveClosure` returns, for every vertex, the set of vertices
+/// reachable from it. The `_with_progress` variant reports the number of
+/// vertices whose closure has been computed to the progress callback.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, TransitiveClosure};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint")).unwrap();
+/// dag.add_edge(String::from("waypoint"), String::from("destination")).unwrap();
+///
+/// let closure = dag.transitive_closure();
+/// assert_eq!(closure[&String::from("origin")].len(), 2);
+/// assert!(closure[&String::from("destination")].is_empty());
+/// ```
+pub trait TransitiveClosure<T> {
+    fn transitive_closure(&self) -> BTreeMap<&T, BTreeSet<&T>>;
+    fn transitive_closure_with_progress(
+        &self,
+        progress: impl FnMut(Progress),
+    ) -> BTreeMap<&T, BTreeSet<&T>>;
+}
+
+/// `PruneWithProgress` prunes a vertex and every descendant like `Prune`,
+/// reporting the number of vertices visited to the progress callback: every
+/// vertex of the dag is visited once, either to remove it or to drop its
+/// edges into the pruned cone.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, PruneWithProgress, Progress, Vertices};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint")).unwrap();
+/// dag.add_edge(String::from("waypoint"), String::from("destination")).unwrap();
+///
+/// let mut last: Option<Progress> = None;
+/// dag.prune_with_progress(String::from("waypoint"), |progress| last = Some(progress))
+///     .unwrap();
+/// assert_eq!(dag.vertices().len(), 1);
+/// assert_eq!(last, Some(Progress { processed: 3, total: 3 }));
+/// ```
+pub trait PruneWithProgress<T> {
+    type Error;
+    fn prune_with_progress(
+        &mut self,
+        x: T,
+        progress: impl FnMut(Progress),
+    ) -> Result<(), Self::Error>;
+}
+
+impl<T> FromEdgesWithProgress<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn from_edges_with_progress(
+        edges: impl IntoIterator<Item = (T, T)>,
+        progress: impl FnMut(Progress),
+    ) -> Result<Self, Self::Error> {
+        let edges: Vec<(T, T)> = edges.into_iter().collect();
+        let mut reporter = Reporter::new(edges.len(), progress);
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = BTreeMap::new();
+        for (x, y) in edges {
+            vertices.entry(y.clone()).or_default();
+            Arc::make_mut(vertices.entry(x).or_default()).insert(y);
+            reporter.advance();
+        }
+        if !is_acyclic(&vertices) {
+            return Err(Error::EdgeExists);
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+impl<T> TransitiveClosure<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn transitive_closure(&self) -> BTreeMap<&T, BTreeSet<&T>> {
+        self.transitive_closure_with_progress(|_| {})
+    }
+
+    fn transitive_closure_with_progress(
+        &self,
+        progress: impl FnMut(Progress),
+    ) -> BTreeMap<&T, BTreeSet<&T>> {
+        let mut reporter = Reporter::new(self.vertices.len(), progress);
+        let mut closure: BTreeMap<&T, BTreeSet<&T>> = BTreeMap::new();
+        // In reverse topological order the closure of every successor is
+        // complete before the closure of its predecessors is computed.
+        for v in self.topological_sort().into_iter().rev() {
+            let mut reachable: BTreeSet<&T> = BTreeSet::new();
+            for y in self.vertices[v].iter() {
+                reachable.insert(y);
+                reachable.extend(closure[y].iter());
+            }
+            closure.insert(v, reachable);
+            reporter.advance();
+        }
+        closure
+    }
+}
+
+impl<T> PruneWithProgress<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune_with_progress(
+        &mut self,
+        x: T,
+        progress: impl FnMut(Progress),
+    ) -> Result<(), Self::Error> {
+        checked_mutation!(self, {
+            if !self.vertices.contains_key(&x) {
+                return Err(Error::VertexDoesNotExist);
+            }
+            let mut reporter = Reporter::new(self.vertices.len(), progress);
+            let mut pruned: BTreeSet<T> = self.descendants_of(&x).into_iter().cloned().collect();
+            pruned.insert(x);
+            for v in pruned.iter() {
+                self.vertices.remove(v);
+                reporter.advance();
+            }
+            for adj in self.vertices.values_mut() {
+                if adj.iter().any(|v| pruned.contains(v)) {
+                    Arc::make_mut(adj).retain(|v| !pruned.contains(v));
+                }
+                reporter.advance();
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+
+/// `Query` selects vertices of a `BTreeDAG` by chaining steps, each of which maps
+/// the current selection to a new one. A query starts with no vertices
+/// selected, and is constructed through the `QueryVertices` trait.
+#[derive(Clone, Debug)]
+pub struct Query<'a, T>
+where
+    T: Ord,
+{
+    dag: &'a BTreeDAG<T>,
+    selected: BTreeSet<&'a T>,
+}
+
+impl<'a, T> Query<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(dag: &'a BTreeDAG<T>) -> Self {
+        Query {
+            dag,
+            selected: BTreeSet::new(),
+        }
+    }
+
+    /// Adds the vertex x to the selection, if it exists.
+    pub fn from(mut self, x: &T) -> Self {
+        if let Some((x, _)) = self.dag.vertices.get_key_value(x) {
+            self.selected.insert(x);
+        }
+        self
+    }
+
+    /// Selects every vertex.
+    pub fn all(mut self) -> Self {
+        self.selected = self.dag.vertices.keys().collect();
+        self
+    }
+
+    /// Selects the vertices which a selected vertex has an edge to.
+    pub fn out(mut self) -> Self {
+        let dag = self.dag;
+        self.selected = self
+            .selected
+            .iter()
+            .flat_map(|x| dag.vertices[*x].iter())
+            .collect();
+        self
+    }
+
+    /// Selects the vertices which have an edge to a selected vertex. The step
+    /// is named `in_` as `in` is a keyword.
+    pub fn in_(mut self) -> Self {
+        let selected = &self.selected;
+        self.selected = self
+            .dag
+            .vertices
+            .iter()
+            .filter(|(_, adj)| adj.iter().any(|y| selected.contains(y)))
+            .map(|(x, _)| x)
+            .collect();
+        self
+    }
+
+    /// Selects the vertices reachable from a selected vertex by at least one
+    /// edge.
+    pub fn descendants(mut self) -> Self {
+        let dag = self.dag;
+        let mut descendants: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = self
+            .selected
+            .iter()
+            .flat_map(|x| dag.vertices[*x].iter())
+            .collect();
+        while let Some(v) = stack.pop() {
+            if descendants.insert(v) {
+                stack.extend(dag.vertices[v].iter());
+            }
+        }
+        self.selected = descendants;
+        self
+    }
+
+    /// Keeps only the selected vertices for which predicate returns true.
+    pub fn filter(mut self, predicate: impl Fn(&T) -> bool) -> Self {
+        self.selected.retain(|x| predicate(x));
+        self
+    }
+
+    /// Returns the selected vertices.
+    pub fn collect(self) -> BTreeSet<&'a T> {
+        self.selected
+    }
+}
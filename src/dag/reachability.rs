@@ -0,0 +1,132 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `ReachabilityIndex` is a precomputed 2-hop labeling of a `BTreeDAG`,
+/// which answers reachability queries by intersecting two sorted labels
+/// instead of searching the dag. The index borrows the dag, so it can not
+/// outlive a mutation; it must be rebuilt after the dag changes.
+///
+/// The labels are built by pruned landmark labeling, visiting the vertices
+/// in descending order of degree. Each label is a set of landmarks, and x
+/// reaches y exactly when some landmark is reachable from x and reaches y.
+#[derive(Clone, Debug)]
+pub struct ReachabilityIndex<'a, T>
+where
+    T: Ord,
+{
+    ids: BTreeMap<&'a T, usize>,
+    // The landmarks reachable from each vertex, sorted by rank.
+    out_labels: Vec<Vec<usize>>,
+    // The landmarks which reach each vertex, sorted by rank.
+    in_labels: Vec<Vec<usize>>,
+}
+
+impl<'a, T> ReachabilityIndex<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(dag: &'a BTreeDAG<T>) -> Self {
+        let vertices: Vec<&T> = dag.vertices.keys().collect();
+        let ids: BTreeMap<&T, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let successors: Vec<Vec<usize>> = dag
+            .vertices
+            .values()
+            .map(|adj| adj.iter().map(|y| ids[y]).collect())
+            .collect();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+        for (x, adj_x) in successors.iter().enumerate() {
+            for y in adj_x.iter() {
+                predecessors[*y].push(x);
+            }
+        }
+
+        // Vertices of high degree cover the most paths, so they make the
+        // best landmarks.
+        let mut order: Vec<usize> = (0..vertices.len()).collect();
+        order.sort_by_key(|v| core::cmp::Reverse(successors[*v].len() + predecessors[*v].len()));
+
+        let mut index = ReachabilityIndex {
+            ids,
+            out_labels: vec![Vec::new(); vertices.len()],
+            in_labels: vec![Vec::new(); vertices.len()],
+        };
+        for (rank, landmark) in order.into_iter().enumerate() {
+            // Label every vertex the landmark reaches, skipping vertices
+            // the existing labels already cover.
+            index.visit(landmark, &successors, |index, v| {
+                if intersects(&index.out_labels[landmark], &index.in_labels[v]) {
+                    return false;
+                }
+                index.in_labels[v].push(rank);
+                true
+            });
+            // Label every vertex which reaches the landmark.
+            index.visit(landmark, &predecessors, |index, v| {
+                if intersects(&index.out_labels[v], &index.in_labels[landmark]) {
+                    return false;
+                }
+                index.out_labels[v].push(rank);
+                true
+            });
+        }
+        index
+    }
+
+    /// Breadth first search from the landmark, only expanding the vertices
+    /// for which label returns true.
+    fn visit(
+        &mut self,
+        landmark: usize,
+        adjacency: &[Vec<usize>],
+        mut label: impl FnMut(&mut Self, usize) -> bool,
+    ) {
+        let mut visited: Vec<bool> = vec![false; adjacency.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        visited[landmark] = true;
+        queue.push_back(landmark);
+        while let Some(v) = queue.pop_front() {
+            if label(self, v) {
+                for adj in adjacency[v].iter() {
+                    if !visited[*adj] {
+                        visited[*adj] = true;
+                        queue.push_back(*adj);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true if there is a path from x to y of at least one edge.
+    pub fn is_reachable(&self, x: &T, y: &T) -> Result<bool, Error> {
+        let x = *self.ids.get(x).ok_or(Error::VertexDoesNotExist)?;
+        let y = *self.ids.get(y).ok_or(Error::VertexDoesNotExist)?;
+        Ok(x != y && intersects(&self.out_labels[x], &self.in_labels[y]))
+    }
+
+    /// Returns the total number of landmarks in all labels, which bounds
+    /// the memory used by the index.
+    pub fn label_len(&self) -> usize {
+        self.out_labels
+            .iter()
+            .chain(self.in_labels.iter())
+            .map(Vec::len)
+            .sum()
+    }
+}
+
+/// Returns true if the two sorted labels share a landmark.
+fn intersects(a: &[usize], b: &[usize]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            core::cmp::Ordering::Less => i += 1,
+            core::cmp::Ordering::Greater => j += 1,
+            core::cmp::Ordering::Equal => return true,
+        }
+    }
+    false
+}
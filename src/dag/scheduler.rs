@@ -0,0 +1,106 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::cmp::Reverse;
+
+use crate::dag::BTreeDAG;
+
+/// `Scheduler` hands out the vertices of a `BTreeDAG` as tasks, in an order
+/// which respects the edges: an edge (x, y) means x depends on y, so a vertex
+/// becomes ready once every vertex it has an edge to has been completed.
+/// Ready vertices are handed out in descending order of priority, and then
+/// in ascending order. It is constructed through the `Schedule` trait.
+pub struct Scheduler<'a, T, P>
+where
+    T: Ord,
+    P: Ord,
+{
+    dag: &'a BTreeDAG<T>,
+    priority: Box<dyn Fn(&T) -> P + 'a>,
+    // The number of incomplete dependencies of each blocked vertex.
+    blocked: BTreeMap<&'a T, usize>,
+    // The vertices with edges to each vertex.
+    dependents: BTreeMap<&'a T, BTreeSet<&'a T>>,
+    ready: BTreeSet<(Reverse<P>, &'a T)>,
+    running: BTreeSet<&'a T>,
+}
+
+impl<'a, T, P> Scheduler<'a, T, P>
+where
+    T: Ord,
+    P: Ord,
+{
+    pub(crate) fn new(dag: &'a BTreeDAG<T>, priority: Box<dyn Fn(&T) -> P + 'a>) -> Self {
+        let mut dependents: BTreeMap<&T, BTreeSet<&T>> = BTreeMap::new();
+        for (x, adj_x) in dag.vertices.iter() {
+            for y in adj_x.iter() {
+                dependents.entry(y).or_default().insert(x);
+            }
+        }
+        let mut scheduler = Scheduler {
+            dag,
+            priority,
+            blocked: BTreeMap::new(),
+            dependents,
+            ready: BTreeSet::new(),
+            running: BTreeSet::new(),
+        };
+        for (x, adj_x) in dag.vertices.iter() {
+            if adj_x.is_empty() {
+                scheduler.make_ready(x);
+            } else {
+                scheduler.blocked.insert(x, adj_x.len());
+            }
+        }
+        scheduler
+    }
+
+    fn make_ready(&mut self, x: &'a T) {
+        let priority = (self.priority)(x);
+        self.ready.insert((Reverse(priority), x));
+    }
+
+    /// Returns an iterator over the ready vertices, in the order they will
+    /// be handed out.
+    pub fn ready(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.ready.iter().map(|(_, x)| *x)
+    }
+
+    /// Hands out the next ready vertex, marking it as running.
+    pub fn pop(&mut self) -> Option<&'a T> {
+        let (_, x) = self.ready.pop_first()?;
+        self.running.insert(x);
+        Some(x)
+    }
+
+    /// Marks the running vertex x as completed, which may make the vertices
+    /// depending on it ready. Returns false if x is not running.
+    pub fn complete(&mut self, x: &T) -> bool {
+        let x = match self.running.take(x) {
+            Some(x) => x,
+            None => return false,
+        };
+        if let Some(dependents) = self.dependents.get(x) {
+            for w in dependents.clone() {
+                // Every dependent is blocked until its last dependency is
+                // complete, so we can safely unwrap.
+                let remaining = self.blocked.get_mut(w).unwrap();
+                *remaining -= 1;
+                if *remaining == 0 {
+                    self.blocked.remove(w);
+                    self.make_ready(w);
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns true once every vertex has been completed.
+    pub fn is_done(&self) -> bool {
+        self.blocked.is_empty() && self.ready.is_empty() && self.running.is_empty()
+    }
+
+    /// Returns the dag being scheduled.
+    pub fn dag(&self) -> &'a BTreeDAG<T> {
+        self.dag
+    }
+}
@@ -2,8 +2,13 @@
 
 mod unit_tests {
     use crate::dag::*;
-    use crate::Error;
+    use crate::{ContextError, Error, ErrorContext};
     use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::sync::Arc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use core::mem::size_of;
 
     #[test]
     fn test_dag() {
@@ -15,10 +20,16 @@ mod unit_tests {
     fn definition() {
         // Instantiate a dag using the new associated function.
         let dag: BTreeDAG<usize> = BTreeDAG::new();
-        let vertices: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+        let vertices: BTreeMap<usize, Arc<BTreeSet<usize>>> = BTreeMap::new();
 
         // Check dag struct is generated.
-        assert_eq!(dag, BTreeDAG { vertices })
+        assert_eq!(
+            dag,
+            BTreeDAG {
+                vertices,
+                max_traversal_depth: None
+            }
+        )
 
         // Test passed
     }
@@ -165,6 +176,9 @@ mod unit_tests {
         let exp_edges_0: BTreeSet<usize> = BTreeSet::new();
         assert_eq!(dag.get_vertex_value(0).unwrap(), &exp_edges_0);
 
+        // Removing a vertex which does not exist raises an error.
+        assert_eq!(dag.remove_vertex(3).unwrap_err(), Error::VertexDoesNotExist);
+
         Ok(())
 
         // Test passed.
@@ -362,5 +376,2706 @@ mod unit_tests {
         Ok(())
     }
 
+    #[test]
+    fn entry() -> Result<(), Error> {
+        // Add two nodes.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+
+        // An entry for a vertex which does not exist has no adjacency list.
+        assert!(dag.entry(0).get().is_none());
+        assert_eq!(dag.entry(0).key(), &0);
+
+        // Adding an edge through a vacant entry inserts the vertex.
+        let mut entry = dag.entry(0);
+        entry.add_edge_to(1)?;
+        entry.add_edge_to(2)?;
+        let mut exp_edges_0: BTreeSet<usize> = BTreeSet::new();
+        exp_edges_0.insert(1);
+        exp_edges_0.insert(2);
+        assert_eq!(entry.get().unwrap(), &exp_edges_0);
+        assert_eq!(dag.get_vertex_value(0).unwrap(), &exp_edges_0);
+
+        // The vertex y must exist.
+        assert_eq!(
+            dag.entry(1).add_edge_to(3).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+        assert_eq!(
+            dag.entry(3).add_edge_to(4).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+        assert!(dag.get_vertex_value(3).is_none());
+
+        // Edges added through an entry must not introduce a cycle.
+        dag.entry(1).add_edge_to(2)?;
+        assert_eq!(dag.entry(2).add_edge_to(0).unwrap_err(), Error::EdgeExists);
+
+        // Inserting through an entry does not clear an existing vertex.
+        assert_eq!(dag.entry(0).or_insert(), &exp_edges_0);
+        assert!(dag.entry(3).or_insert().is_empty());
+        assert_eq!(dag.vertices().len(), 4);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn drain_edges_where() -> Result<(), Error> {
+        // Add four nodes.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+
+        // Add an edge (0, 1), (0, 2), (1, 2), and (2, 3).
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+
+        // Drain every edge pointing to vertex 2.
+        let drained = dag.drain_edges_where(|_, y| *y == 2);
+        assert_eq!(drained, vec![(0, 2), (1, 2)]);
+
+        // The vertices remain, but the drained edges are gone.
+        assert_eq!(dag.vertices().len(), 4);
+        let mut exp_edges_0: BTreeSet<usize> = BTreeSet::new();
+        exp_edges_0.insert(1);
+        assert_eq!(dag.get_vertex_value(0).unwrap(), &exp_edges_0);
+        assert!(dag.get_vertex_value(1).unwrap().is_empty());
+        assert!(dag.adjacent(2, 3)?);
+
+        // A predicate which matches nothing drains nothing.
+        assert!(dag.drain_edges_where(|x, _| *x == 3).is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn path() -> Result<(), Error> {
+        // Add four nodes.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+
+        // Add an edge (0, 1) and (1, 2).
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // Consecutive vertices which are edges form a valid path.
+        let path = Path::new(&dag, vec![&0, &1, &2])?;
+        assert_eq!(path.len(), 3);
+        assert!(!path.is_empty());
+        assert!(path.contains(&1));
+        assert!(!path.contains(&3));
+        assert_eq!(path.source(), Some(&0));
+        assert_eq!(path.target(), Some(&2));
+        assert_eq!(
+            path.iter().copied().collect::<Vec<&usize>>(),
+            vec![&0, &1, &2]
+        );
+
+        // A single vertex is a trivial path.
+        assert_eq!(Path::new(&dag, vec![&3])?.len(), 1);
+
+        // Invalid paths are rejected.
+        assert_eq!(
+            Path::new(&dag, vec![&0, &2]).unwrap_err(),
+            Error::EdgeDoesNotExist
+        );
+        assert_eq!(
+            Path::new(&dag, vec![&2, &1]).unwrap_err(),
+            Error::EdgeDoesNotExist
+        );
+        assert_eq!(
+            Path::new(&dag, vec![&0, &4]).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn shortest_path() -> Result<(), Error> {
+        // Add five nodes.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_vertex(4);
+
+        // Add an edge (0, 1), (1, 2), (2, 3), and (0, 3).
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(0, 3)?;
+
+        // The direct edge is the shortest path from 0 to 3.
+        let path = dag.shortest_path(&0, &3)?.unwrap();
+        assert_eq!(path.into_vec(), vec![&0, &3]);
+
+        let path = dag.shortest_path(&0, &2)?.unwrap();
+        assert_eq!(path.into_vec(), vec![&0, &1, &2]);
+
+        // The path from a vertex to itself is the vertex alone.
+        assert_eq!(dag.shortest_path(&1, &1)?.unwrap().into_vec(), vec![&1]);
+
+        // Unreachable vertices have no path.
+        assert!(dag.shortest_path(&3, &0)?.is_none());
+        assert!(dag.shortest_path(&0, &4)?.is_none());
+
+        // Vertices which do not exist raise an error.
+        assert_eq!(
+            dag.shortest_path(&0, &5).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+        assert_eq!(
+            dag.shortest_path(&5, &0).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn chain_decomposition() -> Result<(), Error> {
+        // An empty dag has no chains.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert!(dag.chain_decomposition().is_empty());
+
+        // Add six nodes.
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_vertex(4);
+        dag.add_vertex(5);
+
+        // Add an edge (0, 1), (0, 2), (1, 3), (2, 3), and (3, 4).
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(3, 4)?;
+
+        // The largest set of mutually unreachable vertices is {1, 2, 5},
+        // so there must be exactly three chains.
+        let chains = dag.chain_decomposition();
+        assert_eq!(chains.len(), 3);
+
+        // Every vertex belongs to exactly one chain.
+        let mut covered: Vec<&usize> = chains.iter().flatten().copied().collect();
+        covered.sort();
+        assert_eq!(covered, vec![&0, &1, &2, &3, &4, &5]);
+
+        // Every vertex in a chain is reachable from its predecessor.
+        for chain in chains.iter() {
+            for pair in chain.windows(2) {
+                assert!(dag.shortest_path(pair[0], pair[1])?.is_some());
+            }
+        }
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn from_edges() -> Result<(), Error> {
+        // Build a dag from the edges (0, 1), (1, 2), and (0, 2).
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 1), (1, 2), (0, 2)])?;
+
+        // The vertices are created from the edges.
+        let mut exp_vertices: BTreeSet<&usize> = BTreeSet::new();
+        exp_vertices.insert(&0);
+        exp_vertices.insert(&1);
+        exp_vertices.insert(&2);
+        assert_eq!(dag.vertices(), exp_vertices);
+
+        // The dag is identical to one built edge by edge.
+        let mut exp_dag: BTreeDAG<usize> = BTreeDAG::new();
+        exp_dag.add_vertex(0);
+        exp_dag.add_vertex(1);
+        exp_dag.add_vertex(2);
+        exp_dag.add_edge(0, 1)?;
+        exp_dag.add_edge(1, 2)?;
+        exp_dag.add_edge(0, 2)?;
+        assert_eq!(dag, exp_dag);
+
+        // An empty list of edges produces an empty dag.
+        assert_eq!(BTreeDAG::<usize>::from_edges(vec![])?, BTreeDAG::new());
+
+        // Edges which contain a cycle are rejected.
+        assert_eq!(
+            BTreeDAG::from_edges(vec![(0, 1), (1, 2), (2, 0)]).unwrap_err(),
+            Error::EdgeExists
+        );
+        assert_eq!(
+            BTreeDAG::from_edges(vec![(0, 0)]).unwrap_err(),
+            Error::EdgeExists
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn feedback_arc_set() -> Result<(), Error> {
+        // Acyclic edges need no edges dropped.
+        let edges: Vec<(usize, usize)> = vec![(0, 1), (1, 2), (0, 2)];
+        assert!(BTreeDAG::feedback_arc_set(edges).is_empty());
+
+        // Two cycles, (0, 1, 2) and (2, 3), sharing the vertex 2; and a
+        // self-loop on the vertex 4.
+        let edges: Vec<(usize, usize)> = vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 2), (4, 4)];
+        let feedback = BTreeDAG::feedback_arc_set(edges.clone());
+        assert_eq!(feedback.len(), 3);
+        assert!(feedback.contains(&(4, 4)));
+
+        // Dropping the suggested edges leaves the remaining edges acyclic.
+        let remaining = edges.into_iter().filter(|e| !feedback.contains(e));
+        BTreeDAG::from_edges(remaining)?;
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn add_vertex_without_clone() -> Result<(), Error> {
+        // A key type which is not Clone.
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+        struct Key(usize);
+
+        // Add two nodes, and re-add one.
+        let mut dag: BTreeDAG<Key> = BTreeDAG::new();
+        assert_eq!(dag.add_vertex(Key(0)), None);
+        assert_eq!(dag.add_vertex(Key(1)), None);
+        assert_eq!(dag.add_vertex(Key(0)), Some(BTreeSet::new()));
+        assert_eq!(dag.vertices.len(), 2);
+
+        // Re-adding a vertex returns its unshared adjacency list.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 1), (0, 2)])?;
+        assert_eq!(dag.add_vertex(0), Some(BTreeSet::from([1, 2])));
+        assert_eq!(dag.add_vertex(0), Some(BTreeSet::new()));
+
+        // A list shared with a snapshot is kept by the snapshot.
+        dag.add_edge(0, 1)?;
+        let snapshot: BTreeDAG<usize> = dag.snapshot();
+        assert_eq!(dag.add_vertex(0), Some(BTreeSet::new()));
+        assert!(!dag.adjacent(0, 1)?);
+        assert!(snapshot.adjacent(0, 1)?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // The snapshot shares every adjacency list with the dag.
+        let snapshot: BTreeDAG<usize> = dag.snapshot();
+        assert_eq!(snapshot, dag);
+        for (v, adj) in dag.vertices.iter() {
+            assert!(Arc::ptr_eq(adj, &snapshot.vertices[v]));
+        }
+
+        // Mutating the dag copies only the adjacency list which changed,
+        // and leaves the snapshot untouched.
+        dag.add_vertex(3);
+        dag.add_edge(0, 3)?;
+        dag.remove_edge(1, 2)?;
+        assert!(Arc::ptr_eq(&dag.vertices[&2], &snapshot.vertices[&2]));
+        assert!(!Arc::ptr_eq(&dag.vertices[&0], &snapshot.vertices[&0]));
+
+        let mut exp_edges_0: BTreeSet<usize> = BTreeSet::new();
+        exp_edges_0.insert(1);
+        assert_eq!(snapshot.get_vertex_value(0).unwrap(), &exp_edges_0);
+        assert!(snapshot.adjacent(1, 2)?);
+        assert_eq!(snapshot.vertices().len(), 3);
+
+        // Removing an edge which does not exist does not copy the
+        // adjacency list.
+        let snapshot: BTreeDAG<usize> = dag.snapshot();
+        dag.remove_edge(2, 0)?;
+        assert!(Arc::ptr_eq(&dag.vertices[&2], &snapshot.vertices[&2]));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn topological_sort() -> Result<(), Error> {
+        // An empty dag sorts to nothing.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert!(dag.topological_sort().is_empty());
+
+        // Add five nodes.
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_vertex(4);
+
+        // Add an edge (3, 1), (1, 0), (3, 2), and (2, 0).
+        dag.add_edge(3, 1)?;
+        dag.add_edge(1, 0)?;
+        dag.add_edge(3, 2)?;
+        dag.add_edge(2, 0)?;
+
+        // Every edge points forwards, and ties are broken by the least vertex.
+        assert_eq!(dag.topological_sort(), vec![&3, &1, &2, &0, &4]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn iter_sorted() -> Result<(), Error> {
+        // Add the nodes out of order.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(2);
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(2, 1)?;
+        dag.add_edge(2, 0)?;
+
+        // The vertices are always iterated in ascending order.
+        let vertices: Vec<&usize> = dag.iter_sorted().map(|(v, _)| v).collect();
+        assert_eq!(vertices, vec![&0, &1, &2]);
+        let reversed: Vec<&usize> = dag.iter_sorted().rev().map(|(v, _)| v).collect();
+        assert_eq!(reversed, vec![&2, &1, &0]);
+        assert_eq!(dag.iter_sorted().len(), 3);
+
+        // Each vertex is paired with its adjacency list.
+        let (_, adj_2) = dag.iter_sorted().next_back().unwrap();
+        assert_eq!(adj_2.iter().collect::<Vec<&usize>>(), vec![&0, &1]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn edges_sorted() -> Result<(), Error> {
+        // An empty dag has no edges.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert_eq!(dag.edges_sorted().count(), 0);
+
+        // Add the nodes and edges out of order; the vertex 1 has none.
+        dag.add_vertex(3);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(0);
+        dag.add_edge(2, 3)?;
+        dag.add_edge(0, 3)?;
+        dag.add_edge(0, 2)?;
+
+        // Edges are always iterated in lexicographic order.
+        let edges: Vec<(&usize, &usize)> = dag.edges_sorted().collect();
+        assert_eq!(edges, vec![(&0, &2), (&0, &3), (&2, &3)]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn canonical() -> Result<(), Error> {
+        // Two dags built in different orders are canonically equal.
+        let mut dag_a: BTreeDAG<usize> = BTreeDAG::new();
+        dag_a.add_vertex(0);
+        dag_a.add_vertex(1);
+        dag_a.add_vertex(2);
+        dag_a.add_edge(0, 1)?;
+        dag_a.add_edge(0, 2)?;
+
+        let dag_b: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 2), (0, 1)])?;
+        assert_eq!(dag_a.to_canonical(), dag_b.to_canonical());
+
+        let canonical = dag_a.to_canonical();
+        assert_eq!(canonical.vertices, vec![&0, &1, &2]);
+        assert_eq!(canonical.edges, vec![(&0, &1), (&0, &2)]);
+
+        // The canonical form converts back into the same dag.
+        let owned: Canonical<usize> = Canonical {
+            vertices: vec![0, 1, 2, 3],
+            edges: vec![(0, 1), (0, 2)],
+        };
+        let dag: BTreeDAG<usize> = BTreeDAG::try_from(owned)?;
+        assert_eq!(dag.vertices().len(), 4);
+        assert!(dag.adjacent(0, 2)?);
+
+        // Edges must reference listed vertices, and must be acyclic.
+        let dangling: Canonical<usize> = Canonical {
+            vertices: vec![0],
+            edges: vec![(0, 1)],
+        };
+        assert_eq!(
+            BTreeDAG::try_from(dangling).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+        let cyclic: Canonical<usize> = Canonical {
+            vertices: vec![0, 1],
+            edges: vec![(0, 1), (1, 0)],
+        };
+        assert_eq!(BTreeDAG::try_from(cyclic).unwrap_err(), Error::EdgeExists);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn canonical_json() -> Result<(), Error> {
+        use try_encoding_from::serde_json;
+
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(1, 2), (0, 2)])?;
+        let json = serde_json::to_string(&dag.to_canonical())?;
+        assert_eq!(json, "{\"vertices\":[0,1,2],\"edges\":[[0,2],[1,2]]}");
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn out_degree() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (0, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+
+        assert_eq!(dag.out_degree(&0), Some(2));
+        assert_eq!(dag.out_degree(&1), Some(0));
+        assert_eq!(dag.out_degree(&3), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn memory_footprint() -> Result<(), Error> {
+        // An empty dag uses no heap memory.
+        let mut dag: BTreeDAG<u64> = BTreeDAG::new();
+        assert_eq!(dag.memory_footprint_estimate(), 0);
+        assert_eq!(dag.vertex_footprint_estimate(&0), None);
+
+        // A single vertex uses one map node and one empty adjacency set.
+        dag.add_vertex(0);
+        let vertex_node: usize = BTREE_NODE_CAPACITY
+            * (size_of::<u64>() + size_of::<Arc<BTreeSet<u64>>>())
+            + size_of::<usize>() * 2;
+        let empty_adjacency: usize = size_of::<usize>() * 2 + size_of::<BTreeSet<u64>>();
+        assert_eq!(
+            dag.memory_footprint_estimate(),
+            vertex_node + empty_adjacency
+        );
+        assert_eq!(dag.vertex_footprint_estimate(&0), Some(empty_adjacency));
+
+        // An edge adds one set node to the adjacency set.
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+        let set_node: usize = BTREE_NODE_CAPACITY * size_of::<u64>() + size_of::<usize>() * 2;
+        assert_eq!(
+            dag.vertex_footprint_estimate(&0),
+            Some(empty_adjacency + set_node)
+        );
+        assert_eq!(
+            dag.memory_footprint_estimate(),
+            vertex_node + empty_adjacency * 2 + set_node
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn reachability_index() -> Result<(), Error> {
+        // Add sixty nodes, and pseudo-random edges from lower to higher
+        // vertices.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..60 {
+            dag.add_vertex(x);
+        }
+        let mut seed: usize = 7;
+        for x in 0..60 {
+            for y in (x + 1)..60 {
+                seed = (seed * 1103515245 + 12345) % 2147483648;
+                if seed.is_multiple_of(16) {
+                    dag.add_edge(x, y)?;
+                }
+            }
+        }
+
+        // The index agrees with a search of the dag for every pair.
+        let index = dag.build_reachability_index();
+        for x in 0..60 {
+            let descendants: BTreeSet<&usize> = dag.descendants_of(&x);
+            for y in 0..60 {
+                assert_eq!(index.is_reachable(&x, &y)?, descendants.contains(&y));
+            }
+        }
+        assert!(index.label_len() > 0);
+        assert_eq!(index.is_reachable(&0, &60), Err(Error::VertexDoesNotExist));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn successors_and_predecessors() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 2) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+
+        assert_eq!(dag.successors(&0), dag.connections(0));
+        assert_eq!(dag.successors(&0), Some(&BTreeSet::from([2])));
+        assert_eq!(dag.predecessors(&2), Some(BTreeSet::from([&0, &1])));
+        assert_eq!(dag.predecessors(&0), Some(BTreeSet::new()));
+        assert_eq!(dag.successors(&3), None);
+        assert_eq!(dag.predecessors(&3), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn budgeted_topological_sort() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // Sorting spends one operation per vertex, and two per edge.
+        assert_eq!(
+            dag.try_topological_sort_with_budget(6),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(
+            dag.try_topological_sort_with_budget(7)?,
+            dag.topological_sort()
+        );
 
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn try_descendants() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        let token = CancellationToken::new();
+        assert_eq!(dag.try_descendants(&0, &token)?, BTreeSet::from([&1, &2]));
+        assert_eq!(dag.try_descendants(&2, &token)?, BTreeSet::new());
+        assert_eq!(
+            dag.try_descendants(&3, &token),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Cancelling any clone cancels the traversal.
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(dag.try_descendants(&0, &token), Err(Error::Cancelled));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn set_operations() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2) to the first dag,
+        // and three nodes, and an edge (0, 1) and (0, 3) to the second.
+        let mut a: BTreeDAG<usize> = BTreeDAG::new();
+        a.add_vertex(0);
+        a.add_vertex(1);
+        a.add_vertex(2);
+        a.add_edge(0, 1)?;
+        a.add_edge(1, 2)?;
+        let mut b: BTreeDAG<usize> = BTreeDAG::new();
+        b.add_vertex(0);
+        b.add_vertex(1);
+        b.add_vertex(3);
+        b.add_edge(0, 1)?;
+        b.add_edge(0, 3)?;
+
+        let union: BTreeDAG<usize> = a.merge(&b)?;
+        assert_eq!(union.vertices(), BTreeSet::from([&0, &1, &2, &3]));
+        assert_eq!(union.connections(0), Some(&BTreeSet::from([1, 3])));
+        assert_eq!(union.connections(1), Some(&BTreeSet::from([2])));
+
+        let intersection: BTreeDAG<usize> = a.intersection(&b);
+        assert_eq!(intersection.vertices(), BTreeSet::from([&0, &1]));
+        assert_eq!(intersection.connections(0), Some(&BTreeSet::from([1])));
+
+        let difference: BTreeDAG<usize> = a.difference(&b);
+        assert_eq!(difference.vertices(), BTreeSet::from([&0, &1, &2]));
+        assert_eq!(difference.connections(0), Some(&BTreeSet::new()));
+        assert_eq!(difference.connections(1), Some(&BTreeSet::from([2])));
+
+        // A union which contains a cycle is rejected.
+        b.add_vertex(2);
+        b.add_edge(2, 0)?;
+        assert_eq!(a.merge(&b), Err(Error::EdgeExists));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn contract_edge() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2), (3, 1) and (0, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(3, 1)?;
+        dag.add_edge(0, 2)?;
+
+        // The path (0, 1, 2) would become a cycle if (0, 2) were contracted.
+        assert_eq!(dag.contract_edge(0, 2), Err(Error::EdgeExists));
+        assert_eq!(dag.contract_edge(2, 0), Err(Error::EdgeDoesNotExist));
+        assert_eq!(dag.contract_edge(0, 4), Err(Error::VertexDoesNotExist));
+
+        // The edges of 1 are moved to 0.
+        assert_eq!(dag.contract_edge(0, 1)?, 1);
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &2, &3]));
+        assert_eq!(dag.connections(0), Some(&BTreeSet::from([2])));
+        assert_eq!(dag.connections(3), Some(&BTreeSet::from([0])));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn coarsen() -> Result<(), Error> {
+        // Add a chain of five nodes, and a node 5 with an edge to 2.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        for x in 0..4 {
+            dag.add_edge(x, x + 1)?;
+        }
+        dag.add_edge(5, 2)?;
+
+        // The edge (1, 2) is not a chain edge, as 2 has two incoming edges.
+        let levels: Vec<Coarsening<usize>> = dag.coarsen(8);
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].dag.vertices(), BTreeSet::from([&0, &2, &4, &5]));
+        assert_eq!(levels[0].mapping[&1], 0);
+        assert_eq!(levels[0].mapping[&3], 2);
+        assert_eq!(levels[0].dag.connections(2), Some(&BTreeSet::from([4])));
+        assert_eq!(levels[1].dag.vertices(), BTreeSet::from([&0, &2, &5]));
+        assert_eq!(levels[1].mapping[&4], 2);
+        assert_eq!(levels[1].dag.connections(0), Some(&BTreeSet::from([2])));
+        assert_eq!(levels[1].dag.connections(2), Some(&BTreeSet::new()));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn vertex_index() {
+        // Add three nodes.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(10);
+        dag.add_vertex(20);
+        dag.add_vertex(30);
+        for (i, x) in dag.vertices().into_iter().enumerate() {
+            assert_eq!(dag.index_of(x), Some(i));
+            assert_eq!(dag.vertex_at(i), Some(x));
+        }
+        assert_eq!(dag.index_of(&15), None);
+        assert_eq!(dag.vertex_at(3), None);
+
+        // Adding a greater vertex leaves the indices unchanged, while
+        // adding a lesser vertex shifts them.
+        dag.add_vertex(40);
+        assert_eq!(dag.index_of(&30), Some(2));
+        dag.add_vertex(0);
+        assert_eq!(dag.index_of(&30), Some(3));
+
+        // Test passed.
+    }
+
+    #[test]
+    fn traverse_all() -> Result<(), Error> {
+        // Add five nodes, and an edge (0, 2), (1, 2), (2, 3) and (1, 3), and
+        // leave 4 isolated.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_vertex(4);
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(1, 3)?;
+
+        // Shared vertices are only visited from the first root.
+        let visited: Vec<&usize> = dag.traverse_all().collect();
+        assert_eq!(visited, [&0, &2, &3, &1, &4]);
+        assert_eq!(BTreeDAG::<usize>::new().traverse_all().next(), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn descendants_within() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2), (2, 3) and (0, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(0, 2)?;
+
+        // Each vertex has its shortest distance.
+        assert_eq!(dag.descendants_within(&0, 0), BTreeMap::new());
+        assert_eq!(
+            dag.descendants_within(&0, 1),
+            BTreeMap::from([(&1, 1), (&2, 1)])
+        );
+        assert_eq!(
+            dag.descendants_within(&0, 5),
+            BTreeMap::from([(&1, 1), (&2, 1), (&3, 2)])
+        );
+        assert_eq!(dag.descendants_within(&4, 5), BTreeMap::new());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn topological_ranks() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2) and (0, 2), and leave 3
+        // isolated.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(0, 2)?;
+
+        assert_eq!(
+            dag.topological_ranks(),
+            BTreeMap::from([(&0, 0), (&1, 1), (&2, 2), (&3, 0)])
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_form() -> Result<(), Error> {
+        // Add a diamond with a tail, and a relabeled copy of it.
+        let a: BTreeDAG<usize> =
+            BTreeDAG::from_edges(vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)])?;
+        let b: BTreeDAG<usize> =
+            BTreeDAG::from_edges(vec![(9, 7), (9, 8), (7, 5), (8, 5), (5, 6)])?;
+        assert_eq!(a.canonical_form(), b.canonical_form());
+        assert_eq!(a.canonical_form().vertices, [0, 1, 2, 3, 4]);
+        assert_eq!(a.canonical_form().edges.len(), 5);
+
+        // A dag with the same degrees but a different structure differs.
+        let c: BTreeDAG<usize> =
+            BTreeDAG::from_edges(vec![(0, 1), (0, 2), (1, 3), (2, 3), (4, 0)])?;
+        assert_ne!(a.canonical_form(), c.canonical_form());
+
+        // Isolated vertices are part of the form.
+        let mut d: BTreeDAG<usize> = a.clone();
+        d.add_vertex(5);
+        assert_ne!(a.canonical_form(), d.canonical_form());
+
+        // Highly symmetric dags, such as many copies of a chain, are handled
+        // by skipping equivalent branches.
+        let chains: BTreeDAG<usize> = BTreeDAG::from_edges((0..20).map(|x| (x, x + 20)))?;
+        let relabeled: BTreeDAG<usize> = BTreeDAG::from_edges((0..20).map(|x| (2 * x + 1, 2 * x)))?;
+        assert_eq!(chains.canonical_form(), relabeled.canonical_form());
+        let mut isolated: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..20 {
+            isolated.add_vertex(x);
+        }
+        assert_eq!(isolated.canonical_form().edges, []);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2) and (1, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+
+        // Without priorities, ready vertices are handed out in order.
+        let mut scheduler = dag.scheduler();
+        assert_eq!(scheduler.ready().collect::<Vec<&usize>>(), [&2, &3]);
+        assert_eq!(scheduler.pop(), Some(&2));
+        assert!(!scheduler.complete(&3));
+        assert!(scheduler.complete(&2));
+        assert!(!scheduler.complete(&2));
+        // 0 is still blocked on 1, which is blocked on 3.
+        assert_eq!(scheduler.pop(), Some(&3));
+        assert_eq!(scheduler.pop(), None);
+        assert!(scheduler.complete(&3));
+        assert_eq!(scheduler.pop(), Some(&1));
+        assert!(scheduler.complete(&1));
+        assert_eq!(scheduler.pop(), Some(&0));
+        assert!(!scheduler.is_done());
+        assert!(scheduler.complete(&0));
+        assert!(scheduler.is_done());
+
+        // With priorities, the highest priority ready vertex is first.
+        let ranks = dag.topological_ranks();
+        let mut scheduler = dag.scheduler_by(|x| ranks[x]);
+        assert_eq!(scheduler.ready().collect::<Vec<&usize>>(), [&3, &2]);
+        assert_eq!(scheduler.pop(), Some(&3));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn critical_path_method() -> Result<(), Error> {
+        // Add four nodes, and an edge (3, 1), (3, 2), (1, 0) and (2, 0), where
+        // each node's duration is its value plus one.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(3, 1)?;
+        dag.add_edge(3, 2)?;
+        dag.add_edge(1, 0)?;
+        dag.add_edge(2, 0)?;
+        let durations = |x: &usize| (*x + 1) as u64;
+
+        assert_eq!(
+            dag.earliest_start_times(durations),
+            BTreeMap::from([(&0, 0), (&1, 1), (&2, 1), (&3, 4)])
+        );
+        // The path (0, 2, 3) is critical, and 1 can be delayed by 1.
+        assert_eq!(
+            dag.slack(durations),
+            BTreeMap::from([(&0, 0), (&1, 1), (&2, 0), (&3, 0)])
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn count_paths() -> Result<(), Error> {
+        // Add a ladder of 200 nodes, where each node has an edge to the next
+        // two, so the number of paths grows as the Fibonacci numbers.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for i in 0..200 {
+            dag.add_vertex(i);
+        }
+        for i in 0..198 {
+            dag.add_edge(i, i + 1)?;
+            dag.add_edge(i, i + 2)?;
+        }
+        dag.add_edge(198, 199)?;
+
+        assert_eq!(dag.count_paths(&0, &0), 1);
+        assert_eq!(dag.count_paths(&0, &1), 1);
+        assert_eq!(dag.count_paths(&0, &5), 8);
+        assert_eq!(dag.count_paths(&5, &0), 0);
+        assert_eq!(dag.count_paths(&0, &200), 0);
+        // The 200th Fibonacci number does not fit, so the count saturates.
+        assert_eq!(dag.count_paths(&0, &199), u128::MAX);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn fold_topological() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 3) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+
+        // Count the paths from the roots to each node.
+        let paths = dag.fold_topological(|_| 1, |_, ps: Vec<&u32>| ps.into_iter().sum());
+        assert_eq!(paths, BTreeMap::from([(&0, 1), (&1, 1), (&2, 1), (&3, 2)]));
+
+        // Predecessors' values are passed in ascending order.
+        let predecessors = dag.fold_topological(
+            |x| vec![*x],
+            |x, xs: Vec<&Vec<usize>>| xs.into_iter().map(|v| v[v.len() - 1]).chain([*x]).collect(),
+        );
+        assert_eq!(predecessors[&3], [1, 2, 3]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn fold_reverse_topological() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 3) and (2, 3), where
+        // each node's size is its value.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+
+        // The cumulative size of each node and all its transitive
+        // dependencies, counting shared dependencies once.
+        let dependencies = dag.fold_reverse_topological(
+            |x| BTreeSet::from([*x]),
+            |x, deps: Vec<&BTreeSet<usize>>| {
+                deps.into_iter().flatten().copied().chain([*x]).collect()
+            },
+        );
+        let sizes: BTreeMap<&usize, usize> = dependencies
+            .iter()
+            .map(|(x, deps)| (*x, deps.iter().sum()))
+            .collect();
+        assert_eq!(sizes, BTreeMap::from([(&0, 6), (&1, 4), (&2, 5), (&3, 3)]));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn interval_labels() -> Result<(), Error> {
+        // Add sixty nodes, and pseudo-random edges from higher to lower
+        // vertices.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..60 {
+            dag.add_vertex(x);
+        }
+        let mut seed: usize = 11;
+        for x in 0..60 {
+            for y in (x + 1)..60 {
+                seed = (seed * 1103515245 + 12345) % 2147483648;
+                if seed.is_multiple_of(16) {
+                    dag.add_edge(y, x)?;
+                }
+            }
+        }
+
+        // The labels agree with a search of the dag for every pair.
+        let labels = dag.build_interval_labels();
+        for x in 0..60 {
+            let descendants: BTreeSet<&usize> = dag.descendants_of(&x);
+            for y in 0..60 {
+                assert_eq!(labels.is_ancestor(&x, &y)?, descendants.contains(&y));
+            }
+        }
+        assert_eq!(labels.is_ancestor(&0, &60), Err(Error::VertexDoesNotExist));
+
+        // A tree needs a single interval per node.
+        let mut tree: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..15 {
+            tree.add_vertex(x);
+        }
+        for x in 1..15 {
+            tree.add_edge((x - 1) / 2, x)?;
+        }
+        assert_eq!(tree.build_interval_labels().label_len(), 15);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn mount() -> Result<(), Error> {
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+        // Add a fragment of two nodes, and an edge (0, 1).
+        let mut fragment: BTreeDAG<usize> = BTreeDAG::new();
+        fragment.add_vertex(0);
+        fragment.add_vertex(1);
+        fragment.add_edge(0, 1)?;
+
+        // Mount the fragment at 10, between 0 and 1.
+        dag.mount(fragment.clone(), |v| v + 10, [(0, 10), (11, 1)])?;
+        assert_eq!(dag.topological_sort(), [&0, &10, &11, &1]);
+
+        // Mounting fails, leaving the dag unchanged, on a collision, a
+        // missing boundary vertex or a cycle.
+        let before: BTreeDAG<usize> = dag.clone();
+        assert_eq!(
+            dag.mount(fragment.clone(), |v| v + 10, []),
+            Err(Error::VertexExists)
+        );
+        assert_eq!(
+            dag.mount(fragment.clone(), |_| 20, []),
+            Err(Error::VertexExists)
+        );
+        assert_eq!(
+            dag.mount(fragment.clone(), |v| v + 20, [(21, 30)]),
+            Err(Error::VertexDoesNotExist)
+        );
+        assert_eq!(
+            dag.mount(fragment, |v| v + 20, [(1, 20), (21, 0)]),
+            Err(Error::EdgeExists)
+        );
+        assert_eq!(dag, before);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn find_matches() -> Result<(), Error> {
+        // Add five nodes, with a diamond (0, 1), (0, 2), (1, 3), (2, 3), and
+        // an edge (3, 4).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..5 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(3, 4)?;
+        // A diamond pattern.
+        let mut diamond: BTreeDAG<char> = BTreeDAG::new();
+        for p in ['a', 'b', 'c', 'd'] {
+            diamond.add_vertex(p);
+        }
+        diamond.add_edge('a', 'b')?;
+        diamond.add_edge('a', 'c')?;
+        diamond.add_edge('b', 'd')?;
+        diamond.add_edge('c', 'd')?;
+
+        // The diamond matches in both mirror images.
+        let matches = dag.find_matches(&diamond, |_, _| true);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            matches[0],
+            BTreeMap::from([(&'a', &0), (&'b', &1), (&'c', &2), (&'d', &3)])
+        );
+        assert_eq!(matches[1][&'b'], &2);
+
+        // The matcher restricts which nodes each pattern node may match.
+        let matches = dag.find_matches(&diamond, |p, t| *p != 'b' || *t == 2);
+        assert_eq!(matches.len(), 1);
+        assert!(dag
+            .find_matches(&diamond, |p, t| *p != 'd' || *t == 4)
+            .is_empty());
+
+        // A pattern without edges matches every injective mapping.
+        let mut pair: BTreeDAG<char> = BTreeDAG::new();
+        pair.add_vertex('a');
+        pair.add_vertex('b');
+        assert_eq!(dag.find_matches(&pair, |_, _| true).len(), 20);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn query() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 3) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+
+        assert_eq!(
+            dag.query().from(&0).out().collect(),
+            BTreeSet::from([&1, &2])
+        );
+        assert_eq!(
+            dag.query().from(&0).out().out().collect(),
+            BTreeSet::from([&3])
+        );
+        assert_eq!(
+            dag.query().from(&3).in_().collect(),
+            BTreeSet::from([&1, &2])
+        );
+        assert_eq!(
+            dag.query().from(&1).descendants().collect(),
+            BTreeSet::from([&3])
+        );
+        assert_eq!(
+            dag.query().all().filter(|x| x % 2 == 1).collect(),
+            BTreeSet::from([&1, &3])
+        );
+        assert!(dag.query().from(&4).out().collect().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn merge_concurrent() -> Result<(), Error> {
+        // Two replicas of three nodes diverge: one adds an edge (0, 1) and
+        // (1, 2), the other an edge (2, 0) and a node 3.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        let mut replica: BTreeDAG<usize> = dag.clone();
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        replica.add_edge(2, 0)?;
+        replica.add_vertex(3);
+
+        // Without priorities, the greatest edge closing the cycle is dropped,
+        // whichever replica merges.
+        let mut merged: BTreeDAG<usize> = dag.clone();
+        let mut other: BTreeDAG<usize> = replica.clone();
+        assert_eq!(
+            merged.merge_concurrent(&replica, |_, _| ()),
+            BTreeSet::from([(2, 0)])
+        );
+        assert_eq!(
+            other.merge_concurrent(&dag, |_, _| ()),
+            BTreeSet::from([(2, 0)])
+        );
+        assert_eq!(merged, other);
+        assert_eq!(merged.vertices().len(), 4);
+
+        // The lowest priority edge closing the cycle is dropped.
+        let mut merged: BTreeDAG<usize> = dag.clone();
+        let dropped = merged.merge_concurrent(&replica, |x, _| *x != 1);
+        assert_eq!(dropped, BTreeSet::from([(1, 2)]));
+        assert!(merged.adjacent(2, 0)?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn collect_unreachable() -> Result<(), Error> {
+        // Add five nodes, and an edge (0, 1), (2, 1), (2, 3) and (4, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..5 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(2, 1)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(4, 3)?;
+
+        // Only 0 and 4 are roots, so 2 is collected, but 1 and 3 are not.
+        let collected = dag.collect_unreachable(&BTreeSet::from([0, 4, 5]));
+        assert_eq!(collected, BTreeSet::from([2]));
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &1, &3, &4]));
+        assert!(dag.adjacent(4, 3)?);
+
+        // Without roots, every node is collected.
+        assert_eq!(dag.collect_unreachable(&BTreeSet::new()).len(), 4);
+        assert!(dag.vertices().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn view() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2), (0, 3) and (3, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(0, 3)?;
+        dag.add_edge(3, 2)?;
+
+        // Hide the vertex 1 and the edge (3, 2).
+        let view = dag.view(|v| *v != 1, |x, y| (*x, *y) != (3, 2));
+        assert!(!view.contains(&1));
+        assert_eq!(view.vertices(), BTreeSet::from([&0, &2, &3]));
+        assert_eq!(view.connections(&0), Some(BTreeSet::from([&3])));
+        assert_eq!(view.connections(&1), None);
+        assert!(!view.adjacent(&3, &2)?);
+        assert_eq!(view.adjacent(&0, &1), Err(Error::VertexDoesNotExist));
+        assert_eq!(view.descendants(&0), BTreeSet::from([&3]));
+        assert!(!view.is_reachable(&0, &2));
+        assert_eq!(view.topological_sort(), [&0, &2, &3]);
+
+        // The dag itself is unchanged.
+        assert!(dag.adjacent(3, 2)?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn explain() -> Result<(), Error> {
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+
+        // A missing vertex of an edge is named by the context.
+        let err = dag.add_edge(2, 1).unwrap_err();
+        assert_eq!(
+            dag.explain_edge(err, &2, &1),
+            ContextError {
+                error: Error::VertexDoesNotExist,
+                context: ErrorContext::Vertex(2),
+            }
+        );
+        let err = dag.add_edge(1, 3).unwrap_err();
+        assert_eq!(
+            dag.explain_edge(err, &1, &3).context,
+            ErrorContext::Vertex(3)
+        );
+
+        // Any other error is attributed to the edge.
+        let err = dag.add_edge(1, 0).unwrap_err();
+        assert_eq!(
+            dag.explain_edge(err, &1, &0),
+            ContextError {
+                error: Error::EdgeExists,
+                context: ErrorContext::Edge(1, 0),
+            }
+        );
+        let err = dag.remove_vertex(4).unwrap_err();
+        assert_eq!(dag.explain_vertex(err, &4).context, ErrorContext::Vertex(4));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn prune_diamond() -> Result<(), Error> {
+        // Add five nodes, and an edge (0, 1), (0, 2), (1, 3), (2, 3) and (4, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..5 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(4, 3)?;
+
+        // A descendant reachable along two paths is only removed once.
+        dag.prune(0)?;
+        assert_eq!(dag.vertices(), BTreeSet::from([&4]));
+        assert_eq!(dag.get_vertex_value(4), Some(&BTreeSet::new()));
+
+        // Pruning a missing vertex leaves the dag unchanged.
+        let before: BTreeDAG<usize> = dag.clone();
+        assert_eq!(dag.prune(0), Err(Error::VertexDoesNotExist));
+        assert_eq!(dag, before);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn find_vertices() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2) and (3, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(3, 2)?;
+
+        let odd: Vec<&usize> = dag.find_vertices(|v| v % 2 == 1).collect();
+        assert_eq!(odd, [&1, &3]);
+        let into_2: Vec<(&usize, &usize)> = dag.find_edges(|_, y| *y == 2).collect();
+        assert_eq!(into_2, [(&1, &2), (&3, &2)]);
+        assert_eq!(dag.find_edges(|x, y| x > y).count(), 1);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn range() -> Result<(), Error> {
+        // Add six nodes, and an edge from 0 to every other node.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        for y in 1..6 {
+            dag.add_edge(0, y)?;
+        }
+
+        assert_eq!(dag.range(2..4).collect::<Vec<&usize>>(), [&2, &3]);
+        assert_eq!(dag.range(4..).count(), 2);
+        let adjacent: Vec<&usize> = dag.connections_range(&0, ..=2).unwrap().collect();
+        assert_eq!(adjacent, [&1, &2]);
+        assert_eq!(dag.connections_range(&1, ..).unwrap().count(), 0);
+        assert!(dag.connections_range(&6, ..).is_none());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn ordered_vertices() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 2), (1, 2) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert_eq!(dag.first_vertex(), None);
+        assert_eq!(dag.pop_first_root(), None);
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+
+        assert_eq!(dag.first_vertex(), Some(&0));
+        assert_eq!(dag.last_vertex(), Some(&3));
+
+        // Roots are popped in ascending order, as they become roots.
+        let mut popped: Vec<usize> = Vec::new();
+        while let Some((root, _)) = dag.pop_first_root() {
+            popped.push(root);
+        }
+        assert_eq!(popped, [0, 1, 2, 3]);
+        assert!(dag.vertices().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn split_off_range() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2), (3, 2) and (3, 0).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(3, 2)?;
+        dag.add_edge(3, 0)?;
+
+        let (upper, dropped) = dag.split_off_range(&2);
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &1]));
+        assert_eq!(upper.vertices(), BTreeSet::from([&2, &3]));
+        assert!(dag.adjacent(0, 1)?);
+        assert!(upper.adjacent(3, 2)?);
+        assert_eq!(dropped, BTreeSet::from([(1, 2), (3, 0)]));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn append() -> Result<(), Error> {
+        // Add two shards with interleaved vertices, and an edge in each.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(2);
+        dag.add_edge(2, 0)?;
+        let mut shard: BTreeDAG<usize> = BTreeDAG::new();
+        shard.add_vertex(1);
+        shard.add_vertex(3);
+        shard.add_edge(1, 3)?;
+
+        dag.append(&mut shard)?;
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &1, &2, &3]));
+        assert!(dag.adjacent(1, 3)?);
+        assert!(shard.vertices().is_empty());
+
+        // Overlapping dags are left unchanged.
+        let mut overlap: BTreeDAG<usize> = BTreeDAG::new();
+        overlap.add_vertex(3);
+        overlap.add_vertex(4);
+        assert_eq!(dag.append(&mut overlap), Err(Error::VertexExists));
+        assert_eq!(dag.vertices().len(), 4);
+        assert_eq!(overlap.vertices().len(), 2);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn connect_across() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(2, 3)?;
+
+        // A cycle formed by the batch alone leaves the dag unchanged.
+        let before: BTreeDAG<usize> = dag.clone();
+        assert_eq!(dag.connect_across([(1, 2), (3, 0)]), Err(Error::EdgeExists));
+        assert_eq!(
+            dag.connect_across([(1, 2), (3, 4)]),
+            Err(Error::VertexDoesNotExist)
+        );
+        assert_eq!(dag, before);
+
+        dag.connect_across([(1, 2), (0, 3)])?;
+        assert!(dag.adjacent(1, 2)?);
+        assert!(dag.adjacent(0, 3)?);
+        assert_eq!(dag.connect_across([(3, 1)]), Err(Error::EdgeExists));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn from_adjacency() -> Result<(), Error> {
+        // An adjacency list of two edges (0, 1) and (1, 2), with 2 not listed.
+        let mut adjacency: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+        adjacency.insert(0, BTreeSet::from([1]));
+        adjacency.insert(1, BTreeSet::from([2]));
+
+        assert_eq!(
+            BTreeDAG::try_from(adjacency.clone()),
+            Err(Error::VertexDoesNotExist)
+        );
+        let dag: BTreeDAG<usize> =
+            BTreeDAG::from_adjacency(adjacency.clone(), MissingVertices::Create)?;
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &1, &2]));
+        assert!(dag.adjacent(1, 2)?);
+
+        // A cycle is rejected either way.
+        adjacency.insert(2, BTreeSet::from([0]));
+        assert_eq!(
+            BTreeDAG::try_from(adjacency.clone()),
+            Err(Error::EdgeExists)
+        );
+        assert_eq!(
+            BTreeDAG::from_adjacency(adjacency, MissingVertices::Create),
+            Err(Error::EdgeExists)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn into_inner() -> Result<(), Error> {
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+
+        let snapshot: BTreeDAG<usize> = dag.snapshot();
+        assert!(Arc::ptr_eq(&dag.as_map()[&0], &snapshot.as_map()[&0]));
+        let mut expected: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+        expected.insert(0, BTreeSet::from([1]));
+        expected.insert(1, BTreeSet::new());
+        assert_eq!(dag.into_inner(), expected);
+        assert_eq!(
+            BTreeDAG::try_from(snapshot.into_inner())?.vertices().len(),
+            2
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn to_edge_list() -> Result<(), Error> {
+        // Add three nodes, and an edge (2, 0) and (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..3 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(2, 0)?;
+        dag.add_edge(0, 1)?;
+
+        let edges: Vec<(usize, usize)> = dag.to_edge_list();
+        assert_eq!(edges, [(0, 1), (2, 0)]);
+        assert_eq!(dag.to_vertex_list(), [0, 1, 2]);
+        assert_eq!(BTreeDAG::from_edges(edges)?, dag);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn degree_histogram() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (0, 3) and (1, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert!(dag.out_degree_histogram().is_empty());
+        for x in 0..4 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(0, 3)?;
+        dag.add_edge(1, 3)?;
+
+        assert_eq!(
+            dag.out_degree_histogram(),
+            BTreeMap::from([(0, 2), (1, 1), (3, 1)])
+        );
+        assert_eq!(
+            dag.in_degree_histogram(),
+            BTreeMap::from([(0, 1), (1, 2), (2, 1)])
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn top_k() -> Result<(), Error> {
+        // Add 70 nodes, a chain (0, 1), ..., (68, 69), and an edge from 69 to
+        // 2 and from 68 to 3.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..70 {
+            dag.add_vertex(x);
+        }
+        for x in 4..69 {
+            dag.add_edge(x, x + 1)?;
+        }
+        dag.add_edge(69, 2)?;
+        dag.add_edge(68, 3)?;
+        dag.add_edge(0, 1)?;
+
+        assert_eq!(dag.top_k_by_out_degree(2), [(&68, 2), (&0, 1)]);
+        assert_eq!(
+            dag.top_k_by_descendant_count(3),
+            [(&4, 67), (&5, 66), (&6, 65)]
+        );
+        assert_eq!(dag.top_k_by_descendant_count(100).len(), 70);
+        assert!(dag.top_k_by_out_degree(0).is_empty());
+
+        // Descendants are counted across chunks of vertices: in a chain of
+        // 5000 nodes, with an edge from the last to a vertex before the first,
+        // each vertex's descendants span both chunks.
+        let dag: BTreeDAG<usize> =
+            BTreeDAG::from_edges((1..5000).map(|x| (x, x + 1)).chain([(5000, 0)]))?;
+        assert_eq!(dag.top_k_by_descendant_count(2), [(&1, 5000), (&2, 4999)]);
+        let counts: Vec<(&usize, usize)> = dag.top_k_by_descendant_count(5001);
+        for (v, count) in counts.iter() {
+            // The descendants of v are v + 1, ..., 5000 and 0.
+            assert_eq!(*count, if **v == 0 { 0 } else { 5001 - **v });
+        }
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn impact() -> Result<(), Error> {
+        // Add six nodes, and an edge (0, 2), (1, 2), (2, 3), (1, 4).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(1, 4)?;
+
+        assert_eq!(dag.impact(&BTreeSet::from([0])), BTreeSet::from([&2, &3]));
+        assert_eq!(
+            dag.impact(&BTreeSet::from([0, 1, 5, 9])),
+            BTreeSet::from([&2, &3, &4])
+        );
+        assert_eq!(
+            dag.impact(&BTreeSet::from([1, 2])),
+            BTreeSet::from([&2, &3, &4])
+        );
+        assert!(dag.impact(&BTreeSet::new()).is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_cover() -> Result<(), Error> {
+        // Add seven nodes, where 0 reaches 3 and 4, 1 reaches 4 and 5, and 2
+        // reaches 3, 4 and 5, and 6 is isolated.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..7 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 3)?;
+        dag.add_edge(0, 4)?;
+        dag.add_edge(1, 4)?;
+        dag.add_edge(1, 5)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(2, 4)?;
+        dag.add_edge(2, 5)?;
+
+        assert_eq!(
+            dag.minimal_cover(&BTreeSet::from([3, 4, 5]))?,
+            BTreeSet::from([&2])
+        );
+        assert_eq!(
+            dag.minimal_cover(&BTreeSet::from([4, 6]))?,
+            BTreeSet::from([&0, &6])
+        );
+        assert!(dag.minimal_cover(&BTreeSet::new())?.is_empty());
+        assert_eq!(
+            dag.minimal_cover(&BTreeSet::from([7])),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn suggest_layout() -> Result<(), Error> {
+        // Add six nodes, where 0 and 1 are roots, with edges crossing between
+        // (0, 3), (1, 2), and a longer path (0, 4), (4, 5), (3, 5).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 3)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(0, 4)?;
+        dag.add_edge(4, 5)?;
+        dag.add_edge(3, 5)?;
+
+        let layout = dag.suggest_layout();
+        assert_eq!(layout.layers().len(), 3);
+        assert_eq!(layout.layers()[0], [&0, &1]);
+        // Ordering by barycenter uncrosses (0, 3) and (1, 2).
+        assert_eq!(layout.layers()[1], [&3, &4, &2]);
+        assert_eq!(layout.position(&5), Some((2, 0)));
+        assert_eq!(layout.position(&6), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn envelope_json() -> Result<(), Error> {
+        use alloc::string::String;
+        use try_encoding_from::serde_json;
+
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(1, 2), (0, 2)])?;
+        let json: String = serde_json::to_string(&dag.to_envelope())?;
+        assert_eq!(
+            json,
+            "{\"version\":2,\"vertices\":[0,1,2],\"edges\":[[0,2],[1,2]]}"
+        );
+        let envelope: Envelope<usize> = serde_json::from_str(&json)?;
+        assert_eq!(BTreeDAG::try_from(envelope)?, dag);
+
+        // The derived format of the dag is migrated from version 1.
+        let legacy: String = serde_json::to_string(&dag)?;
+        let envelope: Envelope<usize> = serde_json::from_str(&legacy)?;
+        assert_eq!(envelope, dag.to_envelope());
+
+        // Newer versions, and mismatched vertices, are rejected.
+        assert!(serde_json::from_str::<Envelope<usize>>(
+            "{\"version\":3,\"vertices\":[],\"edges\":[]}"
+        )
+        .is_err());
+        assert!(
+            serde_json::from_str::<Envelope<usize>>("{\"version\":2,\"vertices\":{\"0\":[]}}")
+                .is_err()
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn intern_adjacency() -> Result<(), Error> {
+        // Add six nodes, with 0, 1 and 2 each having an edge to 4 and 5.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        for x in 0..3 {
+            dag.add_edge(x, 4)?;
+            dag.add_edge(x, 5)?;
+        }
+
+        // The sets of 1 and 2 share the set of 0, and the empty sets of 4
+        // and 5 share the set of 3.
+        assert_eq!(dag.intern_adjacency(), 4);
+        assert!(Arc::ptr_eq(&dag.vertices[&0], &dag.vertices[&2]));
+        assert_eq!(dag.intern_adjacency(), 0);
+
+        // A vertex whose set diverges gets its own copy.
+        dag.remove_edge(1, 5)?;
+        assert!(!Arc::ptr_eq(&dag.vertices[&0], &dag.vertices[&1]));
+        assert_eq!(dag.get_vertex_value(0), Some(&BTreeSet::from([4, 5])));
+        assert_eq!(dag.get_vertex_value(1), Some(&BTreeSet::from([4])));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_traversal_depth() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add a chain 0 -> 1 -> ... -> 9.
+        for i in 0..10 {
+            dag.add_vertex(i);
+        }
+        for i in 0..9 {
+            dag.add_edge(i, i + 1)?;
+        }
+        assert_eq!(dag.max_traversal_depth(), None);
+
+        dag.set_max_traversal_depth(Some(3));
+        assert_eq!(dag.max_traversal_depth(), Some(3));
+        // A shallow cycle check completes.
+        assert!(dag.add_edge(6, 8).is_ok());
+        assert_eq!(dag.add_edge(2, 0), Err(Error::EdgeExists));
+        // A deep one is stopped, leaving the dag unchanged.
+        assert_eq!(dag.add_edge(9, 0), Err(Error::DepthLimitExceeded));
+        assert!(!dag.adjacent(9, 0)?);
+        // The setting is not part of the value of the dag.
+        let mut unlimited: BTreeDAG<usize> = dag.clone();
+        unlimited.set_max_traversal_depth(None);
+        assert_eq!(dag, unlimited);
+        assert_eq!(unlimited.add_edge(9, 0), Err(Error::EdgeExists));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn mutation_op() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        let mut other: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes to the other dag.
+        other.add_vertex(1);
+        other.add_vertex(2);
+        other.add_edge(2, 1)?;
+
+        let ops: Vec<MutationOp<usize>> = vec![
+            MutationOp::AddVertex(0),
+            MutationOp::AddVertex(1),
+            MutationOp::from(Mutation::AddEdge(0, 1)),
+            MutationOp::Merge(other.clone()),
+        ];
+        for op in ops {
+            op.apply(&mut dag)?;
+        }
+        assert!(dag.adjacent(0, 1)?);
+        assert!(dag.adjacent(2, 1)?);
+
+        // Failed operations leave the dag unchanged.
+        let before: BTreeDAG<usize> = dag.clone();
+        assert_eq!(
+            MutationOp::AddEdge(1, 0).apply(&mut dag),
+            Err(Error::EdgeExists)
+        );
+        let mut cyclic: BTreeDAG<usize> = BTreeDAG::new();
+        cyclic.add_vertex(0);
+        cyclic.add_vertex(1);
+        cyclic.add_edge(1, 0)?;
+        assert_eq!(
+            MutationOp::Merge(cyclic).apply(&mut dag),
+            Err(Error::EdgeExists)
+        );
+        assert_eq!(dag, before);
+
+        MutationOp::Prune(2).apply(&mut dag)?;
+        assert_eq!(dag.vertices().len(), 1);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn check_invariants() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes.
+        for i in 0..4 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        assert_eq!(dag.check_invariants(), Ok(()));
+        // An edge from a vertex to itself is a cycle.
+        assert_eq!(dag.add_edge(3, 3), Err(Error::EdgeExists));
+
+        // Corrupt the adjacency list directly, as code within the crate can.
+        let mut cyclic: BTreeDAG<usize> = dag.clone();
+        Arc::make_mut(cyclic.vertices.get_mut(&2).unwrap()).insert(1);
+        Arc::make_mut(cyclic.vertices.get_mut(&3).unwrap()).insert(2);
+        assert_eq!(
+            cyclic.check_invariants(),
+            Err(InvariantViolation::Cycle(vec![&1, &2]))
+        );
+        let mut dangling: BTreeDAG<usize> = dag.clone();
+        Arc::make_mut(dangling.vertices.get_mut(&0).unwrap()).insert(7);
+        assert_eq!(
+            dangling.check_invariants(),
+            Err(InvariantViolation::DanglingEdge(&0, &7))
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "debug-invariants")]
+    #[should_panic(expected = "vertices [1, 2] form a cycle")]
+    fn debug_invariants() {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes.
+        for i in 0..3 {
+            dag.add_vertex(i);
+        }
+        Arc::make_mut(dag.vertices.get_mut(&2).unwrap()).insert(1);
+        Arc::make_mut(dag.vertices.get_mut(&1).unwrap()).insert(2);
+        // The next mutation finds the corrupted adjacency list.
+        dag.add_vertex(3);
+    }
+
+    #[test]
+    fn cycle_check_fast_path() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add a chain 0 -> 1 -> 2, and the vertex 3.
+        for i in 0..4 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // With no traversal allowed, only the edges which need no search
+        // can be added.
+        dag.set_max_traversal_depth(Some(0));
+        // The vertex 2 has no adjacent vertices.
+        assert!(dag.add_edge(0, 2).is_ok());
+        // The edge (0, 1) already exists.
+        assert!(dag.add_edge(0, 1).is_ok());
+        assert_eq!(dag.add_edge(3, 0), Err(Error::DepthLimitExceeded));
+        assert_eq!(dag.add_edge(1, 1), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(1, 4), Err(Error::VertexDoesNotExist));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_from_edges() -> Result<(), Error> {
+        // Add a layered dag, where each vertex depends on the vertices of the
+        // next layer.
+        let edges: Vec<(usize, usize)> = (0..90)
+            .flat_map(|x| {
+                let next = (x / 10 + 1) * 10;
+                (next..next + 10).map(move |y| (x, y))
+            })
+            .collect();
+        let dag: BTreeDAG<usize> = BTreeDAG::par_from_edges(edges.clone())?;
+        assert_eq!(dag, BTreeDAG::from_edges(edges.clone())?);
+        assert_eq!(dag.vertices().len(), 100);
+
+        // A single edge back to the first layer closes a cycle.
+        let mut cyclic: Vec<(usize, usize)> = edges;
+        cyclic.push((99, 0));
+        assert_eq!(
+            BTreeDAG::par_from_edges(cyclic).unwrap_err(),
+            Error::EdgeExists
+        );
+        assert_eq!(
+            BTreeDAG::par_from_edges(vec![(0, 0)]).unwrap_err(),
+            Error::EdgeExists
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn cursor() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes, and the edges (0, 2), (1, 2), (2, 3) and (2, 4).
+        for i in 0..5 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(2, 4)?;
+        assert!(dag.cursor(&5).is_none());
+
+        let mut cursor = dag.cursor(&2).unwrap();
+        assert_eq!(cursor.current(), &2);
+        assert_eq!(
+            cursor.out_neighbors().collect::<Vec<&usize>>(),
+            vec![&3, &4]
+        );
+        assert_eq!(cursor.in_neighbors(), &[&0, &1]);
+        // Stepping past the neighbors does not move the cursor.
+        assert_eq!(cursor.step_out(2), None);
+        assert_eq!(cursor.back(), None);
+
+        assert_eq!(cursor.step_out(1), Some(&4));
+        assert_eq!(cursor.in_neighbors(), &[&2]);
+        assert_eq!(cursor.step_in(0), Some(&2));
+        assert_eq!(cursor.step_in(1), Some(&1));
+        assert!(cursor.in_neighbors().is_empty());
+        assert_eq!(cursor.path(), &[&2, &4, &2, &1]);
+        assert_eq!(cursor.back(), Some(&2));
+        assert_eq!(cursor.back(), Some(&4));
+        assert_eq!(cursor.path(), &[&2, &4]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn traverse_with() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes, and the edges (0, 1), (0, 2), (1, 3) and (2, 4).
+        for i in 0..6 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 4)?;
+        let descending = |a: &usize, b: &usize| b.cmp(a);
+
+        assert_eq!(dag.dfs_with(&6, KeyOrder), None);
+        assert_eq!(dag.dfs_with(&0, KeyOrder), Some(vec![&0, &1, &3, &2, &4]));
+        assert_eq!(dag.dfs_with(&0, descending), Some(vec![&0, &2, &4, &1, &3]));
+        assert_eq!(dag.bfs_with(&0, KeyOrder), Some(vec![&0, &1, &2, &3, &4]));
+        assert_eq!(dag.bfs_with(&0, descending), Some(vec![&0, &2, &1, &4, &3]));
+
+        assert_eq!(dag.topological_sort_with(KeyOrder), dag.topological_sort());
+        assert_eq!(
+            dag.topological_sort_with(descending),
+            vec![&5, &0, &2, &4, &1, &3]
+        );
+        // Vertices the order does not distinguish are visited in ascending
+        // order.
+        let odd_first = |a: &usize, b: &usize| (b % 2).cmp(&(a % 2));
+        assert_eq!(
+            dag.topological_sort_with(odd_first),
+            vec![&5, &0, &1, &3, &2, &4]
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn repair() -> Result<(), Error> {
+        // Construct an adjacency list with a cycle (0, 1, 2), a self-loop on
+        // the vertex 3, and a dangling edge (3, 4), as deserializing could.
+        let adjacency = |edges: &[(usize, usize)]| -> BTreeMap<usize, Arc<BTreeSet<usize>>> {
+            let mut vertices: BTreeMap<usize, Arc<BTreeSet<usize>>> =
+                (0..4).map(|i| (i, Arc::new(BTreeSet::new()))).collect();
+            for (x, y) in edges {
+                Arc::make_mut(vertices.get_mut(x).unwrap()).insert(*y);
+            }
+            vertices
+        };
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 3), (3, 4)];
+
+        let mut dag: BTreeDAG<usize> = BTreeDAG::from_vertices(adjacency(&edges));
+        let report = dag.repair(DanglingEdges::Create);
+        assert_eq!(report.created_vertices, BTreeSet::from([4]));
+        assert_eq!(report.dropped_edges.len(), 2);
+        assert!(report.dropped_edges.contains(&(3, 3)));
+        assert_eq!(dag.check_invariants(), Ok(()));
+        assert!(dag.adjacent(3, 4)?);
+
+        let mut dag: BTreeDAG<usize> = BTreeDAG::from_vertices(adjacency(&edges));
+        let report = dag.repair(DanglingEdges::Drop);
+        assert!(report.created_vertices.is_empty());
+        assert_eq!(report.dropped_edges.len(), 3);
+        assert!(report.dropped_edges.contains(&(3, 4)));
+        assert_eq!(dag.check_invariants(), Ok(()));
+        assert_eq!(dag.vertices().len(), 4);
+
+        // A repaired dag needs no further repair.
+        assert!(dag.repair(DanglingEdges::Drop).is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn distances_from() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add a chain 0 -> 1 -> 2 -> 3 -> 4, with the edge (5, 3).
+        for i in 0..7 {
+            dag.add_vertex(i);
+        }
+        for i in 0..4 {
+            dag.add_edge(i, i + 1)?;
+        }
+        dag.add_edge(5, 3)?;
+
+        let distances = dag.distances_from(&BTreeSet::from([0, 5, 7]));
+        let expected: BTreeMap<&usize, usize> =
+            BTreeMap::from([(&0, 0), (&1, 1), (&2, 2), (&3, 1), (&4, 2), (&5, 0)]);
+        assert_eq!(distances, expected);
+        // Each source alone agrees with descendants_within.
+        let within = dag.descendants_within(&0, 4);
+        let from_0 = dag.distances_from(&BTreeSet::from([0]));
+        assert!(within.iter().all(|(v, d)| from_0[v] == *d));
+        assert!(dag.distances_from(&BTreeSet::new()).is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn k_paths() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        // Add nodes, and the edges (0, 1), (0, 2), (0, 4), (1, 3), (2, 3),
+        // (2, 4) and (3, 4), so there are four paths from 0 to 4.
+        for i in 0..6 {
+            dag.add_vertex(i);
+        }
+        for (x, y) in [(0, 1), (0, 2), (0, 4), (1, 3), (2, 3), (2, 4), (3, 4)] {
+            dag.add_edge(x, y)?;
+        }
+        let vertices = |paths: Vec<Path<'_, usize>>| -> Vec<Vec<usize>> {
+            paths
+                .iter()
+                .map(|p| p.iter().map(|v| **v).collect())
+                .collect()
+        };
+
+        assert_eq!(
+            vertices(dag.k_shortest_paths(&0, &4, 3)?),
+            vec![vec![0, 4], vec![0, 2, 4], vec![0, 1, 3, 4]]
+        );
+        assert_eq!(
+            vertices(dag.k_longest_paths(&0, &4, 10)?),
+            vec![
+                vec![0, 1, 3, 4],
+                vec![0, 2, 3, 4],
+                vec![0, 2, 4],
+                vec![0, 4]
+            ]
+        );
+        assert_eq!(vertices(dag.k_shortest_paths(&4, &4, 2)?), vec![vec![4]]);
+        assert!(dag.k_shortest_paths(&0, &4, 0)?.is_empty());
+        assert!(dag.k_longest_paths(&4, &0, 2)?.is_empty());
+        assert!(dag.k_longest_paths(&5, &4, 2)?.is_empty());
+        assert_eq!(
+            dag.k_shortest_paths(&0, &6, 1).unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn progress() -> Result<(), Error> {
+        let edges: Vec<(usize, usize)> = (0..250).map(|v| (v, v + 1)).collect();
+        let mut reports: Vec<Progress> = Vec::new();
+        let mut dag: BTreeDAG<usize> =
+            BTreeDAG::from_edges_with_progress(edges, |progress| reports.push(progress))?;
+        assert_eq!(dag, BTreeDAG::from_edges((0..250).map(|v| (v, v + 1)))?);
+
+        // Progress is reported about once per percent, and once on completion.
+        assert_eq!(reports.len(), 125);
+        assert!(reports.windows(2).all(|w| w[0].processed < w[1].processed));
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                processed: 250,
+                total: 250
+            })
+        );
+        assert!(BTreeDAG::<usize>::from_edges_with_progress(vec![(0, 1), (1, 0)], |_| {}).is_err());
+
+        let mut last: Option<Progress> = None;
+        let closure = dag.transitive_closure_with_progress(|progress| last = Some(progress));
+        assert_eq!(closure[&0].len(), 250);
+        assert_eq!(closure[&249], BTreeSet::from([&250]));
+        assert!(closure[&250].is_empty());
+        assert_eq!(
+            last,
+            Some(Progress {
+                processed: 251,
+                total: 251
+            })
+        );
+
+        let mut last: Option<Progress> = None;
+        dag.prune_with_progress(100, |progress| last = Some(progress))?;
+        assert_eq!(dag.vertices().len(), 100);
+        assert!(dag.get_vertex_value(99).unwrap().is_empty());
+        assert_eq!(
+            last,
+            Some(Progress {
+                processed: 251,
+                total: 251
+            })
+        );
+        assert_eq!(
+            dag.prune_with_progress(100, |_| {}),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn empty_dag() {
+        let dag: BTreeDAG<usize> = BTreeDAG::new();
+
+        // Queries over the whole dag have empty results.
+        assert!(dag.topological_sort().is_empty());
+        assert!(dag.topological_ranks().is_empty());
+        assert!(dag.chain_decomposition().is_empty());
+        assert!(dag.roots().is_empty());
+        assert!(dag.earliest_start_times(|_| 1).is_empty());
+        assert!(dag.slack(|_| 1).is_empty());
+        assert!(dag.transitive_closure().is_empty());
+
+        // Queries which need a vertex have no result.
+        assert_eq!(dag.critical_path(|_| 1), Err(Error::EmptyGraph));
+        assert_eq!(dag.try_critical_path(|_| 1), None);
+        assert_eq!(dag.try_adjacent(&0, &1), None);
+        assert_eq!(dag.adjacent(0, 1), Err(Error::VertexDoesNotExist));
+
+        // Test passed.
+    }
+
+    #[test]
+    fn roots_and_critical_path() -> Result<(), Error> {
+        // Add five nodes, and an edge (3, 1), (3, 2), (1, 0) and (2, 0), where
+        // each node's duration is its value plus one.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for i in 0..5 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(3, 1)?;
+        dag.add_edge(3, 2)?;
+        dag.add_edge(1, 0)?;
+        dag.add_edge(2, 0)?;
+        let durations = |x: &usize| (*x + 1) as u64;
+
+        assert_eq!(dag.roots(), BTreeSet::from([&3, &4]));
+        assert_eq!(dag.critical_path(durations)?.as_slice(), [&3, &2, &0]);
+        // With equal durations the least of the longest paths is chosen.
+        assert_eq!(dag.critical_path(|_| 1)?.as_slice(), [&3, &1, &0]);
+
+        assert_eq!(dag.try_adjacent(&3, &1), Some(true));
+        assert_eq!(dag.try_adjacent(&1, &3), Some(false));
+        assert_eq!(dag.try_adjacent(&3, &5), None);
+        assert_eq!(dag.try_adjacent(&5, &3), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn try_mutate() -> Result<(), Error> {
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.try_add_edge(0, 1)?;
+
+        assert_eq!(
+            dag.try_add_edge(1, 0),
+            Err(ContextError {
+                error: Error::EdgeExists,
+                context: ErrorContext::Edge(1, 0),
+            })
+        );
+        assert_eq!(
+            dag.try_add_edge(0, 2).unwrap_err().context,
+            ErrorContext::Vertex(2)
+        );
+        assert_eq!(
+            dag.try_remove_edge(5, 0),
+            Err(ContextError {
+                error: Error::VertexDoesNotExist,
+                context: ErrorContext::Vertex(5),
+            })
+        );
+        assert_eq!(
+            dag.try_remove_vertex(2).unwrap_err().context,
+            ErrorContext::Vertex(2)
+        );
+        assert_eq!(
+            dag.try_prune(3).unwrap_err().context,
+            ErrorContext::Vertex(3)
+        );
+
+        // Successful mutations behave like the mutations they wrap.
+        dag.try_remove_edge(0, 1)?;
+        assert!(dag.get_vertex_value(0).unwrap().is_empty());
+        assert_eq!(dag.try_remove_vertex(1)?, BTreeSet::new());
+        dag.try_prune(0)?;
+        assert!(dag.vertices().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn chain_decomposition_rematch() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 2), (0, 3) and (1, 2), so 0 is first
+        // matched to 2, and must be rematched to 3 to make room for 1.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for i in 0..4 {
+            dag.add_vertex(i);
+        }
+        dag.add_edge(0, 2)?;
+        dag.add_edge(0, 3)?;
+        dag.add_edge(1, 2)?;
+
+        assert_eq!(dag.chain_decomposition(), vec![vec![&0, &3], vec![&1, &2]]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_traversal_depth_derived() -> Result<(), Error> {
+        // Add a chain 0 -> 1 -> ... -> 9, limited to a depth of 3.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::from_edges((0..9).map(|i| (i, i + 1)))?;
+        dag.set_max_traversal_depth(Some(3));
+        let other: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 1)])?;
+
+        // Every dag derived from the limited dag is limited too.
+        assert_eq!(dag.merge(&other)?.max_traversal_depth(), Some(3));
+        assert_eq!(dag.intersection(&other).max_traversal_depth(), Some(3));
+        assert_eq!(dag.difference(&other).max_traversal_depth(), Some(3));
+        let mut thawed = dag.freeze().thaw();
+        assert_eq!(thawed.max_traversal_depth(), Some(3));
+        assert_eq!(thawed.add_edge(9, 0), Err(Error::DepthLimitExceeded));
+        let (upper, _) = dag.clone().split_off_range(&5);
+        assert_eq!(upper.max_traversal_depth(), Some(3));
+
+        // A concurrent merge keeps the limit, but does not drop edges deeper
+        // than it, and the other dag's setting does not matter.
+        let mut merged = dag.clone();
+        let replica: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(9, 10)])?;
+        assert!(merged.merge_concurrent(&replica, |_, _| ()).is_empty());
+        assert_eq!(merged.max_traversal_depth(), Some(3));
+        assert!(merged.adjacent(9, 10)?);
+        assert_eq!(merged.add_edge(10, 0), Err(Error::DepthLimitExceeded));
+        let mut unlimited = other.clone();
+        unlimited.merge_concurrent(&dag, |_, _| ());
+        assert_eq!(unlimited.max_traversal_depth(), None);
+
+        // The setting is not part of the frozen value either.
+        assert_eq!(dag.freeze(), other.merge(&dag)?.freeze());
+
+        // Test passed.
+        Ok(())
+    }
+
+    /// A minimal binary format which, like bincode or postcard, is not
+    /// self-describing: `deserialize_any` is an error, and structs and tuples
+    /// are written as their fields in order.
+    #[cfg(feature = "serde")]
+    mod binary {
+        use alloc::string::{String, ToString};
+        use alloc::vec::Vec;
+        use core::convert::TryInto;
+        use core::fmt;
+        use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+        use serde::ser::{
+            self, Impossible, Serialize, SerializeSeq, SerializeStruct, SerializeTuple,
+        };
+
+        #[derive(Debug)]
+        pub struct Error(String);
+
+        impl fmt::Display for Error {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl std::error::Error for Error {}
+
+        impl ser::Error for Error {
+            fn custom<M: fmt::Display>(msg: M) -> Self {
+                Error(msg.to_string())
+            }
+        }
+
+        impl de::Error for Error {
+            fn custom<M: fmt::Display>(msg: M) -> Self {
+                Error(msg.to_string())
+            }
+        }
+
+        fn unsupported<V>() -> Result<V, Error> {
+            Err(Error(String::from("unsupported by the binary format")))
+        }
+
+        pub fn to_bytes<V: Serialize>(value: &V) -> Result<Vec<u8>, Error> {
+            let mut serializer = Serializer(Vec::new());
+            value.serialize(&mut serializer)?;
+            Ok(serializer.0)
+        }
+
+        pub fn from_bytes<'de, V: de::Deserialize<'de>>(bytes: &'de [u8]) -> Result<V, Error> {
+            let mut deserializer = Deserializer(bytes);
+            let value = V::deserialize(&mut deserializer)?;
+            if !deserializer.0.is_empty() {
+                return Err(Error(String::from("trailing bytes")));
+            }
+            Ok(value)
+        }
+
+        pub struct Serializer(Vec<u8>);
+
+        impl ser::Serializer for &mut Serializer {
+            type Ok = ();
+            type Error = Error;
+            type SerializeSeq = Self;
+            type SerializeTuple = Self;
+            type SerializeTupleStruct = Impossible<(), Error>;
+            type SerializeTupleVariant = Impossible<(), Error>;
+            type SerializeMap = Impossible<(), Error>;
+            type SerializeStruct = Self;
+            type SerializeStructVariant = Impossible<(), Error>;
+
+            fn serialize_u32(self, v: u32) -> Result<(), Error> {
+                self.0.extend_from_slice(&v.to_le_bytes());
+                Ok(())
+            }
+            fn serialize_u64(self, v: u64) -> Result<(), Error> {
+                self.0.extend_from_slice(&v.to_le_bytes());
+                Ok(())
+            }
+            fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+                match len {
+                    Some(len) => {
+                        self.serialize_u64(len as u64)?;
+                        Ok(self)
+                    }
+                    None => unsupported(),
+                }
+            }
+            fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+                Ok(self)
+            }
+            fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+                Ok(self)
+            }
+
+            fn serialize_bool(self, _: bool) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_i8(self, _: i8) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_i16(self, _: i16) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_i32(self, _: i32) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_i64(self, _: i64) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_u8(self, _: u8) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_u16(self, _: u16) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_f32(self, _: f32) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_f64(self, _: f64) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_char(self, _: char) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_str(self, _: &str) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_bytes(self, _: &[u8]) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_none(self) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_some<V: ?Sized + Serialize>(self, _: &V) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_unit(self) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_unit_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+            ) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_newtype_struct<V: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                _: &V,
+            ) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_newtype_variant<V: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: &V,
+            ) -> Result<(), Error> {
+                unsupported()
+            }
+            fn serialize_tuple_struct(
+                self,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeTupleStruct, Error> {
+                unsupported()
+            }
+            fn serialize_tuple_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeTupleVariant, Error> {
+                unsupported()
+            }
+            fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+                unsupported()
+            }
+            fn serialize_struct_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeStructVariant, Error> {
+                unsupported()
+            }
+        }
+
+        impl SerializeSeq for &mut Serializer {
+            type Ok = ();
+            type Error = Error;
+            fn serialize_element<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+                value.serialize(&mut **self)
+            }
+            fn end(self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        impl SerializeTuple for &mut Serializer {
+            type Ok = ();
+            type Error = Error;
+            fn serialize_element<V: ?Sized + Serialize>(&mut self, value: &V) -> Result<(), Error> {
+                value.serialize(&mut **self)
+            }
+            fn end(self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        impl SerializeStruct for &mut Serializer {
+            type Ok = ();
+            type Error = Error;
+            fn serialize_field<V: ?Sized + Serialize>(
+                &mut self,
+                _: &'static str,
+                value: &V,
+            ) -> Result<(), Error> {
+                value.serialize(&mut **self)
+            }
+            fn end(self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        pub struct Deserializer<'de>(&'de [u8]);
+
+        impl<'de> Deserializer<'de> {
+            fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+                if self.0.len() < N {
+                    return Err(Error(String::from("unexpected end of input")));
+                }
+                let (taken, rest) = self.0.split_at(N);
+                self.0 = rest;
+                Ok(taken.try_into().unwrap())
+            }
+        }
+
+        /// The elements of a sequence, a tuple or a struct, of known length.
+        struct Elements<'a, 'de> {
+            deserializer: &'a mut Deserializer<'de>,
+            remaining: usize,
+        }
+
+        impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+            type Error = Error;
+            fn next_element_seed<S: DeserializeSeed<'de>>(
+                &mut self,
+                seed: S,
+            ) -> Result<Option<S::Value>, Error> {
+                if self.remaining == 0 {
+                    return Ok(None);
+                }
+                self.remaining -= 1;
+                seed.deserialize(&mut *self.deserializer).map(Some)
+            }
+        }
+
+        impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+            type Error = Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+                unsupported()
+            }
+            fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.visit_u32(u32::from_le_bytes(self.take::<4>()?))
+            }
+            fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.visit_u64(u64::from_le_bytes(self.take::<8>()?))
+            }
+            fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let remaining = u64::from_le_bytes(self.take::<8>()?) as usize;
+                visitor.visit_seq(Elements {
+                    deserializer: self,
+                    remaining,
+                })
+            }
+            fn deserialize_tuple<V: Visitor<'de>>(
+                self,
+                len: usize,
+                visitor: V,
+            ) -> Result<V::Value, Error> {
+                visitor.visit_seq(Elements {
+                    deserializer: self,
+                    remaining: len,
+                })
+            }
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value, Error> {
+                self.deserialize_tuple(fields.len(), visitor)
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u128 f32 f64 char str string bytes
+                byte_buf option unit unit_struct newtype_struct tuple_struct map
+                enum identifier ignored_any
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn envelope_binary() -> Result<(), Error> {
+        // Add nodes.
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(1, 2), (0, 2)])?;
+        let bytes: Vec<u8> = binary::to_bytes(&dag.to_envelope()).unwrap();
+        let envelope: Envelope<usize> = binary::from_bytes(&bytes).unwrap();
+        assert_eq!(envelope, dag.to_envelope());
+        assert_eq!(BTreeDAG::try_from(envelope)?, dag);
+
+        // Newer versions are rejected.
+        let newer: Envelope<usize> = Envelope {
+            version: 3,
+            vertices: vec![],
+            edges: vec![],
+        };
+        let bytes: Vec<u8> = binary::to_bytes(&newer).unwrap();
+        assert!(binary::from_bytes::<Envelope<usize>>(&bytes).is_err());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn chain_decomposition_large() -> Result<(), Error> {
+        // Add two interleaved chains of 2000 nodes each, one through the even
+        // nodes and one through the odd nodes.
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges((0..3998).map(|i| (i, i + 2)))?;
+
+        let evens: Vec<usize> = (0..4000).step_by(2).collect();
+        let odds: Vec<usize> = (1..4000).step_by(2).collect();
+        assert_eq!(
+            dag.chain_decomposition(),
+            vec![evens.iter().collect::<Vec<&usize>>(), odds.iter().collect()]
+        );
+
+        // Test passed.
+        Ok(())
+    }
 }
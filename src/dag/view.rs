@@ -0,0 +1,133 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+type VertexPredicate<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+type EdgePredicate<'a, T> = Box<dyn Fn(&T, &T) -> bool + 'a>;
+
+/// `DagView` is a read-only view of the vertices and edges of a `BTreeDAG`
+/// which satisfy a pair of predicates. The view borrows the dag rather than
+/// copying it, evaluating the predicates as it is queried. An edge is only
+/// visible if both of its vertices are. A view is constructed through the
+/// `View` trait.
+pub struct DagView<'a, T>
+where
+    T: Ord,
+{
+    dag: &'a BTreeDAG<T>,
+    vertex_pred: VertexPredicate<'a, T>,
+    edge_pred: EdgePredicate<'a, T>,
+}
+
+impl<'a, T> DagView<'a, T>
+where
+    T: Ord,
+{
+    pub(crate) fn new(
+        dag: &'a BTreeDAG<T>,
+        vertex_pred: VertexPredicate<'a, T>,
+        edge_pred: EdgePredicate<'a, T>,
+    ) -> Self {
+        DagView {
+            dag,
+            vertex_pred,
+            edge_pred,
+        }
+    }
+
+    /// Returns true if the vertex x is visible.
+    pub fn contains(&self, x: &T) -> bool {
+        self.dag.vertices.contains_key(x) && (self.vertex_pred)(x)
+    }
+
+    /// Returns the visible vertices.
+    pub fn vertices(&self) -> BTreeSet<&'a T> {
+        self.dag
+            .vertices
+            .keys()
+            .filter(|v| (self.vertex_pred)(v))
+            .collect()
+    }
+
+    /// Returns the vertices which the visible vertex x has a visible edge to,
+    /// or None if x is not visible.
+    pub fn connections(&self, x: &T) -> Option<BTreeSet<&'a T>> {
+        let (x, adj_x) = self.dag.vertices.get_key_value(x)?;
+        if !(self.vertex_pred)(x) {
+            return None;
+        }
+        Some(
+            adj_x
+                .iter()
+                .filter(|y| (self.vertex_pred)(y) && (self.edge_pred)(x, y))
+                .collect(),
+        )
+    }
+
+    /// Returns true if the edge (x, y) is visible. An error is returned if x
+    /// or y is not visible.
+    pub fn adjacent(&self, x: &T, y: &T) -> Result<bool, Error> {
+        if !self.contains(y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        self.connections(x)
+            .map(|adj_x| adj_x.contains(y))
+            .ok_or(Error::VertexDoesNotExist)
+    }
+
+    /// Returns the vertices reachable from the vertex x by at least one
+    /// visible edge.
+    pub fn descendants(&self, x: &T) -> BTreeSet<&'a T> {
+        let mut visited: BTreeSet<&'a T> = BTreeSet::new();
+        let mut stack: Vec<&'a T> = self.connections(x).into_iter().flatten().collect();
+        while let Some(v) = stack.pop() {
+            if visited.insert(v) {
+                // Every vertex on the stack is visible, so we can safely
+                // unwrap.
+                stack.extend(self.connections(v).unwrap());
+            }
+        }
+        visited
+    }
+
+    /// Returns true if the vertex y is reachable from the vertex x by
+    /// visible edges.
+    pub fn is_reachable(&self, x: &T, y: &T) -> bool {
+        self.contains(x) && (x == y || self.descendants(x).contains(y))
+    }
+
+    /// Sorts the visible vertices topologically, always taking the least
+    /// vertex with no remaining incoming edges first.
+    pub fn topological_sort(&self) -> Vec<&'a T> {
+        let adjacency: BTreeMap<&'a T, BTreeSet<&'a T>> = self
+            .vertices()
+            .into_iter()
+            // Every vertex is visible, so we can safely unwrap.
+            .map(|v| (v, self.connections(v).unwrap()))
+            .collect();
+        let mut in_degrees: BTreeMap<&'a T, usize> = adjacency.keys().map(|v| (*v, 0)).collect();
+        for adj in adjacency.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        let mut ready: BTreeSet<&'a T> = in_degrees
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(v, _)| *v)
+            .collect();
+        let mut sorted: Vec<&'a T> = Vec::with_capacity(adjacency.len());
+        while let Some(v) = ready.pop_first() {
+            sorted.push(v);
+            for adj in adjacency[v].iter() {
+                let d = in_degrees.get_mut(adj).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    ready.insert(adj);
+                }
+            }
+        }
+        sorted
+    }
+}
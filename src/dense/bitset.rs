@@ -0,0 +1,75 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+const WORD_BITS: usize = 64;
+
+/// `BitSet` is a fixed capacity set of the integers below its capacity,
+/// stored one bit per integer.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub(crate) fn new(capacity: usize) -> Self {
+        BitSet {
+            words: vec![0; capacity.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Grows the set so it can hold the integers below capacity.
+    pub(crate) fn grow(&mut self, capacity: usize) {
+        let words = capacity.div_ceil(WORD_BITS);
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+    }
+
+    pub(crate) fn contains(&self, x: usize) -> bool {
+        self.words[x / WORD_BITS] & (1 << (x % WORD_BITS)) != 0
+    }
+
+    /// Adds x to the set, returning true if it was not already present.
+    pub(crate) fn insert(&mut self, x: usize) -> bool {
+        let present = self.contains(x);
+        self.words[x / WORD_BITS] |= 1 << (x % WORD_BITS);
+        !present
+    }
+
+    /// Removes x from the set, returning true if it was present.
+    pub(crate) fn remove(&mut self, x: usize) -> bool {
+        let present = self.contains(x);
+        self.words[x / WORD_BITS] &= !(1 << (x % WORD_BITS));
+        present
+    }
+
+    /// Adds every element of other to the set. Both sets must have the
+    /// same capacity.
+    pub(crate) fn union_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the elements of the set, in ascending order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * WORD_BITS + bit)
+            })
+        })
+    }
+}
@@ -0,0 +1,220 @@
+mod bitset;
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::default::Default;
+
+use crate::dag::{AddEdge, Adjacent, BTreeDAG, OutDegree, RemoveEdge};
+use crate::Error;
+use bitset::BitSet;
+
+/// `DenseDAG` is a directed acyclic graph over the vertices `0..len`, which
+/// stores the adjacency list and the transitive closure of each vertex as
+/// bitsets. Reachability queries take constant time, at the cost of
+/// quadratic memory and of updating the closure on each mutation. It is
+/// intended for dense graphs keyed by small integers, and converts to and
+/// from `BTreeDAG<usize>`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DenseDAG {
+    adjacency: Vec<BitSet>,
+    reachable: Vec<BitSet>,
+}
+
+impl DenseDAG {
+    /// Returns a dag with the vertices `0..len`, and no edges.
+    pub fn new(len: usize) -> Self {
+        DenseDAG {
+            adjacency: (0..len).map(|_| BitSet::new(len)).collect(),
+            reachable: (0..len).map(|_| BitSet::new(len)).collect(),
+        }
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Returns true if the dag has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    /// Adds a vertex, returning its key, which is the previous number of
+    /// vertices.
+    pub fn add_vertex(&mut self) -> usize {
+        let x = self.len();
+        for set in self.adjacency.iter_mut().chain(self.reachable.iter_mut()) {
+            set.grow(x + 1);
+        }
+        self.adjacency.push(BitSet::new(x + 1));
+        self.reachable.push(BitSet::new(x + 1));
+        x
+    }
+
+    /// Returns an iterator over the vertices adjacent to x, in ascending
+    /// order, or `None` if x does not exist.
+    pub fn connections(&self, x: usize) -> Option<impl Iterator<Item = usize> + '_> {
+        self.adjacency.get(x).map(BitSet::iter)
+    }
+
+    /// Returns true if there is a path from x to y of at least one edge.
+    pub fn reachable(&self, x: usize, y: usize) -> Result<bool, Error> {
+        if y >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let reachable_x = self.reachable.get(x).ok_or(Error::VertexDoesNotExist)?;
+        Ok(reachable_x.contains(y))
+    }
+
+    /// Recomputes the transitive closure from the adjacency lists, visiting
+    /// the vertices in reverse topological order.
+    fn rebuild_reachable(&mut self) {
+        let order = self.topological_order();
+        self.close_in_reverse(order);
+    }
+
+    /// Computes the closure of each vertex in reverse of the given topological
+    /// order, so the closures of its adjacent vertices are already complete.
+    fn close_in_reverse(&mut self, order: Vec<usize>) {
+        for x in order.into_iter().rev() {
+            let mut reachable_x = BitSet::new(self.len());
+            for y in self.adjacency[x].iter() {
+                reachable_x.insert(y);
+                reachable_x.union_with(&self.reachable[y]);
+            }
+            self.reachable[x] = reachable_x;
+        }
+    }
+
+    /// Returns the vertices in a topological order computed from the adjacency
+    /// lists alone, by repeatedly removing the vertices with no incoming edges
+    /// (Kahn's algorithm). The adjacency lists must be acyclic.
+    fn kahn_order(&self) -> Vec<usize> {
+        let mut in_degrees: Vec<usize> = vec![0; self.len()];
+        for y in self.adjacency.iter().flat_map(BitSet::iter) {
+            in_degrees[y] += 1;
+        }
+        let mut sources: Vec<usize> = (0..self.len()).filter(|x| in_degrees[*x] == 0).collect();
+        let mut order: Vec<usize> = Vec::with_capacity(self.len());
+        while let Some(x) = sources.pop() {
+            order.push(x);
+            for y in self.adjacency[x].iter() {
+                in_degrees[y] -= 1;
+                if in_degrees[y] == 0 {
+                    sources.push(y);
+                }
+            }
+        }
+        order
+    }
+
+    /// Returns the vertices in a topological order, by ordering them by the
+    /// number of vertices they can reach, in descending order.
+    fn topological_order(&self) -> Vec<usize> {
+        // Removing edges only shrinks the closure, so the existing closure
+        // still orders every vertex before the vertices it can reach.
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by_key(|x| core::cmp::Reverse(self.reachable[*x].len()));
+        order
+    }
+}
+
+impl Default for DenseDAG {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl AddEdge<usize> for DenseDAG {
+    type Error = Error;
+    fn add_edge(&mut self, x: usize, y: usize) -> Result<BTreeSet<usize>, Self::Error> {
+        if x >= self.len() || y >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        if x == y || self.reachable[y].contains(x) {
+            return Err(Error::EdgeExists);
+        }
+        let previous_adj_x: BTreeSet<usize> = self.adjacency[x].iter().collect();
+        if self.adjacency[x].insert(y) {
+            // Every vertex which reaches x, and x itself, now reaches y, and
+            // every vertex y reaches.
+            let mut reachable_y = self.reachable[y].clone();
+            reachable_y.insert(y);
+            for v in 0..self.len() {
+                if v == x || self.reachable[v].contains(x) {
+                    self.reachable[v].union_with(&reachable_y);
+                }
+            }
+        }
+        Ok(previous_adj_x)
+    }
+}
+
+impl RemoveEdge<usize> for DenseDAG {
+    type Error = Error;
+    fn remove_edge(&mut self, x: usize, y: usize) -> Result<BTreeSet<usize>, Self::Error> {
+        if x >= self.len() || y >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let previous_adj_x: BTreeSet<usize> = self.adjacency[x].iter().collect();
+        if self.adjacency[x].remove(y) {
+            self.rebuild_reachable();
+        }
+        Ok(previous_adj_x)
+    }
+}
+
+impl Adjacent<usize> for DenseDAG {
+    type Error = Error;
+    fn adjacent(&self, x: usize, y: usize) -> Result<bool, Self::Error> {
+        if x >= self.len() || y >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        Ok(self.adjacency[x].contains(y))
+    }
+}
+
+impl OutDegree<usize> for DenseDAG {
+    fn out_degree(&self, x: &usize) -> Option<usize> {
+        self.adjacency.get(*x).map(BitSet::len)
+    }
+}
+
+/// The vertices of the `BTreeDAG` must be exactly `0..len`, otherwise
+/// `Error::ImproperDimension` is returned.
+impl TryFrom<&BTreeDAG<usize>> for DenseDAG {
+    type Error = Error;
+    fn try_from(dag: &BTreeDAG<usize>) -> Result<Self, Self::Error> {
+        let len = dag.vertices.len();
+        if dag.vertices.keys().enumerate().any(|(i, x)| i != *x) {
+            return Err(Error::ImproperDimension);
+        }
+        let mut dense = DenseDAG::new(len);
+        for (x, adj_x) in dag.vertices.iter() {
+            for y in adj_x.iter() {
+                dense.adjacency[*x].insert(*y);
+            }
+        }
+        // The closure is still empty, so it can not order the vertices, but
+        // the BTreeDAG is acyclic, so the adjacency lists can.
+        let order = dense.kahn_order();
+        dense.close_in_reverse(order);
+        Ok(dense)
+    }
+}
+
+impl From<&DenseDAG> for BTreeDAG<usize> {
+    fn from(dag: &DenseDAG) -> Self {
+        let vertices: BTreeMap<usize, Arc<BTreeSet<usize>>> = dag
+            .adjacency
+            .iter()
+            .enumerate()
+            .map(|(x, adj_x)| (x, Arc::new(adj_x.iter().collect())))
+            .collect();
+        BTreeDAG::from_vertices(vertices)
+    }
+}
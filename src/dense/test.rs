@@ -0,0 +1,96 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::dense::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+
+    #[test]
+    fn reachable() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: DenseDAG = DenseDAG::new(2);
+        assert_eq!(dag.add_vertex(), 2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        assert!(dag.reachable(0, 2)?);
+        assert!(!dag.reachable(2, 0)?);
+        assert!(!dag.reachable(0, 0)?);
+        assert_eq!(dag.reachable(0, 3), Err(Error::VertexDoesNotExist));
+
+        // Edges which introduce a cycle are rejected.
+        assert_eq!(dag.add_edge(2, 0), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(1, 1), Err(Error::EdgeExists));
+
+        // Removing an edge shrinks the closure.
+        dag.add_edge(0, 2)?;
+        assert_eq!(dag.remove_edge(1, 2)?, BTreeSet::from([2]));
+        assert!(dag.reachable(0, 2)?);
+        assert!(!dag.reachable(1, 2)?);
+        dag.remove_edge(0, 2)?;
+        assert!(!dag.reachable(0, 2)?);
+        assert!(dag.reachable(0, 1)?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn many_vertices() -> Result<(), Error> {
+        // A chain longer than a single word of bits.
+        let mut dag: DenseDAG = DenseDAG::new(130);
+        for x in 0..129 {
+            dag.add_edge(x, x + 1)?;
+        }
+        assert!(dag.reachable(0, 129)?);
+        assert_eq!(dag.add_edge(129, 0), Err(Error::EdgeExists));
+        assert_eq!(dag.out_degree(&64), Some(1));
+        assert_eq!(dag.connections(64).unwrap().collect::<Vec<usize>>(), [65]);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn conversion() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        let dense: DenseDAG = DenseDAG::try_from(&dag)?;
+        assert!(dense.reachable(0, 2)?);
+        assert_eq!(BTreeDAG::from(&dense), dag);
+
+        // The vertices must be exactly 0..len.
+        dag.remove_vertex(1)?;
+        assert_eq!(DenseDAG::try_from(&dag), Err(Error::ImproperDimension));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_deep_chain() {
+        // Build a chain of 10000 nodes, with an edge (i, i + 1), whose closure
+        // is as deep as the chain is long, and convert it on a thread with a
+        // small stack, which a recursive conversion would overflow.
+        let len: usize = 10_000;
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges((0..len - 1).map(|i| (i, i + 1))).unwrap();
+        let dense: DenseDAG = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || DenseDAG::try_from(&dag).unwrap())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(dense.reachable(0, len - 1), Ok(true));
+        assert_eq!(dense.reachable(len - 1, 0), Ok(false));
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,284 @@
+mod test;
+
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Display, Write};
+use core::iter::Peekable;
+use core::str::{CharIndices, FromStr};
+
+use crate::dag::{AddEdge, BTreeDAG};
+use crate::Error;
+
+/// `ToDot` renders the dag in the Graphviz DOT language, with one statement per
+/// vertex followed by one statement per edge, in the ordering of the vertices.
+/// Vertices are labelled by their `Display` representation.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, ToDot};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("destination"));
+///
+/// assert_eq!(
+///     dag.to_dot(),
+///     "digraph {\n    \"destination\";\n    \"origin\";\n    \"origin\" -> \"destination\";\n}\n"
+/// );
+/// ```
+pub trait ToDot {
+    fn to_dot(&self) -> String;
+}
+
+impl<T> ToDot for BTreeDAG<T>
+where
+    T: Ord + Display,
+{
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for v in self.vertices.keys() {
+            // Writing to a String cannot fail, so we can safely unwrap.
+            writeln!(dot, "    {};", quote(v)).unwrap();
+        }
+        for (x, adj_x) in self.vertices.iter() {
+            for y in adj_x.iter() {
+                writeln!(dot, "    {} -> {};", quote(x), quote(y)).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Renders the vertex as a DOT quoted string, escaping quotes and backslashes.
+fn quote<T>(v: &T) -> String
+where
+    T: Display,
+{
+    let mut quoted = String::from("\"");
+    for c in v.to_string().chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `FromDot` parses a dag from a restricted subset of the Graphviz DOT language:
+/// a single `digraph` of node statements and edge statements, where an edge
+/// statement may chain several edges, as in `a -> b -> c`. Attribute lists,
+/// attribute statements and comments are accepted and ignored; subgraphs and
+/// undirected edges are not supported. Vertices are parsed from their
+/// identifiers with `FromStr`, so the output of `ToDot` is parsed back into an
+/// equal dag. An error is returned if the input is malformed, or if its edges
+/// contain a cycle.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, Adjacent, FromDot};
+/// let dag: BTreeDAG<String> = BTreeDAG::from_dot(
+///     "digraph { origin -> waypoint -> \"destination\" [weight=2]; }",
+/// )
+/// .unwrap();
+///
+/// assert!(dag.adjacent(String::from("waypoint"), String::from("destination")).unwrap());
+/// ```
+pub trait FromDot: Sized {
+    fn from_dot(dot: &str) -> Result<Self, Error>;
+}
+
+impl<T> FromDot for BTreeDAG<T>
+where
+    T: Ord + Clone + FromStr,
+{
+    fn from_dot(dot: &str) -> Result<Self, Error> {
+        let mut tokens = Tokens::new(dot).peekable();
+        let mut token = next_token(&mut tokens)?;
+        if is_keyword(&token, "strict") {
+            token = next_token(&mut tokens)?;
+        }
+        if !is_keyword(&token, "digraph") {
+            return Err(Error::ParseError);
+        }
+        token = next_token(&mut tokens)?;
+        if let Token::Id(_) = token {
+            token = next_token(&mut tokens)?;
+        }
+        if token != Token::Symbol("{") {
+            return Err(Error::ParseError);
+        }
+        let mut dag: BTreeDAG<T> = BTreeDAG::new();
+        loop {
+            let id = match next_token(&mut tokens)? {
+                Token::Symbol("}") => break,
+                Token::Symbol(";") => continue,
+                Token::Id(id) => id,
+                _ => return Err(Error::ParseError),
+            };
+            if ["graph", "node", "edge"]
+                .iter()
+                .any(|keyword| id.eq_ignore_ascii_case(keyword))
+            {
+                skip_attributes(&mut tokens)?;
+                continue;
+            }
+            if tokens.peek() == Some(&Ok(Token::Symbol("="))) {
+                tokens.next();
+                match next_token(&mut tokens)? {
+                    Token::Id(_) => continue,
+                    _ => return Err(Error::ParseError),
+                }
+            }
+            let mut chain: Vec<T> = Vec::from([parse_vertex(&id)?]);
+            while tokens.peek() == Some(&Ok(Token::Symbol("->"))) {
+                tokens.next();
+                match next_token(&mut tokens)? {
+                    Token::Id(id) => chain.push(parse_vertex(&id)?),
+                    _ => return Err(Error::ParseError),
+                }
+            }
+            skip_attributes(&mut tokens)?;
+            for v in chain.iter() {
+                dag.vertices
+                    .entry(v.clone())
+                    .or_insert_with(|| Arc::new(Default::default()));
+            }
+            for edge in chain.windows(2) {
+                dag.add_edge(edge[0].clone(), edge[1].clone())?;
+            }
+        }
+        if tokens.next().is_some() {
+            return Err(Error::ParseError);
+        }
+        Ok(dag)
+    }
+}
+
+/// A token of the DOT language: an identifier, with any quotes and escapes
+/// removed, or a symbol.
+#[derive(PartialEq, Debug)]
+enum Token<'a> {
+    Id(String),
+    Symbol(&'a str),
+}
+
+fn is_keyword(token: &Token<'_>, keyword: &str) -> bool {
+    matches!(token, Token::Id(id) if id.eq_ignore_ascii_case(keyword))
+}
+
+fn next_token<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<Token<'a>, Error> {
+    tokens.next().unwrap_or(Err(Error::ParseError))
+}
+
+fn parse_vertex<T>(id: &str) -> Result<T, Error>
+where
+    T: FromStr,
+{
+    id.parse().map_err(|_| Error::ParseError)
+}
+
+/// Skips any attribute lists following a statement, such as `[shape=box]`.
+fn skip_attributes<'a>(
+    tokens: &mut Peekable<impl Iterator<Item = Result<Token<'a>, Error>>>,
+) -> Result<(), Error> {
+    while tokens.peek() == Some(&Ok(Token::Symbol("["))) {
+        tokens.next();
+        loop {
+            match next_token(tokens)? {
+                Token::Symbol("]") => break,
+                Token::Symbol("[") | Token::Symbol("{") | Token::Symbol("}") => {
+                    return Err(Error::ParseError)
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits DOT source into tokens, skipping whitespace and comments.
+struct Tokens<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(source: &'a str) -> Self {
+        Tokens {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    /// Skips whitespace and comments, returning an error if a block comment
+    /// is not closed.
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        while let Some(&(i, c)) = self.chars.peek() {
+            let rest = &self.source[i..];
+            if c.is_whitespace() {
+                self.chars.next();
+            } else if c == '#' || rest.starts_with("//") {
+                while self.chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            } else if rest.starts_with("/*") {
+                let end = rest.find("*/").ok_or(Error::ParseError)? + i + 2;
+                while self.chars.next_if(|&(j, _)| j < end).is_some() {}
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn quoted(&mut self) -> Result<Token<'a>, Error> {
+        let mut id = String::new();
+        loop {
+            match self.chars.next().ok_or(Error::ParseError)?.1 {
+                '"' => return Ok(Token::Id(id)),
+                '\\' => match self.chars.next().ok_or(Error::ParseError)?.1 {
+                    '"' => id.push('"'),
+                    '\\' => id.push('\\'),
+                    // A backslash before a newline continues the line.
+                    '\n' => {}
+                    c => {
+                        id.push('\\');
+                        id.push(c);
+                    }
+                },
+                c => id.push(c),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Token<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.skip_trivia() {
+            return Some(Err(e));
+        }
+        let (i, c) = self.chars.next()?;
+        Some(match c {
+            '"' => self.quoted(),
+            '-' if self.chars.next_if(|&(_, c)| c == '>').is_some() => Ok(Token::Symbol("->")),
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' => Ok(Token::Symbol(&self.source[i..i + 1])),
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut end = i + c.len_utf8();
+                while let Some((j, c)) = self
+                    .chars
+                    .next_if(|&(_, c)| c.is_alphanumeric() || c == '_' || c == '.')
+                {
+                    end = j + c.len_utf8();
+                }
+                Ok(Token::Id(String::from(&self.source[i..end])))
+            }
+            _ => Err(Error::ParseError),
+        })
+    }
+}
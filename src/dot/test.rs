@@ -0,0 +1,90 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::dot::*;
+    use crate::Error;
+
+    #[test]
+    fn to_dot() -> Result<(), Error> {
+        // An empty dag renders an empty digraph.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        assert_eq!(dag.to_dot(), "digraph {\n}\n");
+
+        // Add three nodes, and an edge (0, 1) and (0, 2).
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        assert_eq!(
+            dag.to_dot(),
+            "digraph {\n    \"0\";\n    \"1\";\n    \"2\";\n    \"0\" -> \"1\";\n    \"0\" -> \"2\";\n}\n"
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn to_dot_escapes_labels() {
+        let mut dag: BTreeDAG<String> = BTreeDAG::new();
+        dag.add_vertex(String::from("say \"hi\" \\o/"));
+        assert_eq!(
+            dag.to_dot(),
+            "digraph {\n    \"say \\\"hi\\\" \\\\o/\";\n}\n"
+        );
+
+        // Test passed.
+    }
+
+    #[test]
+    fn from_dot() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (0, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+
+        // The rendered dag parses back into an equal dag.
+        assert_eq!(BTreeDAG::from_dot(&dag.to_dot())?, dag);
+
+        // Attributes and comments are ignored, and chains add every edge.
+        let parsed: BTreeDAG<usize> = BTreeDAG::from_dot(
+            "strict digraph deps {\n  // build order\n  rankdir = LR;\n  node [shape=box];\n  \
+             3;\n  0 -> 1 -> 2 [color=red] /* chained */\n  0 -> 2\n}",
+        )?;
+        assert_eq!(parsed.vertices().len(), 4);
+        assert!(parsed.adjacent(0, 1)?);
+        assert!(parsed.adjacent(1, 2)?);
+        assert!(parsed.adjacent(0, 2)?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn from_dot_rejects() {
+        let parse = |dot: &str| BTreeDAG::<usize>::from_dot(dot);
+        assert_eq!(parse("digraph { 0 -> 1; 1 -> 0; }"), Err(Error::EdgeExists));
+        assert_eq!(parse("graph { 0 -- 1 }"), Err(Error::ParseError));
+        assert_eq!(parse("digraph { 0 -> }"), Err(Error::ParseError));
+        assert_eq!(parse("digraph { a }"), Err(Error::ParseError));
+        assert_eq!(parse("digraph { 0 }}"), Err(Error::ParseError));
+        assert_eq!(parse("digraph { /* 0 }"), Err(Error::ParseError));
+
+        // Test passed.
+    }
+
+    #[test]
+    fn from_dot_escapes_labels() -> Result<(), Error> {
+        let mut dag: BTreeDAG<String> = BTreeDAG::new();
+        dag.add_vertex(String::from("say \"hi\" \\o/"));
+        assert_eq!(BTreeDAG::from_dot(&dag.to_dot())?, dag);
+
+        // Test passed.
+        Ok(())
+    }
+}
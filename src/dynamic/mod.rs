@@ -0,0 +1,106 @@
+//! An object-safe subset of the API, for accepting any dag as a trait object.
+//!
+//! `DynDag` is not re-exported at the crate root, since its methods share
+//! their names with the traits it is implemented in terms of, and would be
+//! ambiguous wherever both are in scope.
+mod test;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use core::fmt::Debug;
+
+use crate::dag::{
+    AddEdge, AddVertex, Adjacent, Predecessors, RemoveEdge, RemoveVertex, Successors, Vertices,
+};
+
+/// `DynError` is the boxed error returned by the fallible methods of `DynDag`.
+pub type DynError = Box<dyn Debug + Send + Sync>;
+
+/// `DynDag` is an object-safe combination of the query and basic mutation
+/// traits, so plugins can accept a `&dyn DynDag<T>`. It is implemented for
+/// every type implementing those traits, with the errors boxed into a
+/// `DynError`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::dynamic::DynDag;
+/// use btree_dag::BTreeDAG;
+///
+/// fn link(dag: &mut dyn DynDag<String>) {
+///     dag.add_vertex(String::from("origin"));
+///     dag.add_vertex(String::from("destination"));
+///     dag.add_edge(String::from("origin"), String::from("destination")).unwrap();
+/// }
+///
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// link(&mut dag);
+/// assert!(DynDag::adjacent(&dag, String::from("origin"), String::from("destination")).unwrap());
+/// ```
+pub trait DynDag<T> {
+    fn vertices(&self) -> BTreeSet<&T>;
+    fn successors(&self, x: &T) -> Option<&BTreeSet<T>>;
+    fn predecessors(&self, x: &T) -> Option<BTreeSet<&T>>;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, DynError>;
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>>;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, DynError>;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, DynError>;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, DynError>;
+}
+
+/// Boxes an error into a `DynError`.
+fn boxed<E>(err: E) -> DynError
+where
+    E: Debug + Send + Sync + 'static,
+{
+    Box::new(err)
+}
+
+impl<T, D> DynDag<T> for D
+where
+    T: Ord,
+    D: Vertices<T>
+        + Successors<T>
+        + Predecessors<T>
+        + Adjacent<T>
+        + AddVertex<T>
+        + AddEdge<T>
+        + RemoveEdge<T>
+        + RemoveVertex<T>,
+    <D as Adjacent<T>>::Error: Debug + Send + Sync + 'static,
+    <D as AddEdge<T>>::Error: Debug + Send + Sync + 'static,
+    <D as RemoveEdge<T>>::Error: Debug + Send + Sync + 'static,
+    <D as RemoveVertex<T>>::Error: Debug + Send + Sync + 'static,
+{
+    fn vertices(&self) -> BTreeSet<&T> {
+        Vertices::vertices(self)
+    }
+
+    fn successors(&self, x: &T) -> Option<&BTreeSet<T>> {
+        Successors::successors(self, x)
+    }
+
+    fn predecessors(&self, x: &T) -> Option<BTreeSet<&T>> {
+        Predecessors::predecessors(self, x)
+    }
+
+    fn adjacent(&self, x: T, y: T) -> Result<bool, DynError> {
+        Adjacent::adjacent(self, x, y).map_err(boxed)
+    }
+
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        AddVertex::add_vertex(self, x)
+    }
+
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, DynError> {
+        AddEdge::add_edge(self, x, y).map_err(boxed)
+    }
+
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, DynError> {
+        RemoveEdge::remove_edge(self, x, y).map_err(boxed)
+    }
+
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, DynError> {
+        RemoveVertex::remove_vertex(self, x).map_err(boxed)
+    }
+}
@@ -0,0 +1,35 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::BTreeDAG;
+    use crate::dynamic::*;
+    use alloc::collections::BTreeSet;
+    use alloc::format;
+
+    #[test]
+    fn dyn_dag() {
+        // Add three nodes, and an edge (0, 1) and (1, 2) through a trait
+        // object.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        let dyn_dag: &mut dyn DynDag<usize> = &mut dag;
+        dyn_dag.add_vertex(0);
+        dyn_dag.add_vertex(1);
+        dyn_dag.add_vertex(2);
+        dyn_dag.add_edge(0, 1).unwrap();
+        dyn_dag.add_edge(1, 2).unwrap();
+
+        assert_eq!(dyn_dag.vertices(), BTreeSet::from([&0, &1, &2]));
+        assert_eq!(dyn_dag.successors(&0), Some(&BTreeSet::from([1])));
+        assert_eq!(dyn_dag.predecessors(&2), Some(BTreeSet::from([&1])));
+        assert!(dyn_dag.adjacent(1, 2).unwrap());
+
+        // Errors are boxed.
+        let err: DynError = dyn_dag.add_edge(2, 0).unwrap_err();
+        assert_eq!(format!("{:?}", err), "EdgeExists");
+        assert!(dyn_dag.remove_vertex(3).is_err());
+        assert_eq!(dyn_dag.remove_edge(1, 2).unwrap(), BTreeSet::from([2]));
+        assert_eq!(dyn_dag.remove_vertex(2).unwrap(), BTreeSet::new());
+
+        // Test passed.
+    }
+}
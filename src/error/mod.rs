@@ -0,0 +1,212 @@
+#[cfg(feature = "fmt")]
+use core::fmt::{Debug, Display, Formatter, Result};
+
+mod test;
+
+#[cfg(feature = "fmt")]
+static VERTEX_DOES_NOT_EXIST_ERROR: &str = "BTreeDAG Error: Vertex does not exist";
+#[cfg(feature = "fmt")]
+static EDGE_DOES_NOT_EXIST_ERROR: &str = "BTreeDAG Error: Edge does not exist";
+#[cfg(feature = "fmt")]
+static EDGE_EXISTS_ERROR: &str = "BTreeDAG Error: Edge exists";
+#[cfg(feature = "fmt")]
+static VERTEX_EXISTS_ERROR: &str = "BTreeDAG Error: Vertex exists";
+#[cfg(feature = "fmt")]
+static IMPROPER_DIMENSION_ERROR: &str = "BTreeDAG Error: Improper dimension";
+#[cfg(feature = "fmt")]
+static CANCELLED_ERROR: &str = "BTreeDAG Error: Cancelled";
+#[cfg(feature = "fmt")]
+static CONSTRAINT_VIOLATED_ERROR: &str = "BTreeDAG Error: Constraint violated";
+#[cfg(feature = "fmt")]
+static LIMIT_EXCEEDED_ERROR: &str = "BTreeDAG Error: Limit exceeded";
+#[cfg(feature = "fmt")]
+static PARSE_ERROR: &str = "BTreeDAG Error: Parse error";
+#[cfg(feature = "fmt")]
+static DEPTH_LIMIT_EXCEEDED_ERROR: &str = "BTreeDAG Error: Depth limit exceeded";
+#[cfg(feature = "fmt")]
+static EMPTY_GRAPH_ERROR: &str = "BTreeDAG Error: Empty graph";
+#[cfg(feature = "fmt")]
+static VERSION_OUT_OF_SEQUENCE_ERROR: &str = "BTreeDAG Error: Version out of sequence";
+
+/// Errors which may occur during normal usage of the library. The variants
+/// shared with the btree_error crate convert from its `Error`, which this
+/// type replaces; see the changelog.
+#[derive(PartialEq, Debug)]
+pub enum Error {
+    VertexDoesNotExist,
+    EdgeDoesNotExist,
+    VertexExists,
+    EdgeExists,
+    ImproperDimension,
+    /// A budgeted operation ran out of budget, or was cancelled.
+    Cancelled,
+    /// An edge is not allowed by the kinds of its vertices.
+    ConstraintViolated,
+    /// A mutation would exceed a configured limit, or a computed value would
+    /// not fit in its type.
+    LimitExceeded,
+    /// A textual representation of a dag is malformed.
+    ParseError,
+    /// A traversal would exceed the maximum traversal depth of the dag.
+    DepthLimitExceeded,
+    /// A query with no meaningful result on an empty dag was made of one.
+    EmptyGraph,
+    /// A record of a versioned log does not have the next version.
+    VersionOutOfSequence {
+        expected: u64,
+        found: u64,
+    },
+    #[cfg(any(feature = "serde_cbor", feature = "serde_json", feature = "serde_yaml"))]
+    EncodingError(try_encoding_from::Error),
+}
+
+#[cfg(feature = "fmt")]
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Error::VertexDoesNotExist => write!(f, "{}", VERTEX_DOES_NOT_EXIST_ERROR),
+            Error::EdgeDoesNotExist => write!(f, "{}", EDGE_DOES_NOT_EXIST_ERROR),
+            Error::VertexExists => write!(f, "{}", VERTEX_EXISTS_ERROR),
+            Error::EdgeExists => write!(f, "{}", EDGE_EXISTS_ERROR),
+            Error::ImproperDimension => write!(f, "{}", IMPROPER_DIMENSION_ERROR),
+            Error::Cancelled => write!(f, "{}", CANCELLED_ERROR),
+            Error::ConstraintViolated => write!(f, "{}", CONSTRAINT_VIOLATED_ERROR),
+            Error::LimitExceeded => write!(f, "{}", LIMIT_EXCEEDED_ERROR),
+            Error::ParseError => write!(f, "{}", PARSE_ERROR),
+            Error::DepthLimitExceeded => write!(f, "{}", DEPTH_LIMIT_EXCEEDED_ERROR),
+            Error::EmptyGraph => write!(f, "{}", EMPTY_GRAPH_ERROR),
+            Error::VersionOutOfSequence { expected, found } => write!(
+                f,
+                "{}: expected {}, found {}",
+                VERSION_OUT_OF_SEQUENCE_ERROR, expected, found
+            ),
+            #[cfg(any(feature = "serde_cbor", feature = "serde_json", feature = "serde_yaml"))]
+            Error::EncodingError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+// `try_encoding_from::Error` is neither `Clone` nor `Eq`, so neither can be
+// derived. Its variants are unit variants, so equality is reflexive.
+impl Eq for Error {}
+
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        match self {
+            Error::VertexDoesNotExist => Error::VertexDoesNotExist,
+            Error::EdgeDoesNotExist => Error::EdgeDoesNotExist,
+            Error::VertexExists => Error::VertexExists,
+            Error::EdgeExists => Error::EdgeExists,
+            Error::ImproperDimension => Error::ImproperDimension,
+            Error::Cancelled => Error::Cancelled,
+            Error::ConstraintViolated => Error::ConstraintViolated,
+            Error::LimitExceeded => Error::LimitExceeded,
+            Error::ParseError => Error::ParseError,
+            Error::DepthLimitExceeded => Error::DepthLimitExceeded,
+            Error::EmptyGraph => Error::EmptyGraph,
+            Error::VersionOutOfSequence { expected, found } => Error::VersionOutOfSequence {
+                expected: *expected,
+                found: *found,
+            },
+            #[cfg(any(feature = "serde_cbor", feature = "serde_json", feature = "serde_yaml"))]
+            Error::EncodingError(err) => Error::EncodingError(match err {
+                #[cfg(feature = "serde_cbor")]
+                try_encoding_from::Error::CborError => try_encoding_from::Error::CborError,
+                #[cfg(feature = "serde_json")]
+                try_encoding_from::Error::JsonError => try_encoding_from::Error::JsonError,
+                #[cfg(feature = "serde_yaml")]
+                try_encoding_from::Error::YamlError => try_encoding_from::Error::YamlError,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<btree_error::Error> for Error {
+    fn from(e: btree_error::Error) -> Error {
+        match e {
+            btree_error::Error::VertexDoesNotExist => Error::VertexDoesNotExist,
+            btree_error::Error::EdgeDoesNotExist => Error::EdgeDoesNotExist,
+            btree_error::Error::VertexExists => Error::VertexExists,
+            btree_error::Error::EdgeExists => Error::EdgeExists,
+            btree_error::Error::ImproperDimension => Error::ImproperDimension,
+            #[cfg(any(feature = "serde_cbor", feature = "serde_json", feature = "serde_yaml"))]
+            btree_error::Error::EncodingError(err) => Error::EncodingError(err),
+        }
+    }
+}
+
+#[cfg(any(feature = "serde_cbor", feature = "serde_json", feature = "serde_yaml"))]
+impl From<try_encoding_from::Error> for Error {
+    fn from(e: try_encoding_from::Error) -> Error {
+        Error::EncodingError(e)
+    }
+}
+
+#[cfg(feature = "serde_cbor")]
+impl From<try_encoding_from::serde_cbor::Error> for Error {
+    fn from(e: try_encoding_from::serde_cbor::Error) -> Error {
+        Error::EncodingError(try_encoding_from::Error::from(e))
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<try_encoding_from::serde_json::Error> for Error {
+    fn from(e: try_encoding_from::serde_json::Error) -> Error {
+        Error::EncodingError(try_encoding_from::Error::from(e))
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl From<try_encoding_from::serde_yaml::Error> for Error {
+    fn from(e: try_encoding_from::serde_yaml::Error) -> Error {
+        Error::EncodingError(try_encoding_from::Error::from(e))
+    }
+}
+
+/// The vertex or edge an `Error` occurred on.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ErrorContext<T> {
+    Vertex(T),
+    Edge(T, T),
+}
+
+/// An `Error` along with the vertex or edge it occurred on. A `ContextError`
+/// is constructed through the `Explain` trait, which identifies the vertex
+/// responsible for an error where it can.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ContextError<T> {
+    pub error: Error,
+    pub context: ErrorContext<T>,
+}
+
+#[cfg(feature = "fmt")]
+impl<T> Display for ContextError<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match &self.context {
+            ErrorContext::Vertex(x) => write!(f, "{}: vertex {:?}", self.error, x),
+            ErrorContext::Edge(x, y) => write!(f, "{}: edge ({:?}, {:?})", self.error, x, y),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for ContextError<T>
+where
+    T: Debug,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T> From<ContextError<T>> for Error {
+    fn from(e: ContextError<T>) -> Error {
+        e.error
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::Error;
+
+    #[test]
+    fn from_btree_error() {
+        assert_eq!(
+            Error::from(btree_error::Error::VertexDoesNotExist),
+            Error::VertexDoesNotExist
+        );
+        assert_eq!(
+            Error::from(btree_error::Error::EdgeDoesNotExist),
+            Error::EdgeDoesNotExist
+        );
+        assert_eq!(
+            Error::from(btree_error::Error::VertexExists),
+            Error::VertexExists
+        );
+        assert_eq!(
+            Error::from(btree_error::Error::EdgeExists),
+            Error::EdgeExists
+        );
+        assert_eq!(
+            Error::from(btree_error::Error::ImproperDimension),
+            Error::ImproperDimension
+        );
+
+        // Test passed.
+    }
+
+    #[test]
+    #[cfg(feature = "fmt")]
+    fn display() {
+        use alloc::string::ToString;
+        assert_eq!(Error::Cancelled.to_string(), "BTreeDAG Error: Cancelled");
+        assert_eq!(
+            Error::ConstraintViolated.to_string(),
+            "BTreeDAG Error: Constraint violated"
+        );
+        assert_eq!(
+            Error::LimitExceeded.to_string(),
+            "BTreeDAG Error: Limit exceeded"
+        );
+        assert_eq!(Error::ParseError.to_string(), "BTreeDAG Error: Parse error");
+        assert_eq!(
+            Error::DepthLimitExceeded.to_string(),
+            "BTreeDAG Error: Depth limit exceeded"
+        );
+        assert_eq!(Error::EmptyGraph.to_string(), "BTreeDAG Error: Empty graph");
+        assert_eq!(
+            Error::VersionOutOfSequence {
+                expected: 3,
+                found: 4
+            }
+            .to_string(),
+            "BTreeDAG Error: Version out of sequence: expected 3, found 4"
+        );
+
+        // Test passed.
+    }
+
+    #[cfg(feature = "fmt")]
+    #[test]
+    fn display_context() {
+        use crate::{ContextError, ErrorContext};
+        use alloc::string::ToString;
+
+        let vertex: ContextError<usize> = ContextError {
+            error: Error::VertexDoesNotExist,
+            context: ErrorContext::Vertex(3),
+        };
+        assert_eq!(
+            vertex.to_string(),
+            "BTreeDAG Error: Vertex does not exist: vertex 3"
+        );
+        let edge: ContextError<&str> = ContextError {
+            error: Error::EdgeExists,
+            context: ErrorContext::Edge("a", "b"),
+        };
+        assert_eq!(
+            edge.to_string(),
+            "BTreeDAG Error: Edge exists: edge (\"a\", \"b\")"
+        );
+
+        // Test passed.
+    }
+
+    #[test]
+    fn clone_and_eq() {
+        use crate::{ContextError, ErrorContext};
+
+        let err = Error::VersionOutOfSequence {
+            expected: 3,
+            found: 4,
+        };
+        assert_eq!(err.clone(), err);
+        let context: ContextError<usize> = ContextError {
+            error: err.clone(),
+            context: ErrorContext::Edge(0, 1),
+        };
+        assert_eq!(context.clone(), context);
+
+        fn eq_and_clone<E: Eq + Clone>() {}
+        eq_and_clone::<Error>();
+        eq_and_clone::<ContextError<usize>>();
+
+        // Test passed.
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn std_error() {
+        use crate::{ContextError, ErrorContext};
+        use alloc::string::ToString;
+
+        let err: &dyn std::error::Error = &Error::Cancelled;
+        assert_eq!(err.to_string(), "BTreeDAG Error: Cancelled");
+        let context: ContextError<usize> = ContextError {
+            error: Error::VertexDoesNotExist,
+            context: ErrorContext::Vertex(3),
+        };
+        let err: &dyn std::error::Error = &context;
+        assert_eq!(
+            err.source().map(|e| e.to_string()),
+            Some(Error::VertexDoesNotExist.to_string())
+        );
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,454 @@
+//! C bindings for `BTreeDAG`, operating on opaque handles. Two handle types
+//! are provided: `btree_dag_t`, keyed by unsigned 64 bit integers, and
+//! `btree_dag_str_t`, keyed by NUL terminated UTF-8 strings. Every handle
+//! returned by a `*_new` function must be released with the matching
+//! `*_free` function.
+//!
+//! The crate itself is `no_std`, so the shared and static libraries are built
+//! by the `capi` crate, which links them against std:
+//!
+//! ```text
+//! cargo build --release --manifest-path capi/Cargo.toml
+//! ```
+//!
+//! This produces `libbtree_dag.so` (or `.dylib`, or `btree_dag.dll`) and
+//! `libbtree_dag.a` in `capi/target/release`, to be used with
+//! `include/btree_dag.h`.
+mod test;
+
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::string::String;
+use core::ffi::{c_char, CStr};
+use core::ptr;
+
+use crate::dag::{
+    AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex, TopologicalSort,
+};
+use crate::Error;
+
+/// Opaque handle to a dag keyed by unsigned 64 bit integers.
+pub struct BTreeDagU64 {
+    dag: BTreeDAG<u64>,
+}
+
+/// Opaque handle to a dag keyed by strings.
+pub struct BTreeDagStr {
+    dag: BTreeDAG<String>,
+}
+
+/// Status codes returned by the fallible bindings.
+#[repr(C)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BTreeDagStatus {
+    Ok = 0,
+    VertexDoesNotExist = 1,
+    EdgeDoesNotExist = 2,
+    VertexExists = 3,
+    EdgeExists = 4,
+    ImproperDimension = 5,
+    NullPointer = 6,
+    InvalidUtf8 = 7,
+    Other = 8,
+}
+
+impl From<Error> for BTreeDagStatus {
+    #[allow(unreachable_patterns)]
+    fn from(err: Error) -> Self {
+        match err {
+            Error::VertexDoesNotExist => BTreeDagStatus::VertexDoesNotExist,
+            Error::EdgeDoesNotExist => BTreeDagStatus::EdgeDoesNotExist,
+            Error::VertexExists => BTreeDagStatus::VertexExists,
+            Error::EdgeExists => BTreeDagStatus::EdgeExists,
+            Error::ImproperDimension => BTreeDagStatus::ImproperDimension,
+            _ => BTreeDagStatus::Other,
+        }
+    }
+}
+
+fn status<R>(result: Result<R, Error>) -> BTreeDagStatus {
+    match result {
+        Ok(_) => BTreeDagStatus::Ok,
+        Err(err) => err.into(),
+    }
+}
+
+/// Returns a new, empty dag keyed by integers.
+#[no_mangle]
+pub extern "C" fn btree_dag_new() -> *mut BTreeDagU64 {
+    Box::into_raw(Box::new(BTreeDagU64 {
+        dag: BTreeDAG::new(),
+    }))
+}
+
+/// Releases the dag. Passing a null pointer is a no-op.
+///
+/// # Safety
+///
+/// The dag must have been returned by `btree_dag_new`, and must not be used
+/// after it is released.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_free(dag: *mut BTreeDagU64) {
+    if !dag.is_null() {
+        drop(Box::from_raw(dag));
+    }
+}
+
+/// Adds the vertex x, if it is not there. A vertex which is there, and its
+/// edges, are left unchanged.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_add_vertex(dag: *mut BTreeDagU64, x: u64) -> BTreeDagStatus {
+    match dag.as_mut() {
+        Some(handle) => {
+            if !handle.dag.vertices.contains_key(&x) {
+                handle.dag.add_vertex(x);
+            }
+            BTreeDagStatus::Ok
+        }
+        None => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Adds an edge from the vertex x to the vertex y, unless it would introduce
+/// a cycle.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_add_edge(
+    dag: *mut BTreeDagU64,
+    x: u64,
+    y: u64,
+) -> BTreeDagStatus {
+    match dag.as_mut() {
+        Some(handle) => status(handle.dag.add_edge(x, y)),
+        None => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Removes the edge from the vertex x to the vertex y, if it is there.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_remove_edge(
+    dag: *mut BTreeDagU64,
+    x: u64,
+    y: u64,
+) -> BTreeDagStatus {
+    match dag.as_mut() {
+        Some(handle) => status(handle.dag.remove_edge(x, y)),
+        None => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Removes the vertex x and its incident edges.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_remove_vertex(dag: *mut BTreeDagU64, x: u64) -> BTreeDagStatus {
+    match dag.as_mut() {
+        Some(handle) => status(handle.dag.remove_vertex(x)),
+        None => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Removes the vertex x and recursively all of its children.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_prune(dag: *mut BTreeDagU64, x: u64) -> BTreeDagStatus {
+    match dag.as_mut() {
+        Some(handle) => status(handle.dag.prune(x)),
+        None => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Tests whether there is an edge from the vertex x to the vertex y, writing
+/// the answer to out.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`, and out
+/// must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_adjacent(
+    dag: *const BTreeDagU64,
+    x: u64,
+    y: u64,
+    out: *mut bool,
+) -> BTreeDagStatus {
+    match (dag.as_ref(), out.as_mut()) {
+        (Some(handle), Some(out)) => match handle.dag.adjacent(x, y) {
+            Ok(adjacent) => {
+                *out = adjacent;
+                BTreeDagStatus::Ok
+            }
+            Err(err) => err.into(),
+        },
+        _ => BTreeDagStatus::NullPointer,
+    }
+}
+
+/// Returns the number of vertices in the dag, or 0 if the dag is null.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_vertex_count(dag: *const BTreeDagU64) -> usize {
+    dag.as_ref().map_or(0, |handle| handle.dag.vertices.len())
+}
+
+/// Writes up to len vertices of the dag, in topological order, to out, and
+/// returns the total number of vertices. Call with a null out to query the
+/// required length.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_new`, and out
+/// must be null or valid for writes of len integers.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_topological_sort(
+    dag: *const BTreeDagU64,
+    out: *mut u64,
+    len: usize,
+) -> usize {
+    match dag.as_ref() {
+        Some(handle) => {
+            let sorted = handle.dag.topological_sort();
+            if !out.is_null() {
+                for (i, v) in sorted.iter().take(len).enumerate() {
+                    ptr::write(out.add(i), **v);
+                }
+            }
+            sorted.len()
+        }
+        None => 0,
+    }
+}
+
+/// Reads a NUL terminated UTF-8 string.
+unsafe fn key(s: *const c_char) -> Result<String, BTreeDagStatus> {
+    if s.is_null() {
+        return Err(BTreeDagStatus::NullPointer);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map(String::from)
+        .map_err(|_| BTreeDagStatus::InvalidUtf8)
+}
+
+/// Returns a new, empty dag keyed by strings.
+#[no_mangle]
+pub extern "C" fn btree_dag_str_new() -> *mut BTreeDagStr {
+    Box::into_raw(Box::new(BTreeDagStr {
+        dag: BTreeDAG::new(),
+    }))
+}
+
+/// Releases the dag. Passing a null pointer is a no-op.
+///
+/// # Safety
+///
+/// The dag must have been returned by `btree_dag_str_new`, and must not be
+/// used after it is released.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_free(dag: *mut BTreeDagStr) {
+    if !dag.is_null() {
+        drop(Box::from_raw(dag));
+    }
+}
+
+/// Adds the vertex x, if it is not there. A vertex which is there, and its
+/// edges, are left unchanged. The key is copied.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// x must be null or a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_add_vertex(
+    dag: *mut BTreeDagStr,
+    x: *const c_char,
+) -> BTreeDagStatus {
+    match (dag.as_mut(), key(x)) {
+        (Some(handle), Ok(x)) => {
+            if !handle.dag.vertices.contains_key(&x) {
+                handle.dag.add_vertex(x);
+            }
+            BTreeDagStatus::Ok
+        }
+        (None, _) => BTreeDagStatus::NullPointer,
+        (_, Err(err)) => err,
+    }
+}
+
+/// Adds an edge from the vertex x to the vertex y, unless it would introduce
+/// a cycle.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// x and y must be null or NUL terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_add_edge(
+    dag: *mut BTreeDagStr,
+    x: *const c_char,
+    y: *const c_char,
+) -> BTreeDagStatus {
+    match (dag.as_mut(), key(x), key(y)) {
+        (Some(handle), Ok(x), Ok(y)) => status(handle.dag.add_edge(x, y)),
+        (None, _, _) => BTreeDagStatus::NullPointer,
+        (_, Err(err), _) | (_, _, Err(err)) => err,
+    }
+}
+
+/// Removes the edge from the vertex x to the vertex y, if it is there.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// x and y must be null or NUL terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_remove_edge(
+    dag: *mut BTreeDagStr,
+    x: *const c_char,
+    y: *const c_char,
+) -> BTreeDagStatus {
+    match (dag.as_mut(), key(x), key(y)) {
+        (Some(handle), Ok(x), Ok(y)) => status(handle.dag.remove_edge(x, y)),
+        (None, _, _) => BTreeDagStatus::NullPointer,
+        (_, Err(err), _) | (_, _, Err(err)) => err,
+    }
+}
+
+/// Removes the vertex x and its incident edges.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// x must be null or a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_remove_vertex(
+    dag: *mut BTreeDagStr,
+    x: *const c_char,
+) -> BTreeDagStatus {
+    match (dag.as_mut(), key(x)) {
+        (Some(handle), Ok(x)) => status(handle.dag.remove_vertex(x)),
+        (None, _) => BTreeDagStatus::NullPointer,
+        (_, Err(err)) => err,
+    }
+}
+
+/// Removes the vertex x and recursively all of its children.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// x must be null or a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_prune(
+    dag: *mut BTreeDagStr,
+    x: *const c_char,
+) -> BTreeDagStatus {
+    match (dag.as_mut(), key(x)) {
+        (Some(handle), Ok(x)) => status(handle.dag.prune(x)),
+        (None, _) => BTreeDagStatus::NullPointer,
+        (_, Err(err)) => err,
+    }
+}
+
+/// Tests whether there is an edge from the vertex x to the vertex y, writing
+/// the answer to out.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, x
+/// and y must be null or NUL terminated strings, and out must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_adjacent(
+    dag: *const BTreeDagStr,
+    x: *const c_char,
+    y: *const c_char,
+    out: *mut bool,
+) -> BTreeDagStatus {
+    match (dag.as_ref(), key(x), key(y), out.as_mut()) {
+        (Some(handle), Ok(x), Ok(y), Some(out)) => match handle.dag.adjacent(x, y) {
+            Ok(adjacent) => {
+                *out = adjacent;
+                BTreeDagStatus::Ok
+            }
+            Err(err) => err.into(),
+        },
+        (None, _, _, _) | (_, _, _, None) => BTreeDagStatus::NullPointer,
+        (_, Err(err), _, _) | (_, _, Err(err), _) => err,
+    }
+}
+
+/// Returns the number of vertices in the dag, or 0 if the dag is null.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_vertex_count(dag: *const BTreeDagStr) -> usize {
+    dag.as_ref().map_or(0, |handle| handle.dag.vertices.len())
+}
+
+/// Writes up to len vertices of the dag, in topological order, to out, and
+/// returns the total number of vertices. Call with a null out to query the
+/// required length. Each string written is a copy, which must be released
+/// with `btree_dag_string_free`.
+///
+/// # Safety
+///
+/// The dag must be null or a live handle returned by `btree_dag_str_new`, and
+/// out must be null or valid for writes of len pointers.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_str_topological_sort(
+    dag: *const BTreeDagStr,
+    out: *mut *mut c_char,
+    len: usize,
+) -> usize {
+    match dag.as_ref() {
+        Some(handle) => {
+            let sorted = handle.dag.topological_sort();
+            if !out.is_null() {
+                for (i, v) in sorted.iter().take(len).enumerate() {
+                    // Every key was read from a NUL terminated string, so it
+                    // contains no NUL, and we can safely unwrap.
+                    let v = CString::new(v.as_str()).unwrap();
+                    ptr::write(out.add(i), v.into_raw());
+                }
+            }
+            sorted.len()
+        }
+        None => 0,
+    }
+}
+
+/// Releases a string returned by the bindings. Passing a null pointer is a
+/// no-op.
+///
+/// # Safety
+///
+/// The string must have been returned by the bindings, and must not be used
+/// after it is released.
+#[no_mangle]
+pub unsafe extern "C" fn btree_dag_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -0,0 +1,145 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::ffi::*;
+    use core::ptr;
+
+    #[test]
+    fn integer_keys() {
+        unsafe {
+            let dag = btree_dag_new();
+            assert_eq!(btree_dag_add_vertex(dag, 0), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_add_vertex(dag, 1), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_add_vertex(dag, 2), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_vertex_count(dag), 3);
+
+            // Add an edge (1, 0) and (2, 1); the edge (0, 2) is a cycle.
+            assert_eq!(btree_dag_add_edge(dag, 1, 0), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_add_edge(dag, 2, 1), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_add_edge(dag, 0, 2), BTreeDagStatus::EdgeExists);
+            assert_eq!(
+                btree_dag_add_edge(dag, 0, 3),
+                BTreeDagStatus::VertexDoesNotExist
+            );
+
+            let mut adjacent = false;
+            assert_eq!(
+                btree_dag_adjacent(dag, 2, 1, &mut adjacent),
+                BTreeDagStatus::Ok
+            );
+            assert!(adjacent);
+
+            // Adding a vertex which is there keeps its edges.
+            assert_eq!(btree_dag_add_vertex(dag, 2), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_vertex_count(dag), 3);
+            assert_eq!(
+                btree_dag_adjacent(dag, 2, 1, &mut adjacent),
+                BTreeDagStatus::Ok
+            );
+            assert!(adjacent);
+
+            // Query the length, then fill the buffer.
+            assert_eq!(btree_dag_topological_sort(dag, ptr::null_mut(), 0), 3);
+            let mut sorted = [0u64; 3];
+            assert_eq!(btree_dag_topological_sort(dag, sorted.as_mut_ptr(), 3), 3);
+            assert_eq!(sorted, [2, 1, 0]);
+
+            assert_eq!(btree_dag_remove_edge(dag, 2, 1), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_remove_vertex(dag, 2), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_prune(dag, 1), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_vertex_count(dag), 0);
+            assert_eq!(btree_dag_prune(dag, 1), BTreeDagStatus::VertexDoesNotExist);
+            btree_dag_free(dag);
+
+            // Null handles are rejected rather than dereferenced.
+            assert_eq!(
+                btree_dag_add_vertex(ptr::null_mut(), 0),
+                BTreeDagStatus::NullPointer
+            );
+            btree_dag_free(ptr::null_mut());
+        }
+
+        // Test passed.
+    }
+
+    #[test]
+    fn string_keys() {
+        unsafe {
+            let origin = b"origin\0".as_ptr() as *const c_char;
+            let destination = b"destination\0".as_ptr() as *const c_char;
+            let invalid = b"\xff\0".as_ptr() as *const c_char;
+
+            let dag = btree_dag_str_new();
+            assert_eq!(btree_dag_str_add_vertex(dag, origin), BTreeDagStatus::Ok);
+            assert_eq!(
+                btree_dag_str_add_vertex(dag, destination),
+                BTreeDagStatus::Ok
+            );
+            assert_eq!(
+                btree_dag_str_add_vertex(dag, invalid),
+                BTreeDagStatus::InvalidUtf8
+            );
+            assert_eq!(
+                btree_dag_str_add_vertex(dag, ptr::null()),
+                BTreeDagStatus::NullPointer
+            );
+            assert_eq!(btree_dag_str_vertex_count(dag), 2);
+
+            assert_eq!(
+                btree_dag_str_add_edge(dag, origin, destination),
+                BTreeDagStatus::Ok
+            );
+            assert_eq!(
+                btree_dag_str_add_edge(dag, destination, origin),
+                BTreeDagStatus::EdgeExists
+            );
+
+            let mut adjacent = false;
+            assert_eq!(
+                btree_dag_str_adjacent(dag, origin, destination, &mut adjacent),
+                BTreeDagStatus::Ok
+            );
+            assert!(adjacent);
+            assert_eq!(
+                btree_dag_str_adjacent(dag, origin, destination, ptr::null_mut()),
+                BTreeDagStatus::NullPointer
+            );
+
+            // Adding a vertex which is there keeps its edges.
+            assert_eq!(btree_dag_str_add_vertex(dag, origin), BTreeDagStatus::Ok);
+            assert_eq!(
+                btree_dag_str_adjacent(dag, origin, destination, &mut adjacent),
+                BTreeDagStatus::Ok
+            );
+            assert!(adjacent);
+
+            // Query the length, then fill the buffer, and release the copies.
+            assert_eq!(btree_dag_str_topological_sort(dag, ptr::null_mut(), 0), 2);
+            let mut sorted = [ptr::null_mut(); 2];
+            assert_eq!(
+                btree_dag_str_topological_sort(dag, sorted.as_mut_ptr(), 2),
+                2
+            );
+            assert_eq!(CStr::from_ptr(sorted[0]).to_str(), Ok("origin"));
+            assert_eq!(CStr::from_ptr(sorted[1]).to_str(), Ok("destination"));
+            for v in sorted.iter() {
+                btree_dag_string_free(*v);
+            }
+            btree_dag_string_free(ptr::null_mut());
+
+            assert_eq!(
+                btree_dag_str_remove_edge(dag, origin, destination),
+                BTreeDagStatus::Ok
+            );
+            assert_eq!(
+                btree_dag_str_remove_vertex(dag, destination),
+                BTreeDagStatus::Ok
+            );
+            assert_eq!(btree_dag_str_prune(dag, origin), BTreeDagStatus::Ok);
+            assert_eq!(btree_dag_str_vertex_count(dag), 0);
+            btree_dag_str_free(dag);
+        }
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,320 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// `Flow` is a maximum flow from a source to a sink: its value, and the flow
+/// along each edge which carries any.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Flow<'a, T>
+where
+    T: Ord,
+{
+    pub value: u64,
+    pub edges: BTreeMap<(&'a T, &'a T), u64>,
+}
+
+/// `MaxFlow` returns a maximum flow from the vertex source to the vertex sink,
+/// where each edge (x, y) carries at most the given capacity from x to y. An error
+/// is returned if either vertex does not exist, and `Error::LimitExceeded` if
+/// the value of the flow does not fit in a `u64`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, MaxFlow};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint_A"));
+/// dag.add_vertex(String::from("waypoint_B"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_A"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint_B"));
+/// dag.add_edge(String::from("waypoint_A"), String::from("destination"));
+/// dag.add_edge(String::from("waypoint_B"), String::from("destination"));
+///
+/// let flow = dag
+///     .max_flow(&String::from("origin"), &String::from("destination"), |_, _| 1)
+///     .unwrap();
+/// assert_eq!(flow.value, 2);
+/// ```
+pub trait MaxFlow<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn max_flow(
+        &self,
+        source: &T,
+        sink: &T,
+        capacities: impl Fn(&T, &T) -> u64,
+    ) -> Result<Flow<'_, T>, Self::Error>;
+}
+
+impl<T> MaxFlow<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn max_flow(
+        &self,
+        source: &T,
+        sink: &T,
+        capacities: impl Fn(&T, &T) -> u64,
+    ) -> Result<Flow<'_, T>, Self::Error> {
+        let network = Network::new(self, source, sink, capacities)?;
+        Ok(network.into_flow())
+    }
+}
+
+/// `MinCut` returns a minimum set of edges whose removal disconnects the vertex
+/// sink from the vertex source, where each edge (x, y) has the given capacity.
+/// The total capacity of the cut equals the value of a maximum flow, so the cut
+/// edges are the bottleneck between the two vertices. An error is returned if
+/// either vertex does not exist, and `Error::LimitExceeded` if the value of a
+/// maximum flow does not fit in a `u64`.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, MinCut};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let origin = String::from("origin");
+/// let waypoint = String::from("waypoint");
+/// let destination = String::from("destination");
+/// let cut = dag
+///     .min_cut(&origin, &destination, |x, _| if *x == origin { 2 } else { 1 })
+///     .unwrap();
+/// assert_eq!(cut.into_iter().collect::<Vec<_>>(), [(&waypoint, &destination)]);
+/// ```
+pub trait MinCut<T>
+where
+    T: Ord,
+{
+    type Error;
+    fn min_cut(
+        &self,
+        source: &T,
+        sink: &T,
+        capacities: impl Fn(&T, &T) -> u64,
+    ) -> Result<BTreeSet<(&T, &T)>, Self::Error>;
+}
+
+impl<T> MinCut<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn min_cut(
+        &self,
+        source: &T,
+        sink: &T,
+        capacities: impl Fn(&T, &T) -> u64,
+    ) -> Result<BTreeSet<(&T, &T)>, Self::Error> {
+        let network = Network::new(self, source, sink, capacities)?;
+        Ok(network.into_cut(source))
+    }
+}
+
+/// `Network` is the residual network of a dag, by vertex index, which finds
+/// a maximum flow with Dinic's algorithm.
+pub(crate) struct Network<'a, T>
+where
+    T: Ord,
+{
+    vertices: Vec<&'a T>,
+    // Each arc is stored next to its reverse, so arc i ^ 1 is the
+    // reverse of arc i. Even arcs are the edges of the dag.
+    heads: Vec<usize>,
+    residuals: Vec<u64>,
+    arcs: Vec<Vec<usize>>,
+    value: u64,
+}
+
+impl<'a, T> Network<'a, T>
+where
+    T: Ord,
+{
+    /// Builds the residual network, and saturates it with a maximum flow.
+    pub(crate) fn new(
+        dag: &'a BTreeDAG<T>,
+        source: &T,
+        sink: &T,
+        capacities: impl Fn(&T, &T) -> u64,
+    ) -> Result<Self, Error> {
+        let vertices: Vec<&T> = dag.vertices.keys().collect();
+        let source = vertices
+            .binary_search(&source)
+            .map_err(|_| Error::VertexDoesNotExist)?;
+        let sink = vertices
+            .binary_search(&sink)
+            .map_err(|_| Error::VertexDoesNotExist)?;
+        let mut network = Network {
+            vertices,
+            heads: Vec::new(),
+            residuals: Vec::new(),
+            arcs: Vec::new(),
+            value: 0,
+        };
+        network.arcs = vec![Vec::new(); network.vertices.len()];
+        for (x, (v, adj)) in dag.vertices.iter().enumerate() {
+            for w in adj.iter() {
+                // Every adjacent vertex is a vertex, so we can safely unwrap.
+                let y = network.vertices.binary_search(&w).unwrap();
+                network.arcs[x].push(network.heads.len());
+                network.heads.push(y);
+                network.residuals.push(capacities(v, w));
+                network.arcs[y].push(network.heads.len());
+                network.heads.push(x);
+                network.residuals.push(0);
+            }
+        }
+        if source != sink {
+            while let Some(levels) = network.levels(source, sink) {
+                let mut next: Vec<usize> = vec![0; network.vertices.len()];
+                loop {
+                    let pushed = network.augment(source, sink, &levels, &mut next)?;
+                    if pushed == 0 {
+                        break;
+                    }
+                    network.value = network
+                        .value
+                        .checked_add(pushed)
+                        .ok_or(Error::LimitExceeded)?;
+                }
+            }
+        }
+        Ok(network)
+    }
+
+    /// Returns the breadth first distance of each vertex from the source, in
+    /// the residual network, or `None` if the sink is unreachable.
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut levels: Vec<usize> = vec![usize::MAX; self.vertices.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        levels[source] = 0;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            for arc in self.arcs[v].iter() {
+                let w = self.heads[*arc];
+                if self.residuals[*arc] > 0 && levels[w] == usize::MAX {
+                    levels[w] = levels[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        if levels[sink] == usize::MAX {
+            return None;
+        }
+        Some(levels)
+    }
+
+    /// Pushes flow from the source to the sink along a path of arcs which
+    /// increase the level, returning the amount pushed. The path is searched
+    /// depth first with an explicit stack, since it may be as long as the dag
+    /// is deep. Arcs leading to dead ends are skipped for the rest of the
+    /// phase, by advancing next.
+    fn augment(
+        &mut self,
+        source: usize,
+        sink: usize,
+        levels: &[usize],
+        next: &mut [usize],
+    ) -> Result<u64, Error> {
+        let mut path: Vec<usize> = Vec::new();
+        let mut v = source;
+        loop {
+            if v == sink {
+                // The source is not the sink, so the path has an arc.
+                let pushed = path.iter().map(|arc| self.residuals[*arc]).min().unwrap();
+                for arc in path.into_iter() {
+                    self.residuals[arc] -= pushed;
+                    self.residuals[arc ^ 1] = self.residuals[arc ^ 1]
+                        .checked_add(pushed)
+                        .ok_or(Error::LimitExceeded)?;
+                }
+                return Ok(pushed);
+            }
+            let mut advanced = false;
+            while next[v] < self.arcs[v].len() {
+                let arc = self.arcs[v][next[v]];
+                let w = self.heads[arc];
+                if self.residuals[arc] > 0 && levels[w] == levels[v] + 1 {
+                    path.push(arc);
+                    v = w;
+                    advanced = true;
+                    break;
+                }
+                next[v] += 1;
+            }
+            if !advanced {
+                // v is a dead end, so retreat along the arc to it, and skip
+                // that arc from now on.
+                match path.pop() {
+                    Some(arc) => {
+                        v = self.heads[arc ^ 1];
+                        next[v] += 1;
+                    }
+                    None => return Ok(0),
+                }
+            }
+        }
+    }
+
+    /// Returns the edges from vertices reachable from the source, in the
+    /// saturated residual network, to vertices which are not.
+    fn into_cut(self, source: &T) -> BTreeSet<(&'a T, &'a T)> {
+        // The source is a vertex, so we can safely unwrap.
+        let source = self.vertices.binary_search(&source).unwrap();
+        let mut reachable: Vec<bool> = vec![false; self.vertices.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        reachable[source] = true;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            for arc in self.arcs[v].iter() {
+                let w = self.heads[*arc];
+                if self.residuals[*arc] > 0 && !reachable[w] {
+                    reachable[w] = true;
+                    queue.push_back(w);
+                }
+            }
+        }
+        let mut cut: BTreeSet<(&T, &T)> = BTreeSet::new();
+        for (x, arcs) in self.arcs.iter().enumerate().filter(|(x, _)| reachable[*x]) {
+            for arc in arcs.iter().filter(|arc| *arc % 2 == 0) {
+                let y = self.heads[*arc];
+                if !reachable[y] {
+                    cut.insert((self.vertices[x], self.vertices[y]));
+                }
+            }
+        }
+        cut
+    }
+
+    fn into_flow(self) -> Flow<'a, T> {
+        let mut edges: BTreeMap<(&T, &T), u64> = BTreeMap::new();
+        for (x, arcs) in self.arcs.iter().enumerate() {
+            for arc in arcs.iter().filter(|arc| *arc % 2 == 0) {
+                // The flow along an edge is the residual of its reverse.
+                let flow = self.residuals[arc ^ 1];
+                if flow > 0 {
+                    edges.insert((self.vertices[x], self.vertices[self.heads[*arc]]), flow);
+                }
+            }
+        }
+        Flow {
+            value: self.value,
+            edges,
+        }
+    }
+}
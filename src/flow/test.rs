@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::flow::*;
+    use crate::Error;
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn max_flow() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 2), (1, 3) and
+        // (2, 3), with capacities 3, 2, 1, 2 and 3.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        let capacities: BTreeMap<(usize, usize), u64> = BTreeMap::from([
+            ((0, 1), 3),
+            ((0, 2), 2),
+            ((1, 2), 1),
+            ((1, 3), 2),
+            ((2, 3), 3),
+        ]);
+
+        let flow = dag.max_flow(&0, &3, |x, y| capacities[&(*x, *y)])?;
+        assert_eq!(flow.value, 5);
+        // The flow is conserved at every inner vertex, and within capacity.
+        for v in [1, 2] {
+            let inflow: u64 = flow
+                .edges
+                .iter()
+                .filter(|((_, y), _)| **y == v)
+                .map(|(_, f)| f)
+                .sum();
+            let outflow: u64 = flow
+                .edges
+                .iter()
+                .filter(|((x, _), _)| **x == v)
+                .map(|(_, f)| f)
+                .sum();
+            assert_eq!(inflow, outflow);
+        }
+        for ((x, y), f) in flow.edges.iter() {
+            assert!(*f <= capacities[&(**x, **y)]);
+        }
+
+        // Flow can only follow the direction of the edges.
+        assert_eq!(dag.max_flow(&3, &0, |_, _| 1)?.value, 0);
+        assert_eq!(dag.max_flow(&0, &0, |_, _| 1)?.value, 0);
+        assert_eq!(
+            dag.max_flow(&0, &4, |_, _| 1),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn min_cut() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 3) and (2, 3), with
+        // capacities 1, 3, 2 and 1.
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        let capacities: BTreeMap<(usize, usize), u64> =
+            BTreeMap::from([((0, 1), 1), ((0, 2), 3), ((1, 3), 2), ((2, 3), 1)]);
+
+        let cut = dag.min_cut(&0, &3, |x, y| capacities[&(*x, *y)])?;
+        assert_eq!(cut, BTreeSet::from([(&0, &1), (&2, &3)]));
+        let value: u64 = cut.iter().map(|(x, y)| capacities[&(**x, **y)]).sum();
+        assert_eq!(
+            value,
+            dag.max_flow(&0, &3, |x, y| capacities[&(*x, *y)])?.value
+        );
+
+        // No edges separate a sink which is already unreachable.
+        assert!(dag.min_cut(&3, &0, |_, _| 1)?.is_empty());
+        assert_eq!(
+            dag.min_cut(&4, &0, |_, _| 1),
+            Err(Error::VertexDoesNotExist)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_flow_deep_chain() {
+        // Build a chain of 10000 nodes, with an edge (i, i + 1) of capacity
+        // 2 but for a bottleneck (5000, 5001) of capacity 1, and find the flow
+        // and the cut along it on a thread with a small stack, which a
+        // recursive search would overflow.
+        let len: usize = 10_000;
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges((0..len - 1).map(|i| (i, i + 1))).unwrap();
+        let capacities = |x: &usize, _: &usize| if *x == 5000 { 1 } else { 2 };
+        let (value, cut): (u64, Vec<(usize, usize)>) = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                let value = dag.max_flow(&0, &(len - 1), capacities).unwrap().value;
+                let cut = dag.min_cut(&0, &(len - 1), capacities).unwrap();
+                (value, cut.into_iter().map(|(x, y)| (*x, *y)).collect())
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(value, 1);
+        assert_eq!(cut, [(5000, 5001)]);
+
+        // Test passed.
+    }
+
+    #[test]
+    fn max_flow_overflow() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (0, 2), (1, 3) and (2, 3).
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 1), (0, 2), (1, 3), (2, 3)])?;
+
+        // Either path alone carries the largest capacity.
+        let flow = dag.max_flow(&0, &3, |x, _| if *x == 2 { 0 } else { u64::MAX })?;
+        assert_eq!(flow.value, u64::MAX);
+        assert_eq!(flow.edges[&(&0, &1)], u64::MAX);
+        assert_eq!(flow.edges[&(&1, &3)], u64::MAX);
+
+        // Both paths together carry more than a u64 holds.
+        assert_eq!(
+            dag.max_flow(&0, &3, |_, _| u64::MAX),
+            Err(Error::LimitExceeded)
+        );
+        assert_eq!(
+            dag.min_cut(&0, &3, |_, _| u64::MAX),
+            Err(Error::LimitExceeded)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_flow_unconnected() -> Result<(), Error> {
+        // Add five nodes, and an edge (0, 1), (1, 2) and (3, 4).
+        let dag: BTreeDAG<usize> = BTreeDAG::from_edges(vec![(0, 1), (1, 2), (3, 4)])?;
+
+        // No flow reaches a sink in another component.
+        let flow = dag.max_flow(&0, &4, |_, _| u64::MAX)?;
+        assert_eq!(flow.value, 0);
+        assert!(flow.edges.is_empty());
+        assert!(dag.min_cut(&0, &4, |_, _| u64::MAX)?.is_empty());
+
+        // No flow leaves a source which is the sink.
+        let flow = dag.max_flow(&1, &1, |_, _| u64::MAX)?;
+        assert_eq!(flow.value, 0);
+        assert!(flow.edges.is_empty());
+        assert!(dag.min_cut(&1, &1, |_, _| u64::MAX)?.is_empty());
+
+        // Edges of no capacity carry no flow.
+        assert_eq!(dag.max_flow(&0, &2, |_, _| 0)?.value, 0);
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::dag::{BTreeDAG, VertexIndex};
+use crate::Error;
+
+/// `FrozenDag` is an immutable snapshot of a `BTreeDAG` in compressed sparse
+/// row layout: the vertices in ascending order, and the adjacent vertices of
+/// each one as a contiguous slice of indices. Vertices are addressed by
+/// their index, which is their position in ascending order, so traversals
+/// neither allocate per vertex nor compare keys.
+///
+/// With the `serde` feature, the frozen form can be persisted as is; it is
+/// validated when it is deserialized. Like a `BTreeDAG`, the maximum traversal
+/// depth it thaws with is neither compared nor serialized.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct FrozenDag<T> {
+    vertices: Vec<T>,
+    // The adjacent vertices of vertex i are targets[offsets[i]..offsets[i + 1]].
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) max_traversal_depth: Option<usize>,
+}
+
+impl<T> PartialEq for FrozenDag<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.vertices == other.vertices
+            && self.offsets == other.offsets
+            && self.targets == other.targets
+    }
+}
+
+impl<T> Eq for FrozenDag<T> where T: Eq {}
+
+impl<T> FrozenDag<T>
+where
+    T: Ord,
+{
+    /// Constructs a frozen dag from the vertices in ascending order, and the
+    /// adjacent vertices of each one, by key.
+    pub(crate) fn new<'a>(adjacency: impl Iterator<Item = (T, impl Iterator<Item = &'a T>)>) -> Self
+    where
+        T: 'a,
+    {
+        let mut vertices: Vec<T> = Vec::new();
+        let mut adjacent: Vec<Vec<&'a T>> = Vec::new();
+        for (x, adj_x) in adjacency {
+            vertices.push(x);
+            adjacent.push(adj_x.collect());
+        }
+        let mut offsets: Vec<usize> = Vec::with_capacity(vertices.len() + 1);
+        let mut targets: Vec<usize> = Vec::new();
+        offsets.push(0);
+        for adj_x in adjacent {
+            // Every adjacent vertex is a vertex, so we can safely unwrap.
+            targets.extend(
+                adj_x
+                    .into_iter()
+                    .map(|y| vertices.binary_search(y).unwrap()),
+            );
+            offsets.push(targets.len());
+        }
+        FrozenDag {
+            vertices,
+            offsets,
+            targets,
+            max_traversal_depth: None,
+        }
+    }
+
+    /// Converts the snapshot back into an editable `BTreeDAG`.
+    pub fn thaw(&self) -> BTreeDAG<T>
+    where
+        T: Clone,
+    {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self
+            .vertices
+            .iter()
+            .zip(self.offsets.windows(2))
+            .map(|(x, range)| {
+                let adj_x: BTreeSet<T> = self.targets[range[0]..range[1]]
+                    .iter()
+                    .map(|j| self.vertices[*j].clone())
+                    .collect();
+                (x.clone(), Arc::new(adj_x))
+            })
+            .collect();
+        let mut dag = BTreeDAG::from_vertices(vertices);
+        dag.max_traversal_depth = self.max_traversal_depth;
+        dag
+    }
+
+    /// Verifies the invariants which `new` upholds, returning a description
+    /// of the first one violated.
+    #[cfg(any(feature = "serde", test))]
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.vertices.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err("vertices are not in strictly ascending order");
+        }
+        if self.offsets.len() != self.vertices.len() + 1
+            || self.offsets[0] != 0
+            || self.offsets[self.vertices.len()] != self.targets.len()
+            || self.offsets.windows(2).any(|pair| pair[0] > pair[1])
+        {
+            return Err("offsets do not partition the targets");
+        }
+        for i in 0..self.len() {
+            let adj = &self.targets[self.offsets[i]..self.offsets[i + 1]];
+            if adj.iter().any(|j| *j >= self.len()) {
+                return Err("target is out of bounds");
+            }
+            if adj.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err("targets are not in strictly ascending order");
+            }
+        }
+        // Kahn's algorithm visits every vertex exactly when there is no
+        // cycle.
+        let mut in_degrees: Vec<usize> = vec![0; self.len()];
+        for j in self.targets.iter() {
+            in_degrees[*j] += 1;
+        }
+        let mut ready: Vec<usize> = (0..self.len()).filter(|i| in_degrees[*i] == 0).collect();
+        let mut visited: usize = 0;
+        while let Some(i) = ready.pop() {
+            visited += 1;
+            for j in self.targets[self.offsets[i]..self.offsets[i + 1]].iter() {
+                in_degrees[*j] -= 1;
+                if in_degrees[*j] == 0 {
+                    ready.push(*j);
+                }
+            }
+        }
+        if visited != self.len() {
+            return Err("edges contain a cycle");
+        }
+        Ok(())
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns true if the dag has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns the vertices in ascending order.
+    pub fn vertices(&self) -> &[T] {
+        &self.vertices
+    }
+
+    /// Returns the indices of the vertices adjacent to the vertex at index i,
+    /// in ascending order, or `None` if i is out of bounds.
+    pub fn successors(&self, i: usize) -> Option<&[usize]> {
+        if i >= self.len() {
+            return None;
+        }
+        Some(&self.targets[self.offsets[i]..self.offsets[i + 1]])
+    }
+
+    /// Returns the indices of the vertices reachable from the vertex at index
+    /// i, excluding i, in ascending order.
+    pub fn descendants(&self, i: usize) -> Result<Vec<usize>, Error> {
+        let visited = self.visit(i)?;
+        Ok(visited
+            .iter()
+            .enumerate()
+            .filter(|(j, v)| **v && *j != i)
+            .map(|(j, _)| j)
+            .collect())
+    }
+
+    /// Returns true if there is a path of at least one edge from the vertex
+    /// at index i to the vertex at index j.
+    pub fn is_reachable(&self, i: usize, j: usize) -> Result<bool, Error> {
+        if j >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        Ok(i != j && self.visit(i)?[j])
+    }
+
+    /// Depth first search from i, returning which vertices were visited.
+    fn visit(&self, i: usize) -> Result<Vec<bool>, Error> {
+        if i >= self.len() {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let mut visited: Vec<bool> = vec![false; self.len()];
+        let mut stack: Vec<usize> = vec![i];
+        visited[i] = true;
+        while let Some(v) = stack.pop() {
+            for w in self.targets[self.offsets[v]..self.offsets[v + 1]].iter() {
+                if !visited[*w] {
+                    visited[*w] = true;
+                    stack.push(*w);
+                }
+            }
+        }
+        Ok(visited)
+    }
+}
+
+/// Vertices are looked up by binary search, and indices in constant time.
+impl<T> VertexIndex<T> for FrozenDag<T>
+where
+    T: Ord,
+{
+    fn index_of(&self, x: &T) -> Option<usize> {
+        self.vertices.binary_search(x).ok()
+    }
+
+    fn vertex_at(&self, i: usize) -> Option<&T> {
+        self.vertices.get(i)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for FrozenDag<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// The fields of a `FrozenDag`, before they are validated.
+        #[derive(Deserialize)]
+        #[serde(rename = "FrozenDag")]
+        struct Unvalidated<T> {
+            vertices: Vec<T>,
+            offsets: Vec<usize>,
+            targets: Vec<usize>,
+        }
+
+        let unvalidated = Unvalidated::deserialize(deserializer)?;
+        let frozen = FrozenDag {
+            vertices: unvalidated.vertices,
+            offsets: unvalidated.offsets,
+            targets: unvalidated.targets,
+            max_traversal_depth: None,
+        };
+        frozen.validate().map_err(serde::de::Error::custom)?;
+        Ok(frozen)
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::frozen::*;
+    use crate::Error;
+
+    #[test]
+    fn freeze() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2) and (0, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(30);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(0, 30)?;
+
+        let frozen: FrozenDag<usize> = dag.freeze();
+        assert_eq!(frozen.len(), 4);
+        assert_eq!(frozen.edge_count(), 3);
+        assert_eq!(frozen.vertices(), [0, 1, 2, 30]);
+        assert_eq!(frozen.index_of(&30), Some(3));
+        assert_eq!(frozen.vertex_at(3), Some(&30));
+        assert_eq!(frozen.successors(0), Some(&[1, 3][..]));
+        assert_eq!(frozen.successors(4), None);
+
+        assert_eq!(frozen.descendants(0)?, [1, 2, 3]);
+        assert!(frozen.is_reachable(0, 2)?);
+        assert!(!frozen.is_reachable(2, 0)?);
+        assert!(!frozen.is_reachable(0, 0)?);
+        assert_eq!(frozen.is_reachable(0, 4), Err(Error::VertexDoesNotExist));
+
+        // The original remains editable.
+        dag.remove_vertex(1)?;
+        assert_eq!(frozen.len(), 4);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn thaw() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2) and (0, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(0, 3)?;
+
+        let frozen: FrozenDag<usize> = dag.freeze();
+        assert_eq!(frozen.validate(), Ok(()));
+        assert_eq!(frozen.thaw(), dag);
+        assert_eq!(BTreeDAG::<usize>::new().freeze().thaw(), BTreeDAG::new());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn serde() -> Result<(), Error> {
+        use try_encoding_from::serde_json;
+
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        let frozen: FrozenDag<usize> = dag.freeze();
+        let json = serde_json::to_string(&frozen)?;
+        assert_eq!(
+            json,
+            r#"{"vertices":[0,1,2],"offsets":[0,1,2,2],"targets":[1,2]}"#
+        );
+        assert_eq!(serde_json::from_str::<FrozenDag<usize>>(&json)?, frozen);
+
+        // Invalid frozen forms are rejected.
+        let cyclic = r#"{"vertices":[0,1],"offsets":[0,1,2],"targets":[1,0]}"#;
+        assert!(serde_json::from_str::<FrozenDag<usize>>(cyclic).is_err());
+        let out_of_bounds = r#"{"vertices":[0,1],"offsets":[0,1,1],"targets":[2]}"#;
+        assert!(serde_json::from_str::<FrozenDag<usize>>(out_of_bounds).is_err());
+        let unordered = r#"{"vertices":[1,0],"offsets":[0,0,0],"targets":[]}"#;
+        assert!(serde_json::from_str::<FrozenDag<usize>>(unordered).is_err());
+
+        // Test passed.
+        Ok(())
+    }
+}
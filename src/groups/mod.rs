@@ -0,0 +1,99 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::default::Default;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::{AddVertex, BTreeDAG, FromEdges};
+use crate::Error;
+
+/// `Groups` assigns the vertices of a dag to named groups, such as the
+/// modules of a file-level dependency graph. Each vertex belongs to at most
+/// one group. The assignments are kept apart from the dag, so one dag can be
+/// viewed through several groupings.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Groups<T, G>
+where
+    T: Ord,
+{
+    groups: BTreeMap<T, G>,
+}
+
+impl<T, G> Groups<T, G>
+where
+    T: Ord,
+    G: Ord,
+{
+    pub fn new() -> Self {
+        let groups: BTreeMap<T, G> = BTreeMap::new();
+        Groups { groups }
+    }
+
+    /// Assigns the vertex x to the group g, returning its previous group.
+    pub fn set_group(&mut self, x: T, g: G) -> Option<G> {
+        self.groups.insert(x, g)
+    }
+
+    /// Returns the group of the vertex x, if it has been assigned one.
+    pub fn group(&self, x: &T) -> Option<&G> {
+        self.groups.get(x)
+    }
+
+    /// Removes the vertex x from its group, returning the group.
+    pub fn remove_group(&mut self, x: &T) -> Option<G> {
+        self.groups.remove(x)
+    }
+
+    /// Returns the vertices assigned to the group g.
+    pub fn members(&self, g: &G) -> BTreeSet<&T> {
+        self.groups
+            .iter()
+            .filter(|(_, group)| *group == g)
+            .map(|(x, _)| x)
+            .collect()
+    }
+
+    /// Returns the dag of groups, which has an edge (g, h) whenever the dag
+    /// has an edge from a vertex of g to a vertex of h, for distinct groups
+    /// g and h. Every vertex of the dag must be assigned a group, otherwise
+    /// `Error::VertexDoesNotExist` is returned, and `Error::EdgeExists` is
+    /// returned if the groups depend on each other cyclically.
+    pub fn quotient_graph(&self, dag: &BTreeDAG<T>) -> Result<BTreeDAG<G>, Error>
+    where
+        G: Clone,
+    {
+        let mut group_vertices: BTreeSet<&G> = BTreeSet::new();
+        let mut group_edges: BTreeSet<(&G, &G)> = BTreeSet::new();
+        for (x, adj_x) in dag.vertices.iter() {
+            let g = self.groups.get(x).ok_or(Error::VertexDoesNotExist)?;
+            group_vertices.insert(g);
+            for y in adj_x.iter() {
+                let h = self.groups.get(y).ok_or(Error::VertexDoesNotExist)?;
+                if g != h {
+                    group_edges.insert((g, h));
+                }
+            }
+        }
+        let mut quotient: BTreeDAG<G> =
+            BTreeDAG::from_edges(group_edges.into_iter().map(|(g, h)| (g.clone(), h.clone())))?;
+        for g in group_vertices {
+            if !quotient.vertices.contains_key(g) {
+                quotient.add_vertex(g.clone());
+            }
+        }
+        Ok(quotient)
+    }
+}
+
+impl<T, G> Default for Groups<T, G>
+where
+    T: Ord,
+    G: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
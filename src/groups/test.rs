@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::groups::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn set_group() {
+        // Assign three nodes to two groups.
+        let mut groups: Groups<usize, &str> = Groups::new();
+        assert_eq!(groups, Groups::default());
+        assert_eq!(groups.set_group(0, "a"), None);
+        assert_eq!(groups.set_group(1, "a"), None);
+        assert_eq!(groups.set_group(2, "b"), None);
+        assert_eq!(groups.set_group(1, "b"), Some("a"));
+
+        assert_eq!(groups.group(&1), Some(&"b"));
+        assert_eq!(groups.members(&"b"), BTreeSet::from([&1, &2]));
+        assert_eq!(groups.remove_group(&1), Some("b"));
+        assert_eq!(groups.group(&1), None);
+
+        // Test passed.
+    }
+
+    #[test]
+    fn quotient_graph() -> Result<(), Error> {
+        // Add four nodes, and an edge (0, 1), (1, 2) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_vertex(3);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.add_edge(2, 3)?;
+
+        // Every vertex must be grouped.
+        let mut groups: Groups<usize, &str> = Groups::new();
+        groups.set_group(0, "a");
+        groups.set_group(1, "a");
+        groups.set_group(2, "b");
+        assert_eq!(groups.quotient_graph(&dag), Err(Error::VertexDoesNotExist));
+
+        // Edges within a group are dropped.
+        groups.set_group(3, "b");
+        let quotient: BTreeDAG<&str> = groups.quotient_graph(&dag)?;
+        assert_eq!(quotient.vertices(), BTreeSet::from([&"a", &"b"]));
+        assert!(quotient.adjacent("a", "b")?);
+
+        // A group with no edges to other groups is kept.
+        groups.set_group(3, "c");
+        groups.set_group(2, "c");
+        groups.set_group(1, "c");
+        groups.set_group(0, "c");
+        let quotient: BTreeDAG<&str> = groups.quotient_graph(&dag)?;
+        assert_eq!(quotient.vertices(), BTreeSet::from([&"c"]));
+
+        // Groups which depend on each other are rejected.
+        groups.set_group(0, "a");
+        groups.set_group(1, "b");
+        groups.set_group(2, "a");
+        assert_eq!(groups.quotient_graph(&dag), Err(Error::EdgeExists));
+
+        // Test passed.
+        Ok(())
+    }
+}
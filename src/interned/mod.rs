@@ -0,0 +1,205 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::BTreeDAG;
+use crate::Error;
+
+/// The number of keys in each block of `FrontCodedKeys`.
+const BLOCK: usize = 16;
+
+/// `FrontCodedKeys` is a table of distinct strings in ascending order, where
+/// each string is stored as the length of the prefix it shares with the string
+/// before it, and the rest of the string. The first string of each block of 16
+/// is stored in full, so a string is decoded from at most 16 entries, and a
+/// string is found by binary search over the first strings of the blocks.
+/// Keys such as file paths, which mostly share long prefixes, compress well.
+///
+/// A table which was deserialized is not validated, so lookups return None
+/// rather than panicking on a malformed entry.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrontCodedKeys {
+    entries: Vec<(usize, String)>,
+}
+
+impl FrontCodedKeys {
+    /// The keys must be distinct and in ascending order.
+    pub(crate) fn new<'a>(keys: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut entries: Vec<(usize, String)> = Vec::new();
+        let mut previous: &str = "";
+        for (i, key) in keys.into_iter().enumerate() {
+            let shared: usize = if i % BLOCK == 0 {
+                0
+            } else {
+                shared_prefix_len(previous, key)
+            };
+            entries.push((shared, String::from(&key[shared..])));
+            previous = key;
+        }
+        FrontCodedKeys { entries }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of bytes of the stored suffixes, which is at most
+    /// the number of bytes of the keys.
+    pub fn suffix_bytes(&self) -> usize {
+        self.entries.iter().map(|(_, suffix)| suffix.len()).sum()
+    }
+
+    /// Returns the i-th key in ascending order.
+    pub fn get(&self, i: usize) -> Option<String> {
+        let entries = self.entries.get(i - i % BLOCK..=i)?;
+        let mut key: String = String::new();
+        for (shared, suffix) in entries.iter() {
+            decode(&mut key, *shared, suffix)?;
+        }
+        Some(key)
+    }
+
+    /// Returns the position of the key in ascending order, or None if it is
+    /// not in the table.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        // Find the last block whose first key is less than or equal to key.
+        let (mut lo, mut hi) = (0, self.entries.len().div_ceil(BLOCK));
+        while lo < hi {
+            let mid: usize = (lo + hi) / 2;
+            if self.entries[mid * BLOCK].1.as_str() <= key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        let start: usize = lo.checked_sub(1)? * BLOCK;
+        let mut decoded: String = String::new();
+        for (i, (shared, suffix)) in self.entries[start..].iter().take(BLOCK).enumerate() {
+            decode(&mut decoded, *shared, suffix)?;
+            match decoded.as_str().cmp(key) {
+                Ordering::Less => {}
+                Ordering::Equal => return Some(start + i),
+                Ordering::Greater => return None,
+            }
+        }
+        None
+    }
+
+    /// Returns the keys in ascending order, stopping at the first malformed
+    /// entry.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        let mut key: String = String::new();
+        self.entries.iter().map_while(move |(shared, suffix)| {
+            decode(&mut key, *shared, suffix)?;
+            Some(key.clone())
+        })
+    }
+}
+
+/// Returns the length in bytes of the longest common prefix of a and b, which
+/// ends at a character boundary of both.
+fn shared_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or_else(|| a.len().min(b.len()), |((i, _), _)| i)
+}
+
+/// Replaces the key with its first shared bytes followed by the suffix, or
+/// returns None if the key has no such prefix.
+fn decode(key: &mut String, shared: usize, suffix: &str) -> Option<()> {
+    if !key.is_char_boundary(shared) {
+        return None;
+    }
+    key.truncate(shared);
+    key.push_str(suffix);
+    Some(())
+}
+
+/// `InternedDag` is a read-only, compressed copy of a `BTreeDAG<String>`. Each
+/// key is replaced by its position in ascending order, indexing a table of
+/// `FrontCodedKeys`, so the dag over positions orders its vertices like the
+/// original, and both the in-memory and serialized forms store each key once,
+/// front coded. It is constructed from a `&BTreeDAG<String>`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InternedDag {
+    keys: FrontCodedKeys,
+    dag: BTreeDAG<usize>,
+}
+
+impl InternedDag {
+    /// Returns the table of keys.
+    pub fn keys(&self) -> &FrontCodedKeys {
+        &self.keys
+    }
+
+    /// Returns the dag over the positions of the keys.
+    pub fn dag(&self) -> &BTreeDAG<usize> {
+        &self.dag
+    }
+
+    /// Returns the position of the key, or None if it is not a vertex.
+    pub fn id(&self, key: &str) -> Option<usize> {
+        self.keys.position(key)
+    }
+
+    /// Returns the key at the position, or None if there is no such vertex.
+    pub fn key(&self, id: usize) -> Option<String> {
+        self.keys.get(id)
+    }
+
+    /// Decodes the dag with its keys. An error is returned if the table
+    /// of keys is malformed, or if the dag refers to a position which is not
+    /// in the table, which can only happen if it was deserialized.
+    pub fn to_dag(&self) -> Result<BTreeDAG<String>, Error> {
+        let keys: Vec<String> = self.keys.iter().collect();
+        if keys.len() != self.keys.len() {
+            return Err(Error::ParseError);
+        }
+        let mut vertices: BTreeMap<String, Arc<BTreeSet<String>>> = BTreeMap::new();
+        for (x, adj_x) in self.dag.vertices.iter() {
+            let key = |id: &usize| keys.get(*id).cloned().ok_or(Error::VertexDoesNotExist);
+            let adj: BTreeSet<String> = adj_x.iter().map(key).collect::<Result<_, Error>>()?;
+            vertices.insert(key(x)?, Arc::new(adj));
+        }
+        Ok(BTreeDAG::from_vertices(vertices))
+    }
+}
+
+impl From<&BTreeDAG<String>> for InternedDag {
+    fn from(dag: &BTreeDAG<String>) -> Self {
+        let keys: Vec<&String> = dag.vertices.keys().collect();
+        // Every adjacent vertex is a vertex, so we can safely unwrap.
+        let vertices: BTreeMap<usize, Arc<BTreeSet<usize>>> = dag
+            .vertices
+            .values()
+            .enumerate()
+            .map(|(i, adj)| {
+                let adj: BTreeSet<usize> = adj
+                    .iter()
+                    .map(|y| keys.binary_search(&y).unwrap())
+                    .collect();
+                (i, Arc::new(adj))
+            })
+            .collect();
+        InternedDag {
+            keys: FrontCodedKeys::new(keys.into_iter().map(String::as_str)),
+            dag: BTreeDAG::from_vertices(vertices),
+        }
+    }
+}
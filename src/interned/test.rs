@@ -0,0 +1,87 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::interned::*;
+    use crate::Error;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn interned_dag() -> Result<(), Error> {
+        // Add nodes keyed by file paths, each depending on the next.
+        let paths: Vec<String> = (0..40)
+            .map(|i| format!("src/dag/module_{:02}/mod.rs", i))
+            .collect();
+        let mut dag: BTreeDAG<String> = BTreeDAG::new();
+        for path in paths.iter() {
+            dag.add_vertex(path.clone());
+        }
+        for pair in paths.windows(2) {
+            dag.add_edge(pair[0].clone(), pair[1].clone())?;
+        }
+        dag.add_vertex(String::from("Cargo.toml"));
+        dag.add_edge(String::from("Cargo.toml"), paths[0].clone())?;
+
+        let interned = InternedDag::from(&dag);
+        assert_eq!(interned.keys().len(), 41);
+        let key_bytes: usize = dag.vertices().iter().map(|v| v.len()).sum();
+        assert!(interned.keys().suffix_bytes() * 2 < key_bytes);
+
+        // Positions follow the order of the keys.
+        assert_eq!(interned.id("Cargo.toml"), Some(0));
+        assert_eq!(interned.id(&paths[20]), Some(21));
+        assert_eq!(interned.key(21), Some(paths[20].clone()));
+        assert_eq!(interned.id("src/dag/module_20"), None);
+        assert_eq!(interned.id("a"), None);
+        assert_eq!(interned.key(41), None);
+        assert!(interned.dag().adjacent(0, 1)?);
+        assert!(interned.dag().adjacent(21, 22)?);
+        assert_eq!(interned.to_dag()?, dag);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn front_coded_keys() {
+        let keys = FrontCodedKeys::new(["", "ab", "abc", "abd", "b", "é", "éa"]);
+        assert_eq!(keys.iter().collect::<Vec<String>>().len(), 7);
+        assert_eq!(keys.get(3), Some(String::from("abd")));
+        assert_eq!(keys.position(""), Some(0));
+        assert_eq!(keys.position("éa"), Some(6));
+        assert_eq!(keys.position("abe"), None);
+        assert!(FrontCodedKeys::default().is_empty());
+        assert_eq!(FrontCodedKeys::default().position("a"), None);
+
+        // Test passed.
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn interned_json() -> Result<(), Error> {
+        use try_encoding_from::serde_json;
+
+        let dag: BTreeDAG<String> = BTreeDAG::from_edges([
+            (String::from("src/a.rs"), String::from("src/b.rs")),
+            (String::from("src/b.rs"), String::from("src/c.rs")),
+        ])?;
+        let json: String = serde_json::to_string(&InternedDag::from(&dag))?;
+        assert_eq!(
+            json,
+            "{\"keys\":{\"entries\":[[0,\"src/a.rs\"],[4,\"b.rs\"],[4,\"c.rs\"]]},\"dag\":{\"vertices\":{\"0\":[1],\"1\":[2],\"2\":[]}}}"
+        );
+        let interned: InternedDag = serde_json::from_str(&json)?;
+        assert_eq!(interned.to_dag()?, dag);
+
+        // A malformed table is rejected rather than panicking.
+        let malformed: InternedDag = serde_json::from_str(
+            "{\"keys\":{\"entries\":[[0,\"é\"],[1,\"a\"]]},\"dag\":{\"vertices\":{\"0\":[1],\"1\":[]}}}",
+        )?;
+        assert_eq!(malformed.to_dag(), Err(Error::ParseError));
+
+        // Test passed.
+        Ok(())
+    }
+}
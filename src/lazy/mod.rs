@@ -0,0 +1,225 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crate::dag::{AddEdge, AddVertex, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `AdjacencyLoader` loads the adjacency set of a single vertex of a dag which
+/// is too large to load at once, such as from an embedded store. A loader
+/// must describe a dag: every vertex it returns in an adjacency set must also
+/// load, and the loaded edges must contain no cycle. Any function
+/// `Fn(&T) -> Option<BTreeSet<T>>` is a loader.
+pub trait AdjacencyLoader<T> {
+    /// Returns the adjacency set of the vertex x, or None if x does not exist.
+    fn load(&self, x: &T) -> Option<BTreeSet<T>>;
+}
+
+impl<T, F> AdjacencyLoader<T> for F
+where
+    F: Fn(&T) -> Option<BTreeSet<T>>,
+{
+    fn load(&self, x: &T) -> Option<BTreeSet<T>> {
+        self(x)
+    }
+}
+
+/// `LazyDag` is a dag whose adjacency sets are loaded on demand through an
+/// `AdjacencyLoader`, and cached. Mutations apply to the cached sets, loading
+/// whatever they need, so the cycle check of `add_edge` loads the vertices
+/// reachable from y. Vertices which are not loaded are never scanned; instead
+/// a removed vertex is filtered out of every adjacency set loaded after its
+/// removal. Queries load, so they take the dag mutably.
+pub struct LazyDag<T, L>
+where
+    T: Ord,
+{
+    loader: L,
+    cache: BTreeMap<T, Option<BTreeSet<T>>>,
+    removed: BTreeSet<T>,
+    changed: BTreeSet<T>,
+}
+
+impl<T, L> LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    pub fn new(loader: L) -> Self {
+        LazyDag {
+            loader,
+            cache: BTreeMap::new(),
+            removed: BTreeSet::new(),
+            changed: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the loader.
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    /// Returns the number of vertices loaded, including those found not to
+    /// exist.
+    pub fn loaded_len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns the adjacency set of the vertex x, loading it if needed, or
+    /// None if x does not exist.
+    pub fn connections(&mut self, x: &T) -> Option<&BTreeSet<T>> {
+        if !self.cache.contains_key(x) {
+            let removed = &self.removed;
+            let adj_x: Option<BTreeSet<T>> = self.loader.load(x).map(|mut adj_x| {
+                adj_x.retain(|v| !removed.contains(v));
+                adj_x
+            });
+            self.cache.insert(x.clone(), adj_x);
+        }
+        self.cache[x].as_ref()
+    }
+
+    /// Returns true if the vertex x exists, loading it if needed.
+    pub fn contains(&mut self, x: &T) -> bool {
+        self.connections(x).is_some()
+    }
+
+    /// Returns true if the edge (x, y) exists. An error is returned if x or y
+    /// does not exist.
+    pub fn adjacent(&mut self, x: &T, y: &T) -> Result<bool, Error> {
+        if !self.contains(y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        self.connections(x)
+            .map(|adj_x| adj_x.contains(y))
+            .ok_or(Error::VertexDoesNotExist)
+    }
+
+    /// Returns the vertices reachable from x, loading each of them, or None
+    /// if x does not exist.
+    pub fn descendants(&mut self, x: &T) -> Option<BTreeSet<T>> {
+        let mut stack: Vec<T> = self.connections(x)?.iter().cloned().collect();
+        let mut descendants: BTreeSet<T> = BTreeSet::new();
+        while let Some(v) = stack.pop() {
+            if !descendants.contains(&v) {
+                // Every loaded adjacent vertex exists.
+                stack.extend(self.connections(&v).into_iter().flatten().cloned());
+                descendants.insert(v);
+            }
+        }
+        Some(descendants)
+    }
+
+    /// Returns every vertex changed since the dag was constructed, with its
+    /// adjacency set, or None if it was removed, for persisting the changes.
+    pub fn changes(&self) -> BTreeMap<&T, Option<&BTreeSet<T>>> {
+        self.changed
+            .iter()
+            .map(|x| (x, self.cache[x].as_ref()))
+            .collect()
+    }
+
+    /// Removes the vertices from the dag, and every edge to them.
+    fn remove_all(&mut self, vertices: &BTreeSet<T>) {
+        for (v, adj_v) in self.cache.iter_mut() {
+            if let Some(adj_v) = adj_v {
+                if adj_v.iter().any(|w| vertices.contains(w)) {
+                    adj_v.retain(|w| !vertices.contains(w));
+                    self.changed.insert(v.clone());
+                }
+            }
+        }
+        for x in vertices.iter() {
+            self.cache.insert(x.clone(), None);
+            self.removed.insert(x.clone());
+            self.changed.insert(x.clone());
+        }
+    }
+}
+
+impl<T, L> AddVertex<T> for LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.connections(&x);
+        self.changed.insert(x.clone());
+        self.cache.insert(x, Some(BTreeSet::new())).flatten()
+    }
+}
+
+impl<T, L> AddEdge<T> for LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.contains(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        // The edge closes a cycle if x is y, or is reachable from y.
+        let descendants: BTreeSet<T> = self.descendants(&y).ok_or(Error::VertexDoesNotExist)?;
+        if x == y || descendants.contains(&x) {
+            return Err(Error::EdgeExists);
+        }
+        self.changed.insert(x.clone());
+        // We have already loaded x, and verified it exists, so we can safely
+        // unwrap.
+        let adj_x: &mut BTreeSet<T> = self.cache.get_mut(&x).unwrap().as_mut().unwrap();
+        let previous_adj_x: BTreeSet<T> = adj_x.clone();
+        adj_x.insert(y);
+        Ok(previous_adj_x)
+    }
+}
+
+impl<T, L> RemoveEdge<T> for LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.adjacent(&x, &y)? {
+            // The edge does not exist, so nothing changes.
+            return Ok(self.connections(&x).unwrap().clone());
+        }
+        self.changed.insert(x.clone());
+        let adj_x: &mut BTreeSet<T> = self.cache.get_mut(&x).unwrap().as_mut().unwrap();
+        let previous_adj_x: BTreeSet<T> = adj_x.clone();
+        adj_x.remove(&y);
+        Ok(previous_adj_x)
+    }
+}
+
+impl<T, L> RemoveVertex<T> for LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x: BTreeSet<T> = self
+            .connections(&x)
+            .cloned()
+            .ok_or(Error::VertexDoesNotExist)?;
+        self.remove_all(&BTreeSet::from([x]));
+        Ok(adj_x)
+    }
+}
+
+impl<T, L> Prune<T> for LazyDag<T, L>
+where
+    T: Ord + Clone,
+    L: AdjacencyLoader<T>,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        let mut pruned: BTreeSet<T> = self.descendants(&x).ok_or(Error::VertexDoesNotExist)?;
+        pruned.insert(x);
+        self.remove_all(&pruned);
+        Ok(())
+    }
+}
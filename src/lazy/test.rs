@@ -0,0 +1,54 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::lazy::*;
+    use crate::Error;
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    /// Loads a chain 0 -> 1 -> ... -> 9, with the edge (5, 9).
+    fn load(x: &usize) -> Option<BTreeSet<usize>> {
+        match *x {
+            5 => Some(BTreeSet::from([6, 9])),
+            9 => Some(BTreeSet::new()),
+            x if x < 9 => Some(BTreeSet::from([x + 1])),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn lazy_dag() -> Result<(), Error> {
+        let mut dag = LazyDag::new(load);
+        assert_eq!(dag.connections(&5), Some(&BTreeSet::from([6, 9])));
+        assert!(!dag.contains(&10));
+        assert_eq!(dag.loaded_len(), 2);
+
+        // The cycle check only loads the vertices reachable from y.
+        assert_eq!(dag.add_edge(9, 7), Err(Error::EdgeExists));
+        assert_eq!(dag.loaded_len(), 5);
+        assert!(dag.add_edge(7, 9).is_ok());
+        assert_eq!(dag.add_edge(7, 7), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(7, 10), Err(Error::VertexDoesNotExist));
+
+        // A removed vertex is filtered out of adjacency sets loaded later.
+        dag.remove_vertex(6)?;
+        assert_eq!(dag.connections(&5), Some(&BTreeSet::from([9])));
+        assert_eq!(
+            dag.descendants(&0),
+            Some(BTreeSet::from([1, 2, 3, 4, 5, 9]))
+        );
+        dag.add_vertex(6);
+        assert!(!dag.adjacent(&5, &6)?);
+
+        dag.prune(3)?;
+        assert!(!dag.contains(&9));
+        assert_eq!(dag.connections(&2), Some(&BTreeSet::new()));
+        let changes: BTreeMap<&usize, Option<&BTreeSet<usize>>> = dag.changes();
+        assert_eq!(changes[&2], Some(&BTreeSet::new()));
+        assert_eq!(changes[&9], None);
+        assert_eq!(changes[&6], Some(&BTreeSet::new()));
+
+        // Test passed.
+        Ok(())
+    }
+}
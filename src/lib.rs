@@ -1,8 +1,100 @@
 #![no_std]
 extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
 
-/// `Error` type is re-exported from the separate btree_error crate.
-pub type Error = btree_error::Error;
+mod error;
+pub use error::*;
+
+#[macro_use]
+mod trace;
 
 mod dag;
 pub use dag::*;
+mod versioned;
+pub use versioned::*;
+mod groups;
+pub use groups::*;
+mod frozen;
+pub use frozen::*;
+mod flow;
+pub use flow::*;
+mod cache;
+pub use cache::*;
+mod multi;
+pub use multi::*;
+mod constraint;
+pub use constraint::*;
+mod limits;
+pub use limits::*;
+mod counted;
+pub use counted::*;
+mod alias;
+pub use alias::*;
+mod tombstone;
+pub use tombstone::*;
+mod overlay;
+pub use overlay::*;
+mod validated;
+pub use validated::*;
+mod loader;
+pub use loader::*;
+mod lazy;
+pub use lazy::*;
+mod interned;
+pub use interned::*;
+pub mod dynamic;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+pub use shared::*;
+#[cfg(feature = "std")]
+mod csv;
+#[cfg(feature = "std")]
+pub use csv::*;
+#[cfg(feature = "dot")]
+mod dot;
+#[cfg(feature = "dot")]
+pub use dot::*;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "python")]
+pub use python::*;
+#[cfg(feature = "arc-swap")]
+mod swap;
+#[cfg(feature = "arc-swap")]
+pub use swap::*;
+#[cfg(feature = "small-adjacency")]
+mod compact;
+#[cfg(feature = "small-adjacency")]
+pub use compact::*;
+#[cfg(feature = "dense")]
+mod dense;
+#[cfg(feature = "dense")]
+pub use dense::*;
+#[cfg(feature = "rand")]
+mod sample;
+#[cfg(feature = "rand")]
+pub use sample::*;
+#[cfg(feature = "metadata")]
+mod metadata;
+#[cfg(feature = "metadata")]
+pub use metadata::*;
+#[cfg(feature = "storage")]
+mod storage;
+#[cfg(feature = "storage")]
+pub use storage::*;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
+#[cfg(feature = "insertion-order")]
+mod sequenced;
+#[cfg(feature = "insertion-order")]
+pub use sequenced::*;
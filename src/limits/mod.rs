@@ -0,0 +1,349 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `Limits` are hard limits on the size and shape of a `LimitedDAG`. Each limit is
+/// unset by default. The depth of a dag is the number of edges in its longest
+/// path.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Limits {
+    pub max_vertices: Option<usize>,
+    pub max_out_degree: Option<usize>,
+    pub max_depth: Option<usize>,
+}
+
+/// `LimitedDAG` wraps a `BTreeDAG`, rejecting any mutation which would exceed its
+/// `Limits` with `Error::LimitExceeded`, so a dag built from untrusted input
+/// can not grow beyond them.
+///
+/// While the depth is limited, the length of the longest path to and from
+/// each vertex is maintained as edges are added, so checking an edge takes
+/// logarithmic time, plus time for the vertices whose longest paths grow.
+/// Those lengths never exceed the depth limit, so building a dag of V vertices
+/// and E edges takes O((V + E) · max_depth) time overall. Removing an edge or
+/// a vertex discards the lengths, and the next edge added recomputes them in
+/// O(V + E) time.
+#[derive(Clone, Debug)]
+pub struct LimitedDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    limits: Limits,
+    // The longest paths of the dag, if the depth is limited and they have
+    // been computed since the last removal.
+    depths: Option<Depths<T>>,
+}
+
+/// The predecessors of each vertex, and the number of edges in the longest
+/// path ending at and starting from it. Vertices with no entry have no
+/// predecessors, or no such path.
+#[derive(Clone, Debug)]
+struct Depths<T>
+where
+    T: Ord,
+{
+    predecessors: BTreeMap<T, BTreeSet<T>>,
+    to: BTreeMap<T, usize>,
+    from: BTreeMap<T, usize>,
+}
+
+impl<T> PartialEq for LimitedDAG<T>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.dag == other.dag && self.limits == other.limits
+    }
+}
+
+impl<T> Eq for LimitedDAG<T> where T: Ord {}
+
+impl<T> LimitedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new(limits: Limits) -> Self {
+        LimitedDAG {
+            dag: BTreeDAG::new(),
+            limits,
+            depths: None,
+        }
+    }
+
+    /// Wraps an existing dag, returning an error if it already exceeds the
+    /// limits.
+    pub fn from_dag(dag: BTreeDAG<T>, limits: Limits) -> Result<Self, Error> {
+        let dag = LimitedDAG {
+            dag,
+            limits,
+            depths: None,
+        };
+        if exceeds(dag.dag.vertices.len(), limits.max_vertices)
+            || dag
+                .dag
+                .vertices
+                .values()
+                .any(|adj| exceeds(adj.len(), limits.max_out_degree))
+            || exceeds(dag.depth(), limits.max_depth)
+        {
+            return Err(Error::LimitExceeded);
+        }
+        Ok(dag)
+    }
+
+    /// Returns the limited dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the limits enforced on the dag.
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the dag, discarding the limits.
+    pub fn into_dag(self) -> BTreeDAG<T> {
+        self.dag
+    }
+
+    /// Returns the number of edges in the longest path of the dag.
+    pub fn depth(&self) -> usize {
+        if let Some(depths) = &self.depths {
+            return depths.to.values().copied().max().unwrap_or(0);
+        }
+        let mut lengths: BTreeMap<&T, usize> = BTreeMap::new();
+        self.dag
+            .vertices
+            .keys()
+            .map(|v| longest_path_len(v, |v| self.dag.vertices[v].iter().collect(), &mut lengths))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<T> LimitedDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Adds the vertex x, as `AddVertex::add_vertex`, returning an error instead
+    /// if x does not exist and the dag already has the maximum number of
+    /// vertices.
+    pub fn try_add_vertex(&mut self, x: T) -> Result<Option<BTreeSet<T>>, Error> {
+        if !self.dag.vertices.contains_key(&x) {
+            if exceeds(self.dag.vertices.len() + 1, self.limits.max_vertices) {
+                return Err(Error::LimitExceeded);
+            }
+        } else {
+            // Re-adding a vertex removes its edges.
+            self.depths = None;
+        }
+        Ok(self.dag.add_vertex(x))
+    }
+
+    /// Returns an error if adding the edge (x, y) would exceed the limits.
+    fn check_edge(&mut self, x: &T, y: &T) -> Result<(), Error> {
+        let adj_x = match self.dag.vertices.get(x) {
+            Some(adj_x) if !adj_x.contains(y) && self.dag.vertices.contains_key(y) => adj_x,
+            // The dag's own checks handle missing vertices, and an existing
+            // edge changes nothing.
+            _ => return Ok(()),
+        };
+        if exceeds(adj_x.len() + 1, self.limits.max_out_degree) {
+            return Err(Error::LimitExceeded);
+        }
+        if self.limits.max_depth.is_some() {
+            let dag = &self.dag;
+            let depths = self.depths.get_or_insert_with(|| Depths::new(dag));
+            // The longest new path runs through the edge, from the longest
+            // path ending at x to the longest path starting at y.
+            let to_x = depths.to.get(x).copied().unwrap_or(0);
+            let from_y = depths.from.get(y).copied().unwrap_or(0);
+            if exceeds(to_x + 1 + from_y, self.limits.max_depth) {
+                return Err(Error::LimitExceeded);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Depths<T>
+where
+    T: Ord + Clone,
+{
+    /// Computes the longest paths of the dag.
+    fn new(dag: &BTreeDAG<T>) -> Self {
+        let mut predecessors: BTreeMap<T, BTreeSet<T>> = BTreeMap::new();
+        for (v, adj) in dag.vertices.iter() {
+            for w in adj.iter() {
+                predecessors.entry(w.clone()).or_default().insert(v.clone());
+            }
+        }
+        let mut to: BTreeMap<&T, usize> = BTreeMap::new();
+        let mut from: BTreeMap<&T, usize> = BTreeMap::new();
+        for v in dag.vertices.keys() {
+            longest_path_len(
+                v,
+                |v| {
+                    predecessors
+                        .get(v)
+                        .map_or_else(Vec::new, |p| p.iter().collect())
+                },
+                &mut to,
+            );
+            longest_path_len(v, |v| dag.vertices[v].iter().collect(), &mut from);
+        }
+        let lengths = |lengths: BTreeMap<&T, usize>| {
+            lengths
+                .into_iter()
+                .filter(|(_, length)| *length > 0)
+                .map(|(v, length)| (v.clone(), length))
+                .collect()
+        };
+        Depths {
+            to: lengths(to),
+            from: lengths(from),
+            predecessors,
+        }
+    }
+
+    /// Updates the longest paths for an edge (x, y) added to the dag, by
+    /// lengthening the paths to the descendants of y and from the ancestors
+    /// of x, as far as they grow.
+    fn add_edge(&mut self, dag: &BTreeDAG<T>, x: &T, y: &T) {
+        self.predecessors
+            .entry(y.clone())
+            .or_default()
+            .insert(x.clone());
+        let mut stack: Vec<(&T, usize)> = vec![(y, self.length_to(x) + 1)];
+        while let Some((v, length)) = stack.pop() {
+            if lengthen(&mut self.to, v, length) {
+                stack.extend(dag.vertices[v].iter().map(|w| (w, length + 1)));
+            }
+        }
+        let mut stack: Vec<(&T, usize)> = vec![(x, self.length_from(y) + 1)];
+        while let Some((v, length)) = stack.pop() {
+            if lengthen(&mut self.from, v, length) {
+                if let Some(predecessors) = self.predecessors.get(v) {
+                    stack.extend(predecessors.iter().map(|u| (u, length + 1)));
+                }
+            }
+        }
+    }
+
+    fn length_to(&self, v: &T) -> usize {
+        self.to.get(v).copied().unwrap_or(0)
+    }
+
+    fn length_from(&self, v: &T) -> usize {
+        self.from.get(v).copied().unwrap_or(0)
+    }
+}
+
+/// Sets the length of v to the given length if it is longer, returning true
+/// if it is.
+fn lengthen<T>(lengths: &mut BTreeMap<T, usize>, v: &T, length: usize) -> bool
+where
+    T: Ord + Clone,
+{
+    match lengths.get_mut(v) {
+        Some(current) if *current >= length => false,
+        Some(current) => {
+            *current = length;
+            true
+        }
+        None => {
+            lengths.insert(v.clone(), length);
+            true
+        }
+    }
+}
+
+impl<T> AddEdge<T> for LimitedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.check_edge(&x, &y)?;
+        let previous = self.dag.add_edge(x.clone(), y.clone())?;
+        if let Some(depths) = &mut self.depths {
+            depths.add_edge(&self.dag, &x, &y);
+        }
+        Ok(previous)
+    }
+}
+
+impl<T> RemoveEdge<T> for LimitedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.depths = None;
+        self.dag.remove_edge(x, y)
+    }
+}
+
+impl<T> RemoveVertex<T> for LimitedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.depths = None;
+        self.dag.remove_vertex(x)
+    }
+}
+
+impl<T> Prune<T> for LimitedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.depths = None;
+        self.dag.prune(x)
+    }
+}
+
+/// Returns true if the value exceeds the limit, if there is one.
+fn exceeds(value: usize, limit: Option<usize>) -> bool {
+    limit.is_some_and(|limit| value > limit)
+}
+
+/// Returns the number of edges in the longest path from start, following the
+/// vertices returned by next, memoizing the lengths of every vertex visited.
+/// The search is iterative, so deep dags can not overflow the stack.
+fn longest_path_len<'a, T>(
+    start: &'a T,
+    next: impl Fn(&'a T) -> Vec<&'a T>,
+    lengths: &mut BTreeMap<&'a T, usize>,
+) -> usize
+where
+    T: Ord,
+{
+    let mut stack: Vec<(&T, bool)> = vec![(start, false)];
+    while let Some((v, expanded)) = stack.pop() {
+        if expanded {
+            // Every vertex following v was pushed after it, so its length
+            // is already known.
+            let length = next(v).iter().map(|w| lengths[w] + 1).max().unwrap_or(0);
+            lengths.insert(v, length);
+        } else if !lengths.contains_key(v) {
+            stack.push((v, true));
+            stack.extend(
+                next(v)
+                    .into_iter()
+                    .filter(|w| !lengths.contains_key(w))
+                    .map(|w| (w, false)),
+            );
+        }
+    }
+    lengths[start]
+}
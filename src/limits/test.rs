@@ -0,0 +1,156 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::limits::*;
+    use crate::Error;
+
+    #[test]
+    fn max_vertices_and_out_degree() -> Result<(), Error> {
+        let limits = Limits {
+            max_vertices: Some(3),
+            max_out_degree: Some(1),
+            ..Limits::default()
+        };
+        let mut dag: LimitedDAG<usize> = LimitedDAG::new(limits);
+
+        // Add three nodes; a fourth exceeds the limit, but re-adding does not.
+        dag.try_add_vertex(0)?;
+        dag.try_add_vertex(1)?;
+        dag.try_add_vertex(2)?;
+        assert_eq!(dag.try_add_vertex(3), Err(Error::LimitExceeded));
+        assert_eq!(dag.try_add_vertex(2), Ok(Some(BTreeSet::new())));
+
+        // Add an edge (0, 1); a second edge from 0 exceeds the limit.
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 1)?;
+        assert_eq!(dag.add_edge(0, 2), Err(Error::LimitExceeded));
+        dag.remove_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_depth() -> Result<(), Error> {
+        let limits = Limits {
+            max_depth: Some(2),
+            ..Limits::default()
+        };
+        let mut dag: LimitedDAG<usize> = LimitedDAG::new(limits);
+        for x in 0..4 {
+            dag.try_add_vertex(x)?;
+        }
+
+        // Add an edge (0, 1) and (2, 3), then joining them exceeds the depth.
+        dag.add_edge(0, 1)?;
+        dag.add_edge(2, 3)?;
+        assert_eq!(dag.add_edge(1, 2), Err(Error::LimitExceeded));
+        dag.add_edge(0, 2)?;
+        assert_eq!(dag.depth(), 2);
+
+        // Existing dags are validated against the limits.
+        let inner: BTreeDAG<usize> = dag.into_dag();
+        assert!(LimitedDAG::from_dag(inner.clone(), limits).is_ok());
+        let limits = Limits {
+            max_depth: Some(1),
+            ..Limits::default()
+        };
+        assert_eq!(
+            LimitedDAG::from_dag(inner, limits),
+            Err(Error::LimitExceeded)
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn max_depth_maintained() -> Result<(), Error> {
+        let limits = Limits {
+            max_depth: Some(4),
+            ..Limits::default()
+        };
+        let mut dag: LimitedDAG<usize> = LimitedDAG::new(limits);
+        for x in 0..12 {
+            dag.try_add_vertex(x)?;
+        }
+
+        // Apply a pseudo-random sequence of mutations, checking every
+        // accepted or rejected edge against the depth computed from scratch.
+        let mut seed: u64 = 7;
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let x = (seed >> 33) as usize % 12;
+            let y = (seed >> 45) as usize % 12;
+            match (seed >> 60) % 8 {
+                0 => {
+                    dag.remove_edge(x, y)?;
+                }
+                1 if seed.is_multiple_of(13) => {
+                    dag.try_add_vertex(x)?;
+                }
+                _ => {
+                    let mut inner: BTreeDAG<usize> = dag.dag().clone();
+                    let expected = match inner.add_edge(x, y) {
+                        Ok(_) if LimitedDAG::from_dag(inner, limits).is_err() => {
+                            Err(Error::LimitExceeded)
+                        }
+                        result => result,
+                    };
+                    match expected {
+                        // The limits are checked before the cycle, and the
+                        // depth through an edge closing a cycle may
+                        // exceed them.
+                        Err(Error::EdgeExists) => assert!(dag.add_edge(x, y).is_err()),
+                        expected => assert_eq!(dag.add_edge(x, y), expected),
+                    }
+                }
+            }
+            assert!(LimitedDAG::from_dag(dag.dag().clone(), limits).is_ok());
+            assert_eq!(
+                dag.depth(),
+                LimitedDAG::from_dag(dag.dag().clone(), limits)?.depth()
+            );
+        }
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    // The invariant checks make every mutation linear, which this would
+    // exercise 50000 times.
+    #[cfg(not(feature = "debug-invariants"))]
+    fn max_depth_large_input() -> Result<(), Error> {
+        // Add 100 layers of 100 nodes, and an edge from each node to five
+        // nodes of the next layer, which is fast as the longest paths are
+        // maintained rather than recomputed for every edge.
+        let limits = Limits {
+            max_depth: Some(99),
+            ..Limits::default()
+        };
+        let mut dag: LimitedDAG<usize> = LimitedDAG::new(limits);
+        for x in 0..10_000 {
+            dag.try_add_vertex(x)?;
+        }
+        for x in 0..9_900 {
+            let next = (x / 100 + 1) * 100;
+            for i in 0..5 {
+                dag.add_edge(x, next + (x + i * 17) % 100)?;
+            }
+        }
+        assert_eq!(dag.depth(), 99);
+
+        // An edge from the last layer back to the first would double the
+        // depth, and a removal does not lose track of it.
+        assert_eq!(dag.add_edge(9_950, 0), Err(Error::LimitExceeded));
+        dag.remove_edge(0, 100 + 17)?;
+        assert_eq!(dag.add_edge(9_950, 0), Err(Error::LimitExceeded));
+        assert_eq!(dag.depth(), 99);
+
+        // Test passed.
+        Ok(())
+    }
+}
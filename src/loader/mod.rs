@@ -0,0 +1,114 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::dag::{is_acyclic, AddEdge, BTreeDAG};
+use crate::{ContextError, Error, ErrorContext};
+
+/// `StreamingLoader` builds a `BTreeDAG` from a stream of edges. Edges are
+/// buffered, creating their vertices as needed, and the buffer is cycle
+/// checked as a whole once it holds `batch_size` edges, so the cost of the
+/// check is amortized over the batch rather than paid for every edge.
+///
+/// If a batch would form a cycle, its edges are added one at a time with
+/// cycle checks up to the first edge which forms a cycle. That edge is
+/// returned as the error and dropped; the edges pushed after it remain
+/// buffered, so loading can continue.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct StreamingLoader<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    pending: Vec<(T, T)>,
+    batch_size: usize,
+}
+
+impl<T> StreamingLoader<T>
+where
+    T: Ord + Clone,
+{
+    /// Returns a loader which checks the buffered edges for cycles every
+    /// batch_size edges. A batch size of zero is treated as one.
+    pub fn new(batch_size: usize) -> Self {
+        StreamingLoader {
+            dag: BTreeDAG::new(),
+            pending: Vec::new(),
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Returns the number of buffered edges which have not been checked.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Adds the vertex x, if it does not already exist.
+    pub fn push_vertex(&mut self, x: T) {
+        self.dag
+            .vertices
+            .entry(x)
+            .or_insert_with(|| Arc::new(BTreeSet::new()));
+    }
+
+    /// Buffers the edge (x, y), adding x and y if they do not already exist,
+    /// and checks the buffer once it is full. An edge from a vertex to itself
+    /// is rejected immediately.
+    pub fn push(&mut self, x: T, y: T) -> Result<(), ContextError<T>> {
+        if x == y {
+            return Err(ContextError {
+                error: Error::EdgeExists,
+                context: ErrorContext::Edge(x, y),
+            });
+        }
+        self.push_vertex(x.clone());
+        self.push_vertex(y.clone());
+        self.pending.push((x, y));
+        if self.pending.len() >= self.batch_size {
+            return self.flush();
+        }
+        Ok(())
+    }
+
+    /// Checks the buffered edges for cycles, adding them to the dag.
+    pub fn flush(&mut self) -> Result<(), ContextError<T>> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut vertices: BTreeMap<T, Arc<BTreeSet<T>>> = self.dag.vertices.clone();
+        for (x, y) in self.pending.iter() {
+            // Every buffered vertex was added when it was pushed, so we can
+            // safely unwrap.
+            Arc::make_mut(vertices.get_mut(x).unwrap()).insert(y.clone());
+        }
+        if is_acyclic(&vertices) {
+            self.dag.vertices = vertices;
+            self.pending.clear();
+            return Ok(());
+        }
+        // Find the first edge of the batch which forms a cycle.
+        let mut pending = core::mem::take(&mut self.pending).into_iter();
+        while let Some((x, y)) = pending.next() {
+            if let Err(error) = self.dag.add_edge(x.clone(), y.clone()) {
+                self.pending = pending.collect();
+                return Err(ContextError {
+                    error,
+                    context: ErrorContext::Edge(x, y),
+                });
+            }
+        }
+        // The batch as a whole formed a cycle, so one of its edges must have.
+        unreachable!()
+    }
+
+    /// Checks any buffered edges, returning the loaded dag. If an edge forms
+    /// a cycle, it is returned as the error and the dag is discarded.
+    pub fn finish(mut self) -> Result<BTreeDAG<T>, ContextError<T>> {
+        while !self.pending.is_empty() {
+            self.flush()?;
+        }
+        Ok(self.dag)
+    }
+}
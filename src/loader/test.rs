@@ -0,0 +1,63 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::loader::*;
+    use crate::{ContextError, Error, ErrorContext};
+
+    #[test]
+    fn streaming_loader() -> Result<(), ContextError<usize>> {
+        // Push a chain of five edges in batches of two.
+        let mut loader: StreamingLoader<usize> = StreamingLoader::new(2);
+        loader.push(0, 1)?;
+        assert_eq!(loader.pending_len(), 1);
+        loader.push(1, 2)?;
+        assert_eq!(loader.pending_len(), 0);
+        loader.push(2, 3)?;
+        loader.push_vertex(6);
+
+        // The edge closing a cycle is dropped; later edges remain pending.
+        assert_eq!(
+            loader.push(3, 0),
+            Err(ContextError {
+                error: Error::EdgeExists,
+                context: ErrorContext::Edge(3, 0),
+            })
+        );
+        assert_eq!(loader.pending_len(), 0);
+        loader.push(4, 5)?;
+        assert_eq!(
+            loader.push(5, 5).map_err(|e| e.error),
+            Err(Error::EdgeExists)
+        );
+
+        let dag: BTreeDAG<usize> = loader.finish()?;
+        assert_eq!(dag.vertices().len(), 7);
+        assert!(dag
+            .adjacent(2, 3)
+            .map_err(|e| dag.explain_edge(e, &2, &3))?);
+        assert!(dag
+            .adjacent(4, 5)
+            .map_err(|e| dag.explain_edge(e, &4, &5))?);
+        assert!(!dag
+            .adjacent(3, 0)
+            .map_err(|e| dag.explain_edge(e, &3, &0))?);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn streaming_loader_keeps_later_edges() {
+        // The cycle is closed by the first edge of the batch.
+        let mut loader: StreamingLoader<usize> = StreamingLoader::new(3);
+        loader.push(0, 1).unwrap();
+        loader.push(1, 0).unwrap();
+        assert!(loader.push(2, 3).is_err());
+        assert_eq!(loader.pending_len(), 1);
+        let dag: BTreeDAG<usize> = loader.finish().unwrap();
+        assert!(dag.adjacent(2, 3).unwrap());
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,223 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `Clock` supplies the time at which a vertex or edge of an `AnnotatedDAG` is
+/// added. Any function returning an ordered value is a clock, so `no_std`
+/// applications can supply their own time source.
+pub trait Clock {
+    type Instant: Ord + Clone;
+    fn now(&self) -> Self::Instant;
+}
+
+impl<F, I> Clock for F
+where
+    F: Fn() -> I,
+    I: Ord + Clone,
+{
+    type Instant = I;
+    fn now(&self) -> I {
+        self()
+    }
+}
+
+/// `SystemClock` is a `Clock` reading the system time.
+#[cfg(feature = "std")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    type Instant = std::time::SystemTime;
+    fn now(&self) -> std::time::SystemTime {
+        std::time::SystemTime::now()
+    }
+}
+
+/// `Metadata` records when a vertex or edge was added, and by which source.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Metadata<I, S> {
+    pub added_at: I,
+    pub source: S,
+}
+
+/// `Expiry` is what `AnnotatedDAG::expire_older_than` removes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Expiry {
+    /// Only edges older than the cutoff.
+    Edges,
+    /// Edges and vertices older than the cutoff, along with every edge to or
+    /// from the expired vertices.
+    Vertices,
+    /// Edges and vertices older than the cutoff, along with every descendant
+    /// of the expired vertices, as `Prune`.
+    Prune,
+}
+
+/// `AnnotatedDAG` wraps a `BTreeDAG`, recording the `Metadata` of every vertex and
+/// edge. Each is added with a source tag, and timestamped by the clock. Adding
+/// an edge which already exists keeps its original metadata, while adding a
+/// vertex which already exists replaces it, as the vertex's edges are removed.
+pub struct AnnotatedDAG<T, S, C>
+where
+    T: Ord,
+    C: Clock,
+{
+    dag: BTreeDAG<T>,
+    clock: C,
+    vertices: BTreeMap<T, Metadata<C::Instant, S>>,
+    edges: BTreeMap<(T, T), Metadata<C::Instant, S>>,
+}
+
+impl<T, S, C> AnnotatedDAG<T, S, C>
+where
+    T: Ord,
+    C: Clock,
+{
+    pub fn new(clock: C) -> Self {
+        AnnotatedDAG {
+            dag: BTreeDAG::new(),
+            clock,
+            vertices: BTreeMap::new(),
+            edges: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the annotated dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the metadata of the vertex x, if it exists.
+    pub fn vertex_metadata(&self, x: &T) -> Option<&Metadata<C::Instant, S>> {
+        self.vertices.get(x)
+    }
+
+    /// Returns the metadata of the edge (x, y), if it exists.
+    pub fn edge_metadata(&self, x: &T, y: &T) -> Option<&Metadata<C::Instant, S>>
+    where
+        T: Clone,
+    {
+        self.edges.get(&(x.clone(), y.clone()))
+    }
+
+    /// Removes the metadata of every vertex and edge which no longer exists.
+    fn retain_existing(&mut self) {
+        let dag = &self.dag;
+        self.vertices.retain(|x, _| dag.vertices.contains_key(x));
+        self.edges
+            .retain(|(x, y), _| dag.vertices.get(x).is_some_and(|adj_x| adj_x.contains(y)));
+    }
+}
+
+impl<T, S, C> AnnotatedDAG<T, S, C>
+where
+    T: Ord + Clone,
+    C: Clock,
+{
+    /// Adds the vertex x from the given source, as `AddVertex::add_vertex`.
+    pub fn add_vertex(&mut self, x: T, source: S) -> Option<BTreeSet<T>> {
+        let added_at = self.clock.now();
+        let adj_x = self.dag.add_vertex(x.clone());
+        self.vertices.insert(x, Metadata { added_at, source });
+        if adj_x.as_ref().is_some_and(|adj_x| !adj_x.is_empty()) {
+            self.retain_existing();
+        }
+        adj_x
+    }
+
+    /// Removes everything added before the cutoff, as given by expiry,
+    /// returning the removed vertices. Removing vertices and edges can not
+    /// introduce a cycle, so the dag stays consistent.
+    pub fn expire_older_than(&mut self, cutoff: &C::Instant, expiry: Expiry) -> BTreeSet<T> {
+        let dag = &mut self.dag;
+        self.edges.retain(|(x, y), metadata| {
+            if metadata.added_at >= *cutoff {
+                return true;
+            }
+            // The edge has metadata, so it exists and can be removed.
+            dag.remove_edge(x.clone(), y.clone()).unwrap();
+            false
+        });
+        let mut expired: BTreeSet<T> = match expiry {
+            Expiry::Edges => return BTreeSet::new(),
+            Expiry::Vertices | Expiry::Prune => self
+                .vertices
+                .iter()
+                .filter(|(_, metadata)| metadata.added_at < *cutoff)
+                .map(|(x, _)| x.clone())
+                .collect(),
+        };
+        if expiry == Expiry::Prune {
+            let descendants: BTreeSet<T> = expired
+                .iter()
+                .flat_map(|x| self.dag.descendants_of(x))
+                .cloned()
+                .collect();
+            expired.extend(descendants);
+        }
+        for x in expired.iter() {
+            // Every expired vertex has metadata, so it exists and can be
+            // removed.
+            self.dag.remove_vertex(x.clone()).unwrap();
+        }
+        self.retain_existing();
+        expired
+    }
+
+    /// Adds the edge (x, y) from the given source, as `AddEdge::add_edge`.
+    pub fn add_edge(&mut self, x: T, y: T, source: S) -> Result<BTreeSet<T>, Error> {
+        let added_at = self.clock.now();
+        let adj_x = self.dag.add_edge(x.clone(), y.clone())?;
+        self.edges
+            .entry((x, y))
+            .or_insert(Metadata { added_at, source });
+        Ok(adj_x)
+    }
+}
+
+impl<T, S, C> RemoveEdge<T> for AnnotatedDAG<T, S, C>
+where
+    T: Ord + Clone,
+    C: Clock,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_edge(x.clone(), y.clone())?;
+        self.edges.remove(&(x, y));
+        Ok(adj_x)
+    }
+}
+
+impl<T, S, C> RemoveVertex<T> for AnnotatedDAG<T, S, C>
+where
+    T: Ord + Clone,
+    C: Clock,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_vertex(x)?;
+        self.retain_existing();
+        Ok(adj_x)
+    }
+}
+
+impl<T, S, C> Prune<T> for AnnotatedDAG<T, S, C>
+where
+    T: Ord + Clone,
+    C: Clock,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x)?;
+        self.retain_existing();
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::metadata::*;
+    use crate::Error;
+    use core::cell::Cell;
+
+    #[test]
+    fn edge_metadata() -> Result<(), Error> {
+        // A clock which ticks on every reading.
+        let ticks: Cell<u64> = Cell::new(0);
+        let clock = || {
+            ticks.set(ticks.get() + 1);
+            ticks.get()
+        };
+        let mut dag: AnnotatedDAG<usize, &str, _> = AnnotatedDAG::new(clock);
+
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        dag.add_vertex(0, "manifest");
+        dag.add_vertex(1, "manifest");
+        dag.add_vertex(2, "lockfile");
+        dag.add_edge(0, 1, "manifest")?;
+        dag.add_edge(1, 2, "lockfile")?;
+        assert_eq!(
+            dag.vertex_metadata(&2),
+            Some(&Metadata {
+                added_at: 3,
+                source: "lockfile"
+            })
+        );
+        assert_eq!(dag.edge_metadata(&1, &2).map(|m| m.added_at), Some(5));
+
+        // Re-adding an edge keeps its metadata, and failed edges have none.
+        dag.add_edge(0, 1, "lockfile")?;
+        assert_eq!(
+            dag.edge_metadata(&0, &1).map(|m| m.source),
+            Some("manifest")
+        );
+        assert!(dag.add_edge(2, 0, "lockfile").is_err());
+        assert_eq!(dag.edge_metadata(&2, &0), None);
+
+        // Removed vertices and edges lose their metadata.
+        dag.remove_vertex(1)?;
+        assert_eq!(dag.vertex_metadata(&1), None);
+        assert_eq!(dag.edge_metadata(&0, &1), None);
+        assert_eq!(dag.edge_metadata(&1, &2), None);
+        assert!(dag.vertex_metadata(&2).is_some());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn expire_older_than() -> Result<(), Error> {
+        // A clock which ticks on every reading.
+        let ticks: Cell<u64> = Cell::new(0);
+        let clock = || {
+            ticks.set(ticks.get() + 1);
+            ticks.get()
+        };
+        // Add four nodes at times 1 to 4, and an edge (0, 1), (2, 3) and
+        // (1, 2) at times 5 to 7.
+        let mut dag: AnnotatedDAG<usize, (), _> = AnnotatedDAG::new(clock);
+        for x in 0..4 {
+            dag.add_vertex(x, ());
+        }
+        dag.add_edge(0, 1, ())?;
+        dag.add_edge(2, 3, ())?;
+        dag.add_edge(1, 2, ())?;
+
+        // Expiring edges keeps every node.
+        assert!(dag.expire_older_than(&7, Expiry::Edges).is_empty());
+        assert_eq!(dag.dag().vertices().len(), 4);
+        assert_eq!(dag.edge_metadata(&0, &1), None);
+        assert!(!dag.dag().adjacent(2, 3)?);
+        assert_eq!(dag.edge_metadata(&1, &2).map(|m| m.added_at), Some(7));
+
+        // Expiring vertices removes their edges, and pruning removes their
+        // descendants too.
+        assert_eq!(
+            dag.expire_older_than(&2, Expiry::Vertices),
+            BTreeSet::from([0])
+        );
+        assert_eq!(
+            dag.expire_older_than(&3, Expiry::Prune),
+            BTreeSet::from([1, 2])
+        );
+        assert_eq!(dag.dag().vertices(), BTreeSet::from([&3]));
+        assert_eq!(dag.vertex_metadata(&1), None);
+        assert_eq!(dag.edge_metadata(&1, &2), None);
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::{index_adjacency, BTreeDAG};
+
+/// `Centrality` measures how load-bearing each vertex of the dag is.
+///
+/// Betweenness centrality sums, over every ordered pair of other vertices
+/// (s, t) where t is reachable from s, the fraction of shortest paths from s
+/// to t which pass through the vertex, computed with Brandes' algorithm in
+/// O(VE). The approximate variant only sums over the pairs whose source is one
+/// of the given pivots, scaling by the number of vertices over the number of
+/// pivots. Reach centrality is the fraction of the other vertices reachable
+/// from the vertex.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, Centrality};
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let betweenness = dag.betweenness_centrality();
+/// assert_eq!(betweenness[&String::from("waypoint")], 1.0);
+/// assert_eq!(betweenness[&String::from("origin")], 0.0);
+/// assert_eq!(dag.reach_centrality()[&String::from("origin")], 1.0);
+/// ```
+pub trait Centrality<T> {
+    fn betweenness_centrality(&self) -> BTreeMap<&T, f64>;
+    fn approximate_betweenness_centrality(&self, pivots: &BTreeSet<T>) -> BTreeMap<&T, f64>;
+    fn reach_centrality(&self) -> BTreeMap<&T, f64>;
+}
+
+impl<T> Centrality<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn betweenness_centrality(&self) -> BTreeMap<&T, f64> {
+        let sources: Vec<usize> = (0..self.vertices.len()).collect();
+        let centrality: Vec<f64> = brandes(&index_adjacency(self), &sources);
+        self.vertices.keys().zip(centrality).collect()
+    }
+
+    fn approximate_betweenness_centrality(&self, pivots: &BTreeSet<T>) -> BTreeMap<&T, f64> {
+        let sources: Vec<usize> = self
+            .vertices
+            .keys()
+            .enumerate()
+            .filter(|(_, v)| pivots.contains(v))
+            .map(|(i, _)| i)
+            .collect();
+        let mut centrality: Vec<f64> = brandes(&index_adjacency(self), &sources);
+        if !sources.is_empty() {
+            let scale = self.vertices.len() as f64 / sources.len() as f64;
+            centrality.iter_mut().for_each(|c| *c *= scale);
+        }
+        self.vertices.keys().zip(centrality).collect()
+    }
+
+    fn reach_centrality(&self) -> BTreeMap<&T, f64> {
+        let others = self.vertices.len().saturating_sub(1).max(1) as f64;
+        self.vertices
+            .keys()
+            .map(|v| (v, self.descendants_of(v).len() as f64 / others))
+            .collect()
+    }
+}
+
+/// Brandes' algorithm: accumulates, for each vertex, the fraction of the
+/// shortest paths from each source which pass through it.
+fn brandes(adjacency: &[Vec<usize>], sources: &[usize]) -> Vec<f64> {
+    let n = adjacency.len();
+    let mut centrality: Vec<f64> = vec![0.0; n];
+    for &s in sources {
+        // The number of shortest paths from s to each vertex, the distance
+        // from s, and the predecessors on a shortest path.
+        let mut paths: Vec<f64> = vec![0.0; n];
+        let mut distances: Vec<Option<usize>> = vec![None; n];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut order: Vec<usize> = Vec::new();
+        let mut queue: VecDeque<usize> = VecDeque::from([s]);
+        paths[s] = 1.0;
+        distances[s] = Some(0);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            // Every queued vertex has a distance, so we can safely unwrap.
+            let d = distances[v].unwrap() + 1;
+            for &w in adjacency[v].iter() {
+                if distances[w].is_none() {
+                    distances[w] = Some(d);
+                    queue.push_back(w);
+                }
+                if distances[w] == Some(d) {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        let mut dependencies: Vec<f64> = vec![0.0; n];
+        for &w in order.iter().rev() {
+            for &v in predecessors[w].iter() {
+                dependencies[v] += paths[v] / paths[w] * (1.0 + dependencies[w]);
+            }
+            if w != s {
+                centrality[w] += dependencies[w];
+            }
+        }
+    }
+    centrality
+}
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::metrics::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn betweenness_centrality() -> Result<(), Error> {
+        // Add five nodes, a diamond (0, 1), (0, 2), (1, 3), (2, 3), and an
+        // edge (3, 4).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..5 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(0, 2)?;
+        dag.add_edge(1, 3)?;
+        dag.add_edge(2, 3)?;
+        dag.add_edge(3, 4)?;
+
+        // 3 lies on every path from 0, 1 and 2 to 4, and 1 and 2 each on
+        // half of the shortest paths from 0 to 3 and 4.
+        let centrality = dag.betweenness_centrality();
+        assert_eq!(centrality[&3], 3.0);
+        assert_eq!(centrality[&1], 1.0);
+        assert_eq!(centrality[&2], 1.0);
+        assert_eq!(centrality[&0], 0.0);
+        assert_eq!(centrality[&4], 0.0);
+
+        // With every vertex as a pivot, the approximation is exact.
+        let pivots: BTreeSet<usize> = (0..5).collect();
+        assert_eq!(dag.approximate_betweenness_centrality(&pivots), centrality);
+        let approximate = dag.approximate_betweenness_centrality(&BTreeSet::from([0]));
+        assert_eq!(approximate[&3], 5.0);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn reach_centrality() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..3 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+
+        let centrality = dag.reach_centrality();
+        assert_eq!(centrality[&0], 0.5);
+        assert_eq!(centrality[&1], 0.0);
+
+        // Test passed.
+        Ok(())
+    }
+}
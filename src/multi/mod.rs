@@ -0,0 +1,227 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::default::Default;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dag::{AddVertex, Adjacent, BTreeDAG, OutDegree, RemoveVertex, Vertices};
+use crate::Error;
+
+/// `BTreeMultiDAG` is a directed acyclic graph which allows parallel edges between
+/// the same two vertices, distinguished by their labels. Each vertex maps its
+/// adjacent vertices to the labels of the edges to them. Acyclicity is enforced
+/// at the vertex level: an edge (x, y) of any label may not be added if x is
+/// reachable from y.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    vertices: BTreeMap<T, BTreeMap<T, BTreeSet<L>>>,
+}
+
+impl<T, L> BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    pub fn new() -> Self {
+        let vertices: BTreeMap<T, BTreeMap<T, BTreeSet<L>>> = BTreeMap::new();
+        BTreeMultiDAG { vertices }
+    }
+
+    /// Returns the adjacent vertices of the vertex x, with the labels of the
+    /// edges to each, if x exists.
+    pub fn connections(&self, x: &T) -> Option<&BTreeMap<T, BTreeSet<L>>> {
+        self.vertices.get(x)
+    }
+
+    /// Returns the labels of the edges (x, y), if x exists.
+    pub fn labels(&self, x: &T, y: &T) -> Option<&BTreeSet<L>> {
+        self.vertices.get(x).and_then(|adj_x| adj_x.get(y))
+    }
+
+    /// Returns the number of labelled edges in the dag.
+    pub fn edge_count(&self) -> usize {
+        self.vertices
+            .values()
+            .flat_map(|adj| adj.values())
+            .map(BTreeSet::len)
+            .sum()
+    }
+
+    /// Removes the edge (x, y) with the given label, returning true if it
+    /// existed. An error is returned if either vertex does not exist.
+    pub fn remove_edge(&mut self, x: &T, y: &T, label: &L) -> Result<bool, Error> {
+        if !self.vertices.contains_key(y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let adj_x = self.vertices.get_mut(x).ok_or(Error::VertexDoesNotExist)?;
+        match adj_x.get_mut(y) {
+            Some(labels) => {
+                let removed = labels.remove(label);
+                // Vertices are only adjacent while an edge to them remains.
+                if labels.is_empty() {
+                    adj_x.remove(y);
+                }
+                Ok(removed)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Removes every edge (x, y), returning their labels. An error is returned
+    /// if either vertex does not exist.
+    pub fn remove_edges(&mut self, x: &T, y: &T) -> Result<BTreeSet<L>, Error> {
+        if !self.vertices.contains_key(y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let adj_x = self.vertices.get_mut(x).ok_or(Error::VertexDoesNotExist)?;
+        Ok(adj_x.remove(y).unwrap_or_default())
+    }
+
+    /// Returns an error if x is reachable from y, as an edge (x, y) would
+    /// then introduce a cycle.
+    fn cyclic_relationship_exists(&self, x: &T, y: &T) -> Result<(), Error> {
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = Vec::new();
+        stack.push(
+            self.vertices
+                .get_key_value(y)
+                .ok_or(Error::VertexDoesNotExist)?
+                .0,
+        );
+        while let Some(v) = stack.pop() {
+            if v == x {
+                return Err(Error::EdgeExists);
+            }
+            if visited.insert(v) {
+                stack.extend(self.vertices[v].keys());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, L> BTreeMultiDAG<T, L>
+where
+    T: Ord + Clone,
+    L: Ord,
+{
+    /// Adds an edge (x, y) with the given label, returning true if no edge
+    /// (x, y) had the label yet. An error is returned if either vertex does not
+    /// exist, or if the edge would introduce a cycle.
+    pub fn add_edge(&mut self, x: T, y: T, label: L) -> Result<bool, Error> {
+        if !self.vertices.contains_key(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        // Parallel edges can not introduce a cycle, so only a new adjacent
+        // vertex needs checking.
+        if !self.vertices[&x].contains_key(&y) {
+            self.cyclic_relationship_exists(&x, &y)?;
+        }
+        // We have already verified x is in vertices, so we can safely
+        // unwrap.
+        let adj_x = self.vertices.get_mut(&x).unwrap();
+        Ok(adj_x.entry(y).or_default().insert(label))
+    }
+}
+
+impl<T, L> Default for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, L> Vertices<T> for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    fn vertices(&self) -> BTreeSet<&T> {
+        self.vertices.keys().collect()
+    }
+}
+
+impl<T, L> AddVertex<T> for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.vertices
+            .insert(x, BTreeMap::new())
+            .map(|adj_x| adj_x.into_keys().collect())
+    }
+}
+
+impl<T, L> RemoveVertex<T> for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.vertices.remove(&x).ok_or(Error::VertexDoesNotExist)?;
+        // Remove any dangling edges to x.
+        for adj in self.vertices.values_mut() {
+            adj.remove(&x);
+        }
+        Ok(adj_x.into_keys().collect())
+    }
+}
+
+impl<T, L> Adjacent<T> for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        if self.vertices.contains_key(&y) {
+            if let Some(adj_x) = self.vertices.get(&x) {
+                return Ok(adj_x.contains_key(&y));
+            }
+        }
+        Err(Error::VertexDoesNotExist)
+    }
+}
+
+/// The out-degree of a vertex counts its adjacent vertices, not its labelled
+/// edges.
+impl<T, L> OutDegree<T> for BTreeMultiDAG<T, L>
+where
+    T: Ord,
+    L: Ord,
+{
+    fn out_degree(&self, x: &T) -> Option<usize> {
+        self.vertices.get(x).map(BTreeMap::len)
+    }
+}
+
+/// Converting to a `BTreeDAG` merges the parallel edges between each pair of
+/// vertices into one, discarding the labels.
+impl<T, L> From<BTreeMultiDAG<T, L>> for BTreeDAG<T>
+where
+    T: Ord,
+    L: Ord,
+{
+    fn from(dag: BTreeMultiDAG<T, L>) -> Self {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = dag
+            .vertices
+            .into_iter()
+            .map(|(x, adj_x)| (x, Arc::new(adj_x.into_keys().collect())))
+            .collect();
+        BTreeDAG::from_vertices(vertices)
+    }
+}
@@ -0,0 +1,62 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::multi::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn parallel_edges() -> Result<(), Error> {
+        // Add three nodes, and two edges (0, 1) labelled "build" and "test",
+        // and an edge (1, 2) labelled "build".
+        let mut dag: BTreeMultiDAG<usize, &str> = BTreeMultiDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        assert!(dag.add_edge(0, 1, "build")?);
+        assert!(dag.add_edge(0, 1, "test")?);
+        assert!(!dag.add_edge(0, 1, "test")?);
+        assert!(dag.add_edge(1, 2, "build")?);
+        assert_eq!(dag.labels(&0, &1), Some(&BTreeSet::from(["build", "test"])));
+        assert_eq!(dag.edge_count(), 3);
+        assert_eq!(dag.out_degree(&0), Some(1));
+        assert!(dag.adjacent(0, 1)?);
+
+        // Acyclicity holds regardless of the label.
+        assert_eq!(dag.add_edge(2, 0, "test"), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(3, 0, "test"), Err(Error::VertexDoesNotExist));
+
+        // Removing one label leaves the other edge.
+        assert!(dag.remove_edge(&0, &1, &"build")?);
+        assert!(!dag.remove_edge(&0, &1, &"build")?);
+        assert!(dag.adjacent(0, 1)?);
+        assert!(dag.remove_edge(&0, &1, &"test")?);
+        assert!(!dag.adjacent(0, 1)?);
+        assert_eq!(dag.labels(&0, &1), None);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn into_btree_dag() -> Result<(), Error> {
+        // Add three nodes, and two edges (0, 1) and an edge (0, 2).
+        let mut dag: BTreeMultiDAG<usize, u8> = BTreeMultiDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1, 0)?;
+        dag.add_edge(0, 1, 1)?;
+        dag.add_edge(0, 2, 0)?;
+        assert_eq!(dag.remove_edges(&0, &2)?, BTreeSet::from([0]));
+        dag.add_edge(0, 2, 1)?;
+
+        assert_eq!(dag.remove_vertex(2)?, BTreeSet::new());
+        let dag: BTreeDAG<usize> = BTreeDAG::from(dag);
+        assert_eq!(dag.connections(0), Some(&BTreeSet::from([1])));
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::dag::{AddEdge, AddVertex, Adjacent, BTreeDAG, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `OverlayDag` layers a mutable delta over an immutable base `BTreeDAG`. The
+/// delta records the adjacency list of every vertex added, changed or removed
+/// since the overlay was constructed, so mutations and cycle checks see the
+/// combined dag while the base is left untouched. The delta can be folded
+/// into a copy of the base with `commit`, or dropped to discard it.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct OverlayDag<'a, T>
+where
+    T: Ord,
+{
+    base: &'a BTreeDAG<T>,
+    // The adjacency list of each changed vertex, or None if it was removed.
+    delta: BTreeMap<T, Option<BTreeSet<T>>>,
+}
+
+impl<'a, T> OverlayDag<'a, T>
+where
+    T: Ord,
+{
+    pub fn new(base: &'a BTreeDAG<T>) -> Self {
+        OverlayDag {
+            base,
+            delta: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the base dag.
+    pub fn base(&self) -> &'a BTreeDAG<T> {
+        self.base
+    }
+
+    /// Returns the number of vertices changed by the delta.
+    pub fn delta_len(&self) -> usize {
+        self.delta.len()
+    }
+
+    /// Returns true if the combined dag contains the vertex x.
+    pub fn contains(&self, x: &T) -> bool {
+        self.connections(x).is_some()
+    }
+
+    /// Returns the adjacency list of the vertex x in the combined dag.
+    pub fn connections(&self, x: &T) -> Option<&BTreeSet<T>> {
+        match self.delta.get(x) {
+            Some(adj_x) => adj_x.as_ref(),
+            None => self.base.vertices.get(x).map(Arc::as_ref),
+        }
+    }
+
+    /// Returns the vertices of the combined dag.
+    pub fn vertices(&self) -> BTreeSet<&T> {
+        self.base
+            .vertices
+            .keys()
+            .filter(|v| !self.delta.contains_key(*v))
+            .chain(
+                self.delta
+                    .iter()
+                    .filter(|(_, adj)| adj.is_some())
+                    .map(|(v, _)| v),
+            )
+            .collect()
+    }
+
+    /// Returns true if the vertex y is reachable from the vertex x in the
+    /// combined dag.
+    pub fn is_reachable(&self, x: &T, y: &T) -> bool {
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = Vec::from([x]);
+        while let Some(v) = stack.pop() {
+            if v == y {
+                return true;
+            }
+            if visited.insert(v) {
+                stack.extend(self.connections(v).into_iter().flatten());
+            }
+        }
+        false
+    }
+}
+
+impl<'a, T> OverlayDag<'a, T>
+where
+    T: Ord + Clone,
+{
+    /// Returns a copy of the base dag with the delta folded in. The copy
+    /// shares every unchanged adjacency list with the base.
+    pub fn commit(self) -> BTreeDAG<T> {
+        let mut dag: BTreeDAG<T> = self.base.clone();
+        for (x, adj_x) in self.delta {
+            match adj_x {
+                Some(adj_x) => {
+                    dag.vertices.insert(x, Arc::new(adj_x));
+                }
+                None => {
+                    dag.vertices.remove(&x);
+                }
+            }
+        }
+        dag
+    }
+
+    /// Returns the adjacency list of the vertex x in the delta, copying it
+    /// from the base if it has not been changed yet.
+    fn delta_mut(&mut self, x: &T) -> Option<&mut BTreeSet<T>> {
+        if !self.delta.contains_key(x) {
+            let adj_x: BTreeSet<T> = BTreeSet::clone(self.base.vertices.get(x)?);
+            self.delta.insert(x.clone(), Some(adj_x));
+        }
+        self.delta.get_mut(x)?.as_mut()
+    }
+}
+
+impl<'a, T> AddVertex<T> for OverlayDag<'a, T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        let previous = self.connections(&x).cloned();
+        self.delta.insert(x, Some(BTreeSet::new()));
+        previous
+    }
+}
+
+impl<'a, T> AddEdge<T> for OverlayDag<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.contains(&x) || !self.contains(&y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        if self.is_reachable(&y, &x) {
+            return Err(Error::EdgeExists);
+        }
+        // We have already verified x is in the combined dag, so we can
+        // safely unwrap.
+        let adj_x = self.delta_mut(&x).unwrap();
+        let previous_adj_x: BTreeSet<T> = adj_x.clone();
+        adj_x.insert(y);
+        Ok(previous_adj_x)
+    }
+}
+
+impl<'a, T> RemoveEdge<T> for OverlayDag<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.contains(&y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let adj_x = self.connections(&x).ok_or(Error::VertexDoesNotExist)?;
+        let previous_adj_x: BTreeSet<T> = adj_x.clone();
+        // Only copy the adjacency list into the delta if it changes.
+        if adj_x.contains(&y) {
+            // We have already verified x is in the combined dag, so we can
+            // safely unwrap.
+            self.delta_mut(&x).unwrap().remove(&y);
+        }
+        Ok(previous_adj_x)
+    }
+}
+
+impl<'a, T> RemoveVertex<T> for OverlayDag<'a, T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.contains(&x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let incoming: Vec<T> = self
+            .vertices()
+            .into_iter()
+            .filter(|v| self.connections(v).is_some_and(|adj| adj.contains(&x)))
+            .cloned()
+            .collect();
+        for v in incoming {
+            self.remove_edge(v, x.clone())?;
+        }
+        // We have already verified x is in the combined dag, so we can
+        // safely unwrap.
+        let adj_x = self.connections(&x).unwrap().clone();
+        self.delta.insert(x, None);
+        Ok(adj_x)
+    }
+}
+
+impl<'a, T> Adjacent<T> for OverlayDag<'a, T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        if !self.contains(&y) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        self.connections(&x)
+            .map(|adj_x| adj_x.contains(&y))
+            .ok_or(Error::VertexDoesNotExist)
+    }
+}
+
+impl<'a, T> From<&'a BTreeDAG<T>> for OverlayDag<'a, T>
+where
+    T: Ord,
+{
+    fn from(base: &'a BTreeDAG<T>) -> Self {
+        Self::new(base)
+    }
+}
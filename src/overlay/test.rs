@@ -0,0 +1,44 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::overlay::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn overlay() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut base: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..3 {
+            base.add_vertex(x);
+        }
+        base.add_edge(0, 1)?;
+        base.add_edge(1, 2)?;
+
+        // Cycle checks are made against the combined dag.
+        let mut overlay: OverlayDag<usize> = OverlayDag::new(&base);
+        overlay.add_vertex(3);
+        overlay.add_edge(2, 3)?;
+        assert_eq!(overlay.add_edge(3, 0), Err(Error::EdgeExists));
+        assert!(overlay.is_reachable(&0, &3));
+
+        // Removing a vertex of the base removes its incoming edges.
+        overlay.remove_vertex(1)?;
+        assert_eq!(overlay.vertices(), BTreeSet::from([&0, &2, &3]));
+        assert_eq!(overlay.connections(&0), Some(&BTreeSet::new()));
+        overlay.add_edge(3, 0)?;
+        assert_eq!(overlay.delta_len(), 4);
+
+        // The base is unchanged until the delta is committed.
+        assert!(base.adjacent(0, 1)?);
+        let dag: BTreeDAG<usize> = overlay.commit();
+        assert_eq!(dag.vertices(), BTreeSet::from([&0, &2, &3]));
+        assert!(dag.adjacent(2, 3)?);
+        assert!(dag.adjacent(3, 0)?);
+        assert!(!dag.adjacent(0, 2)?);
+
+        // Test passed.
+        Ok(())
+    }
+}
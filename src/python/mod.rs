@@ -0,0 +1,119 @@
+mod test;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyKeyError};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::dag::{
+    AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex, TopologicalSort,
+};
+use crate::Error;
+
+create_exception!(
+    btree_dag,
+    CycleError,
+    PyException,
+    "Raised when adding an edge would introduce a cycle."
+);
+
+/// `PyDag` exposes a `BTreeDAG` keyed by strings to Python as the class
+/// `btree_dag.BTreeDAG`. The class behaves like a read-only mapping from each
+/// vertex to the sorted list of its successors. Adding an edge which would
+/// introduce a cycle raises `CycleError`, and referring to a vertex which does
+/// not exist raises `KeyError`.
+#[pyclass(name = "BTreeDAG", module = "btree_dag")]
+#[derive(Default)]
+pub struct PyDag {
+    dag: BTreeDAG<String>,
+}
+
+#[pymethods]
+impl PyDag {
+    #[new]
+    fn new() -> Self {
+        PyDag {
+            dag: BTreeDAG::new(),
+        }
+    }
+
+    fn add_vertex(&mut self, x: String) {
+        self.dag.add_vertex(x);
+    }
+
+    fn add_edge(&mut self, x: String, y: String) -> PyResult<()> {
+        self.dag.add_edge(x, y).map(|_| ()).map_err(py_error)
+    }
+
+    fn remove_edge(&mut self, x: String, y: String) -> PyResult<()> {
+        self.dag.remove_edge(x, y).map(|_| ()).map_err(py_error)
+    }
+
+    fn remove_vertex(&mut self, x: String) -> PyResult<()> {
+        self.dag.remove_vertex(x).map(|_| ()).map_err(py_error)
+    }
+
+    fn prune(&mut self, x: String) -> PyResult<()> {
+        self.dag.prune(x).map_err(py_error)
+    }
+
+    fn adjacent(&self, x: String, y: String) -> PyResult<bool> {
+        self.dag.adjacent(x, y).map_err(py_error)
+    }
+
+    fn topological_sort(&self) -> Vec<String> {
+        self.dag.topological_sort().into_iter().cloned().collect()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.dag.vertices.keys().cloned().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.dag.vertices.len()
+    }
+
+    fn __contains__(&self, x: &str) -> bool {
+        self.dag.vertices.contains_key(x)
+    }
+
+    fn __getitem__(&self, x: &str) -> PyResult<Vec<String>> {
+        self.dag
+            .vertices
+            .get(x)
+            .map(|adj| adj.iter().cloned().collect())
+            .ok_or_else(|| PyKeyError::new_err(String::from(x)))
+    }
+
+    fn __delitem__(&mut self, x: String) -> PyResult<()> {
+        self.remove_vertex(x)
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(PyList::new(py, self.keys())?.try_iter()?.into_any())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BTreeDAG({:?})", self.keys())
+    }
+}
+
+fn py_error(err: Error) -> PyErr {
+    match err {
+        Error::EdgeExists => CycleError::new_err("the edge would introduce a cycle"),
+        Error::VertexDoesNotExist => PyKeyError::new_err("vertex does not exist"),
+        err => PyException::new_err(format!("{:?}", err)),
+    }
+}
+
+/// The `btree_dag` Python module, built as an extension module by the
+/// `python` crate.
+#[pymodule]
+fn btree_dag(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDag>()?;
+    m.add("CycleError", m.py().get_type::<CycleError>())?;
+    Ok(())
+}
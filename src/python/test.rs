@@ -0,0 +1,54 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::python::*;
+    use alloc::string::String;
+    use alloc::vec;
+    use pyo3::exceptions::PyKeyError;
+    use pyo3::Python;
+
+    #[test]
+    fn py_dag() {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut dag: PyDag = PyDag::new();
+            dag.add_vertex(String::from("origin"));
+            dag.add_vertex(String::from("destination"));
+            dag.add_edge(String::from("origin"), String::from("destination"))
+                .unwrap();
+
+            // Mapping access.
+            assert_eq!(dag.__len__(), 2);
+            assert!(dag.__contains__("origin"));
+            assert!(!dag.__contains__("waypoint"));
+            assert_eq!(
+                dag.__getitem__("origin").unwrap(),
+                vec![String::from("destination")]
+            );
+            assert!(dag
+                .__getitem__("waypoint")
+                .unwrap_err()
+                .is_instance_of::<PyKeyError>(py));
+
+            // Cycles raise a CycleError.
+            assert!(dag
+                .add_edge(String::from("destination"), String::from("origin"))
+                .unwrap_err()
+                .is_instance_of::<CycleError>(py));
+            assert!(dag
+                .add_edge(String::from("origin"), String::from("waypoint"))
+                .unwrap_err()
+                .is_instance_of::<PyKeyError>(py));
+
+            assert_eq!(
+                dag.topological_sort(),
+                vec![String::from("origin"), String::from("destination")]
+            );
+
+            dag.__delitem__(String::from("destination")).unwrap();
+            assert_eq!(dag.keys(), vec![String::from("origin")]);
+        });
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,200 @@
+mod test;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use rand_core::RngCore;
+
+use crate::dag::BTreeDAG;
+
+/// `SampleAddableEdges` returns up to k random edges (x, y) which are not in
+/// the dag and which can all be added together without introducing a cycle.
+/// The edges are drawn uniformly from the pairs ordered by a random
+/// topological order of the dag, so every addable edge may be sampled.
+/// Fewer than k edges are returned if fewer are addable. The edges are
+/// returned in ascending order.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, SampleAddableEdges};
+/// # struct Counter(u64);
+/// # impl rand_core::RngCore for Counter {
+/// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+/// #     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+/// #     fn fill_bytes(&mut self, dst: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dst) }
+/// # }
+/// let mut rng = Counter(0);
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let edges = dag.sample_addable_edges(&mut rng, 4);
+/// assert_eq!(edges, vec![(String::from("origin"), String::from("destination"))]);
+/// ```
+pub trait SampleAddableEdges<T> {
+    fn sample_addable_edges(&self, rng: &mut impl RngCore, k: usize) -> Vec<(T, T)>;
+}
+
+/// Returns a random integer below n, which must not be zero.
+pub(crate) fn below(rng: &mut impl RngCore, n: usize) -> usize {
+    (rng.next_u64() % n as u64) as usize
+}
+
+impl<T> SampleAddableEdges<T> for BTreeDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn sample_addable_edges(&self, rng: &mut impl RngCore, k: usize) -> Vec<(T, T)> {
+        // Kahn's algorithm, removing a random vertex with no remaining
+        // incoming edges each time.
+        let mut in_degrees: BTreeMap<&T, usize> = self.vertices.keys().map(|v| (v, 0)).collect();
+        for adj in self.vertices.values().flat_map(|adj| adj.iter()) {
+            *in_degrees.get_mut(adj).unwrap() += 1;
+        }
+        let mut ready: Vec<&T> = in_degrees
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(v, _)| *v)
+            .collect();
+        let mut order: Vec<&T> = Vec::with_capacity(self.vertices.len());
+        while !ready.is_empty() {
+            let v = ready.swap_remove(below(rng, ready.len()));
+            order.push(v);
+            for adj in self.vertices[v].iter() {
+                let d = in_degrees.get_mut(adj).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    ready.push(adj);
+                }
+            }
+        }
+
+        // Every edge from an earlier to a later vertex of the order can be
+        // added together, so reservoir sample k of those not in the dag.
+        let mut sampled: Vec<(&T, &T)> = Vec::with_capacity(k);
+        let mut seen: usize = 0;
+        for (i, x) in order.iter().enumerate() {
+            for y in order[i + 1..].iter() {
+                if self.vertices[*x].contains(*y) {
+                    continue;
+                }
+                if sampled.len() < k {
+                    sampled.push((*x, *y));
+                } else {
+                    let j = below(rng, seen + 1);
+                    if j < k {
+                        sampled[j] = (*x, *y);
+                    }
+                }
+                seen += 1;
+            }
+        }
+        sampled.sort();
+        sampled
+            .into_iter()
+            .map(|(x, y)| (x.clone(), y.clone()))
+            .collect()
+    }
+}
+
+/// `SampleVertices` returns k distinct vertices drawn uniformly at random, or
+/// every vertex if there are fewer than k, in ascending order. The vertices are
+/// selected in a single pass, holding only the sampled vertices.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, SampleVertices};
+/// # struct Counter(u64);
+/// # impl rand_core::RngCore for Counter {
+/// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+/// #     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+/// #     fn fill_bytes(&mut self, dst: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dst) }
+/// # }
+/// let mut rng = Counter(0);
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+///
+/// assert_eq!(dag.sample_vertices(&mut rng, 2).len(), 2);
+/// assert_eq!(dag.sample_vertices(&mut rng, 4).len(), 3);
+/// ```
+pub trait SampleVertices<T> {
+    fn sample_vertices(&self, rng: &mut impl RngCore, k: usize) -> Vec<&T>;
+}
+
+/// `SampleEdges` returns k distinct edges (x, y) drawn uniformly at random, or
+/// every edge if there are fewer than k, in ascending order. The edges are
+/// selected in a single pass, holding only the sampled edges.
+///
+/// # Example
+///
+/// ```
+/// use btree_dag::{BTreeDAG, AddVertex, AddEdge, SampleEdges};
+/// # struct Counter(u64);
+/// # impl rand_core::RngCore for Counter {
+/// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+/// #     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+/// #     fn fill_bytes(&mut self, dst: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dst) }
+/// # }
+/// let mut rng = Counter(0);
+/// let mut dag: BTreeDAG<String> = BTreeDAG::new();
+/// dag.add_vertex(String::from("origin"));
+/// dag.add_vertex(String::from("waypoint"));
+/// dag.add_vertex(String::from("destination"));
+/// dag.add_edge(String::from("origin"), String::from("waypoint"));
+/// dag.add_edge(String::from("waypoint"), String::from("destination"));
+///
+/// let edges = dag.sample_edges(&mut rng, 1);
+/// assert_eq!(edges.len(), 1);
+/// assert!(dag.sample_edges(&mut rng, 0).is_empty());
+/// ```
+pub trait SampleEdges<T> {
+    fn sample_edges(&self, rng: &mut impl RngCore, k: usize) -> Vec<(&T, &T)>;
+}
+
+/// Selects k of the n items uniformly at random, keeping their order, by
+/// selecting each item with probability equal to the number of items left to
+/// select over the number of items left (Knuth's algorithm S).
+fn select<I>(rng: &mut impl RngCore, items: I, n: usize, k: usize) -> Vec<I::Item>
+where
+    I: Iterator,
+{
+    let mut selected: Vec<I::Item> = Vec::with_capacity(k.min(n));
+    for (i, item) in items.enumerate() {
+        if selected.len() == k {
+            break;
+        }
+        if below(rng, n - i) < k - selected.len() {
+            selected.push(item);
+        }
+    }
+    selected
+}
+
+impl<T> SampleVertices<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn sample_vertices(&self, rng: &mut impl RngCore, k: usize) -> Vec<&T> {
+        select(rng, self.vertices.keys(), self.vertices.len(), k)
+    }
+}
+
+impl<T> SampleEdges<T> for BTreeDAG<T>
+where
+    T: Ord,
+{
+    fn sample_edges(&self, rng: &mut impl RngCore, k: usize) -> Vec<(&T, &T)> {
+        let n: usize = self.vertices.values().map(|adj| adj.len()).sum();
+        let edges = self
+            .vertices
+            .iter()
+            .flat_map(|(x, adj_x)| adj_x.iter().map(move |y| (x, y)));
+        select(rng, edges, n, k)
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::sample::*;
+    use crate::Error;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// A xorshift generator, which is sufficient for testing.
+    pub(crate) struct XorShift(pub(crate) u64);
+
+    impl RngCore for XorShift {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dst)
+        }
+    }
+
+    #[test]
+    fn sample_addable_edges() -> Result<(), Error> {
+        // Add six nodes, and an edge (0, 1) and (2, 3).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(2, 3)?;
+
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+        for _ in 0..16 {
+            // Every sampled edge is new, and they can all be added.
+            let edges: Vec<(usize, usize)> = dag.sample_addable_edges(&mut rng, 5);
+            assert_eq!(edges.len(), 5);
+            let mut grown: BTreeDAG<usize> = dag.clone();
+            for (x, y) in edges {
+                assert!(!dag.adjacent(x, y)?);
+                grown.add_edge(x, y)?;
+            }
+        }
+
+        // At most every pair but the existing edges can be sampled.
+        let edges: Vec<(usize, usize)> = dag.sample_addable_edges(&mut rng, 100);
+        assert_eq!(edges.len(), 15 - 2);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn sample_vertices_and_edges() -> Result<(), Error> {
+        // Add a chain of ten nodes, with an edge (i, i + 1).
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        for x in 0..10 {
+            dag.add_vertex(x);
+        }
+        for x in 0..9 {
+            dag.add_edge(x, x + 1)?;
+        }
+
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+        assert_eq!(dag.sample_vertices(&mut rng, 20).len(), 10);
+        assert_eq!(dag.sample_edges(&mut rng, 20).len(), 9);
+        assert!(BTreeDAG::<usize>::new()
+            .sample_edges(&mut rng, 3)
+            .is_empty());
+
+        // Every vertex, and every edge, is sampled about equally often.
+        let mut vertex_counts: Vec<usize> = vec![0; 10];
+        let mut edge_counts: Vec<usize> = vec![0; 9];
+        for _ in 0..3000 {
+            let vertices: Vec<&usize> = dag.sample_vertices(&mut rng, 3);
+            assert_eq!(vertices.len(), 3);
+            assert!(vertices.windows(2).all(|pair| pair[0] < pair[1]));
+            for v in vertices {
+                vertex_counts[*v] += 1;
+            }
+            for (x, y) in dag.sample_edges(&mut rng, 3) {
+                assert!(dag.adjacent(*x, *y)?);
+                edge_counts[*x] += 1;
+            }
+        }
+        // Each vertex is expected 900 times, and each edge 1000 times.
+        assert!(vertex_counts.iter().all(|c| (800..1000).contains(c)));
+        assert!(edge_counts.iter().all(|c| (900..1100).contains(c)));
+
+        // Test passed.
+        Ok(())
+    }
+}
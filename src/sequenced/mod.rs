@@ -0,0 +1,163 @@
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::dag::{
+    AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex, TraversalOrder,
+};
+use crate::Error;
+
+/// `SequencedDAG` wraps a `BTreeDAG`, recording a monotonically increasing
+/// sequence number for each vertex when it is first added. Re-adding an
+/// existing vertex keeps its sequence number, and the sequence numbers of
+/// removed vertices are never reused.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SequencedDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    sequence: BTreeMap<T, u64>,
+    next: u64,
+}
+
+impl<T> SequencedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        SequencedDAG {
+            dag: BTreeDAG::new(),
+            sequence: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns the sequenced dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the sequence number of the vertex x, or None if x does not
+    /// exist.
+    pub fn sequence_number(&self, x: &T) -> Option<u64> {
+        self.sequence.get(x).copied()
+    }
+
+    /// Returns the vertices in the order they were first added.
+    pub fn vertices_by_insertion(&self) -> Vec<&T> {
+        let mut vertices: Vec<(&T, u64)> = self.sequence.iter().map(|(v, s)| (v, *s)).collect();
+        vertices.sort_unstable_by_key(|(_, s)| *s);
+        vertices.into_iter().map(|(v, _)| v).collect()
+    }
+
+    /// Returns the `TraversalOrder` which visits the vertex added first first.
+    pub fn insertion_order(&self) -> InsertionOrder<'_, T> {
+        InsertionOrder {
+            sequence: &self.sequence,
+        }
+    }
+
+    /// Removes the sequence numbers of every vertex which no longer exists.
+    fn retain_existing(&mut self) {
+        let dag = &self.dag;
+        self.sequence.retain(|v, _| dag.vertices.contains_key(v));
+    }
+}
+
+impl<T> Default for SequencedDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `InsertionOrder` is a `TraversalOrder` visiting vertices in the order they
+/// were first added to a `SequencedDAG`. It is constructed through
+/// `SequencedDAG::insertion_order`.
+pub struct InsertionOrder<'a, T>
+where
+    T: Ord,
+{
+    sequence: &'a BTreeMap<T, u64>,
+}
+
+impl<'a, T> TraversalOrder<T> for InsertionOrder<'a, T>
+where
+    T: Ord,
+{
+    fn cmp(&self, a: &T, b: &T) -> Ordering {
+        self.sequence.get(a).cmp(&self.sequence.get(b))
+    }
+}
+
+impl<T> AddVertex<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        if !self.sequence.contains_key(&x) {
+            self.sequence.insert(x.clone(), self.next);
+            self.next += 1;
+        }
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T> AddEdge<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.add_edge(x, y)
+    }
+}
+
+impl<T> RemoveEdge<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.remove_edge(x, y)
+    }
+}
+
+impl<T> RemoveVertex<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_vertex(x)?;
+        self.retain_existing();
+        Ok(adj_x)
+    }
+}
+
+impl<T> Prune<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x)?;
+        self.retain_existing();
+        Ok(())
+    }
+}
+
+impl<T> Adjacent<T> for SequencedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        self.dag.adjacent(x, y)
+    }
+}
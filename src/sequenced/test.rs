@@ -0,0 +1,45 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::sequenced::*;
+    use crate::Error;
+    use alloc::vec;
+
+    #[test]
+    fn insertion_order() -> Result<(), Error> {
+        // Add nodes in descending order, and the edges (3, 1) and (3, 2).
+        let mut dag: SequencedDAG<usize> = SequencedDAG::new();
+        dag.add_vertex(3);
+        dag.add_vertex(2);
+        dag.add_vertex(1);
+        dag.add_vertex(0);
+        dag.add_edge(3, 1)?;
+        dag.add_edge(3, 2)?;
+        // Re-adding a vertex keeps its sequence number.
+        dag.add_vertex(2);
+        assert_eq!(dag.sequence_number(&2), Some(1));
+        assert_eq!(dag.vertices_by_insertion(), vec![&3, &2, &1, &0]);
+
+        // Ties are broken by the order the vertices were added.
+        assert_eq!(
+            dag.dag().topological_sort_with(dag.insertion_order()),
+            vec![&3, &2, &1, &0]
+        );
+        assert_eq!(
+            dag.dag().bfs_with(&3, dag.insertion_order()),
+            Some(vec![&3, &2, &1])
+        );
+
+        // Sequence numbers of removed vertices are not reused.
+        dag.remove_vertex(2)?;
+        assert_eq!(dag.sequence_number(&2), None);
+        dag.add_vertex(2);
+        assert_eq!(dag.sequence_number(&2), Some(4));
+        dag.prune(3)?;
+        assert_eq!(dag.vertices_by_insertion(), vec![&0, &2]);
+
+        // Test passed.
+        Ok(())
+    }
+}
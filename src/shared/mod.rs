@@ -0,0 +1,227 @@
+mod test;
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard};
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Mutation, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `SharedDAG` is a thread-safe handle to a `BTreeDAG`, shared behind an
+/// `Arc<RwLock<_>>`. Cloning the handle shares the same dag.
+///
+/// Queries take a read lock, and each mutation takes the write lock only for
+/// the duration of the (cycle checked) mutation. Long traversals should be run
+/// against a `snapshot`, which holds no lock at all.
+///
+/// Every mutation of a `BTreeDAG` is validated before the dag is changed, so a
+/// panic while the lock is held cannot leave the dag cyclic; poisoned locks are
+/// therefore recovered rather than propagated.
+///
+/// A `Subscription` to a vertex receives every mutation through the handle's
+/// own mutation methods which changes the vertex, or any vertex reachable from
+/// it. Mutations made within `update` are not sent.
+#[derive(Debug)]
+pub struct SharedDAG<T>
+where
+    T: Ord,
+{
+    dag: Arc<RwLock<BTreeDAG<T>>>,
+    subscribers: Arc<Mutex<Vec<Subscriber<T>>>>,
+}
+
+/// A subscribed vertex, and the sender notifying its subscription.
+type Subscriber<T> = (T, Sender<Mutation<T>>);
+
+/// `Subscription` is a handle receiving the mutations of a `SharedDAG` which
+/// change the subscribed vertex, or any vertex reachable from it. Dropping the
+/// subscription unsubscribes.
+#[derive(Debug)]
+pub struct Subscription<T> {
+    receiver: Receiver<Mutation<T>>,
+}
+
+impl<T> Subscription<T> {
+    /// Blocks until the next mutation is received, returning `None` once every
+    /// handle to the dag has been dropped.
+    pub fn recv(&self) -> Option<Mutation<T>> {
+        self.receiver.recv().ok()
+    }
+
+    /// Returns the next mutation if one has been received, without blocking.
+    pub fn try_recv(&self) -> Option<Mutation<T>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl<T> SharedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        SharedDAG {
+            dag: Arc::new(RwLock::new(BTreeDAG::new())),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Acquires a read guard for running queries against the dag. The write
+    /// lock cannot be taken while the guard is held.
+    pub fn read(&self) -> RwLockReadGuard<'_, BTreeDAG<T>> {
+        self.dag.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs the query f against the dag while holding the read lock.
+    pub fn query<R>(&self, f: impl FnOnce(&BTreeDAG<T>) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Runs the closure f against the dag while holding the write lock, for
+    /// grouping several mutations together. The dag's own mutation methods
+    /// still enforce acyclicity.
+    pub fn update<R>(&self, f: impl FnOnce(&mut BTreeDAG<T>) -> R) -> R {
+        f(&mut self.dag.write().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<T> SharedDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Returns a snapshot of the dag, releasing the read lock before
+    /// returning. See `BTreeDAG::snapshot`.
+    pub fn snapshot(&self) -> BTreeDAG<T> {
+        self.read().snapshot()
+    }
+
+    /// Subscribes to the mutations which change the vertex x, or any vertex
+    /// reachable from x. An error is returned if x does not exist.
+    pub fn subscribe(&self, x: &T) -> Result<Subscription<T>, Error> {
+        // Holding the read lock ensures x is not removed before the
+        // subscription is registered.
+        let dag = self.read();
+        if !dag.vertices.contains_key(x) {
+            return Err(Error::VertexDoesNotExist);
+        }
+        let (sender, receiver) = channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((x.clone(), sender));
+        Ok(Subscription { receiver })
+    }
+
+    pub fn add_vertex(&self, x: T) -> Option<BTreeSet<T>> {
+        let mutation = Mutation::AddVertex(x.clone());
+        let changed = |_: &BTreeDAG<T>| BTreeSet::from([x.clone()]);
+        // Adding a vertex cannot fail, so we can safely unwrap.
+        self.mutate(mutation, changed, |dag| Ok(dag.add_vertex(x.clone())))
+            .unwrap()
+    }
+
+    pub fn add_edge(&self, x: T, y: T) -> Result<BTreeSet<T>, Error> {
+        let mutation = Mutation::AddEdge(x.clone(), y.clone());
+        let changed = |_: &BTreeDAG<T>| BTreeSet::from([x.clone()]);
+        self.mutate(mutation, changed, |dag| dag.add_edge(x.clone(), y))
+    }
+
+    pub fn remove_edge(&self, x: T, y: T) -> Result<BTreeSet<T>, Error> {
+        let mutation = Mutation::RemoveEdge(x.clone(), y.clone());
+        let changed = |_: &BTreeDAG<T>| BTreeSet::from([x.clone()]);
+        self.mutate(mutation, changed, |dag| dag.remove_edge(x.clone(), y))
+    }
+
+    pub fn remove_vertex(&self, x: T) -> Result<BTreeSet<T>, Error> {
+        // The vertices with an edge to x also change, but x is reachable
+        // from each of them, so their subscribers are notified of x anyway.
+        let mutation = Mutation::RemoveVertex(x.clone());
+        let changed = |_: &BTreeDAG<T>| BTreeSet::from([x.clone()]);
+        self.mutate(mutation, changed, |dag| dag.remove_vertex(x.clone()))
+    }
+
+    pub fn prune(&self, x: T) -> Result<(), Error> {
+        let mutation = Mutation::Prune(x.clone());
+        let changed = |dag: &BTreeDAG<T>| {
+            let mut changed: BTreeSet<T> = dag.descendants_of(&x).into_iter().cloned().collect();
+            changed.insert(x.clone());
+            changed
+        };
+        self.mutate(mutation, changed, |dag| dag.prune(x.clone()))
+    }
+
+    /// Runs the mutation f against the dag while holding the write lock, and
+    /// if it succeeds, sends the mutation to every subscriber to a vertex from
+    /// which one of the changed vertices is reachable before the mutation.
+    ///
+    /// Those vertices are found in one traversal, from the changed vertices
+    /// towards their ancestors, however many subscribers there are. The write
+    /// lock is released before the mutation is sent, while the subscribers
+    /// stay locked, so subscribers receive mutations in the order they were
+    /// made.
+    fn mutate<R>(
+        &self,
+        mutation: Mutation<T>,
+        changed: impl FnOnce(&BTreeDAG<T>) -> BTreeSet<T>,
+        f: impl FnOnce(&mut BTreeDAG<T>) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let mut dag = self.dag.write().unwrap_or_else(PoisonError::into_inner);
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if subscribers.is_empty() {
+            return f(&mut dag);
+        }
+        let changed: BTreeSet<T> = changed(&dag);
+        let notified: Vec<bool> = {
+            let ancestors: BTreeSet<&T> = dag.ancestors_of_all(changed.iter());
+            subscribers
+                .iter()
+                .map(|(x, _)| changed.contains(x) || ancestors.contains(x))
+                .collect()
+        };
+        let result = f(&mut dag)?;
+        drop(dag);
+        let mut notified = notified.into_iter();
+        // Subscriptions which have been dropped are removed as they are
+        // next notified.
+        subscribers.retain(|(_, sender)| {
+            !notified.next().unwrap() || sender.send(mutation.clone()).is_ok()
+        });
+        Ok(result)
+    }
+}
+
+impl<T> Clone for SharedDAG<T>
+where
+    T: Ord,
+{
+    fn clone(&self) -> Self {
+        SharedDAG {
+            dag: Arc::clone(&self.dag),
+            subscribers: Arc::clone(&self.subscribers),
+        }
+    }
+}
+
+impl<T> Default for SharedDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<BTreeDAG<T>> for SharedDAG<T>
+where
+    T: Ord,
+{
+    fn from(dag: BTreeDAG<T>) -> Self {
+        SharedDAG {
+            dag: Arc::new(RwLock::new(dag)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::shared::*;
+    use crate::Error;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn clone_shares_dag() -> Result<(), Error> {
+        // Add three nodes through one handle.
+        let dag: SharedDAG<usize> = SharedDAG::new();
+        let handle: SharedDAG<usize> = dag.clone();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+
+        // Add an edge (0, 1) and (1, 2) through the other handle.
+        handle.add_edge(0, 1)?;
+        handle.add_edge(1, 2)?;
+
+        // Both handles observe the same dag.
+        assert_eq!(dag.read().vertices().len(), 3);
+        assert!(dag.query(|dag| dag.adjacent(0, 1))?);
+
+        // Mutations through the handle are still cycle checked.
+        assert_eq!(handle.add_edge(2, 0).unwrap_err(), Error::EdgeExists);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_and_update() -> Result<(), Error> {
+        let mut dag: BTreeDAG<usize> = BTreeDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        let shared: SharedDAG<usize> = SharedDAG::from(dag);
+
+        // Snapshots hold no lock, so the dag can be mutated meanwhile.
+        let snapshot: BTreeDAG<usize> = shared.snapshot();
+        shared.update(|dag| -> Result<(), Error> {
+            dag.add_vertex(2);
+            dag.add_edge(0, 2)?;
+            Ok(())
+        })?;
+        assert_eq!(snapshot.vertices().len(), 2);
+        assert_eq!(shared.read().vertices().len(), 3);
+
+        shared.remove_edge(0, 2)?;
+        shared.remove_vertex(2)?;
+        shared.prune(0)?;
+        assert_eq!(shared.read().vertices().len(), 1);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_mutation() {
+        let dag: SharedDAG<usize> = SharedDAG::new();
+        for v in 0..8 {
+            dag.add_vertex(v);
+        }
+
+        // Race threads adding edges in both directions; the cycle check
+        // ensures exactly one direction of each pair succeeds.
+        let threads: Vec<thread::JoinHandle<()>> = (0..8)
+            .map(|x| {
+                let dag = dag.clone();
+                thread::spawn(move || {
+                    for y in 0..8 {
+                        if x != y {
+                            let _ = dag.add_edge(x, y);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+
+        let dag: BTreeDAG<usize> = dag.snapshot();
+        for x in 0..8 {
+            for y in (x + 1)..8 {
+                assert!(dag.adjacent(x, y).unwrap() ^ dag.adjacent(y, x).unwrap());
+            }
+        }
+
+        // Test passed.
+    }
+
+    #[test]
+    fn subscribe() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1).
+        let dag: SharedDAG<usize> = SharedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        let subscription = dag.subscribe(&0)?;
+        assert!(dag.subscribe(&3).is_err());
+
+        // Changes to 1, which is reachable from 0, are received, but changes
+        // to 2 are not until it is reachable.
+        dag.add_edge(2, 1)?;
+        assert_eq!(subscription.try_recv(), None);
+        dag.add_edge(1, 2).unwrap_err();
+        dag.remove_edge(2, 1)?;
+        dag.add_edge(1, 2)?;
+        assert_eq!(subscription.try_recv(), Some(Mutation::AddEdge(1, 2)));
+        assert_eq!(subscription.try_recv(), None);
+        dag.prune(2)?;
+        assert_eq!(subscription.try_recv(), Some(Mutation::Prune(2)));
+
+        // Subscribers are notified across threads, and dropped once the
+        // subscription is dropped.
+        let handle = dag.clone();
+        thread::spawn(move || handle.remove_vertex(0))
+            .join()
+            .unwrap()?;
+        assert_eq!(subscription.recv(), Some(Mutation::RemoveVertex(0)));
+        drop(subscription);
+        dag.add_vertex(0);
+        assert_eq!(dag.subscribers.lock().unwrap().len(), 0);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn subscribe_many() -> Result<(), Error> {
+        // Add a chain 0 -> 1 -> 2 -> 3, and an unconnected node 4, with a
+        // subscription to each.
+        let dag: SharedDAG<usize> = SharedDAG::new();
+        for x in 0..5 {
+            dag.add_vertex(x);
+        }
+        for x in 0..3 {
+            dag.add_edge(x, x + 1)?;
+        }
+        let subscriptions: Vec<Subscription<usize>> = (0..5)
+            .map(|x| dag.subscribe(&x))
+            .collect::<Result<_, _>>()?;
+
+        // Changing 2 notifies the subscribers to 2 and its ancestors only.
+        dag.remove_edge(2, 3)?;
+        let notified: Vec<bool> = subscriptions
+            .iter()
+            .map(|s| s.try_recv() == Some(Mutation::RemoveEdge(2, 3)))
+            .collect();
+        assert_eq!(notified, [true, true, true, false, false]);
+
+        // Pruning 1 notifies the subscribers to every pruned vertex, and to
+        // the ancestors of any of them.
+        dag.prune(1)?;
+        let notified: Vec<bool> = subscriptions
+            .iter()
+            .map(|s| s.try_recv() == Some(Mutation::Prune(1)))
+            .collect();
+        assert_eq!(notified, [true, true, true, false, false]);
+        assert!(subscriptions.iter().all(|s| s.try_recv().is_none()));
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::storage::{AdjacencyStore, PersistentDAG, StorageError};
+
+/// `StoreCodec` encodes the vertices of a `FileStore` as bytes.
+pub trait StoreCodec: Sized {
+    fn encode(&self) -> Vec<u8>;
+    /// Returns `None` if the bytes are not an encoding of a vertex.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl StoreCodec for String {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+macro_rules! integer_codec {
+    ($($t:ty),*) => {
+        $(
+            impl StoreCodec for $t {
+                fn encode(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+
+                fn decode(bytes: &[u8]) -> Option<Self> {
+                    Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+integer_codec!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// `FileStore` is an `AdjacencyStore` persisted in a single file, an append-only
+/// log of batches. Each batch is written as one frame, holding its length and
+/// a checksum, and synced to disk before `commit` returns. On `open`, frames are
+/// read until the first which is incomplete or fails its checksum, and the file
+/// is truncated there, so a batch torn by a crash is discarded whole.
+///
+/// The log grows with every batch; `compact` rewrites it as a single batch of
+/// the current entries, replacing the file atomically.
+#[derive(Debug)]
+pub struct FileStore<T>
+where
+    T: Ord,
+{
+    path: PathBuf,
+    file: File,
+    // The length of the valid frames, at which the next frame is written.
+    len: u64,
+    entries: BTreeMap<T, BTreeSet<T>>,
+}
+
+impl<T> FileStore<T>
+where
+    T: Ord + Clone + StoreCodec,
+{
+    /// Opens the store at the given path, creating the file if it does not
+    /// exist. A torn batch at the end of the file is discarded.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let mut bytes: Vec<u8> = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut entries: BTreeMap<T, BTreeSet<T>> = BTreeMap::new();
+        let mut len: usize = 0;
+        while let Some((payload, next)) = frame(&bytes, len) {
+            for (x, adj_x) in decode_batch::<T>(payload)?.into_iter() {
+                match adj_x {
+                    Some(adj_x) => entries.insert(x, adj_x),
+                    None => entries.remove(&x),
+                };
+            }
+            len = next;
+        }
+        if len < bytes.len() {
+            file.set_len(len as u64)?;
+            file.sync_data()?;
+        }
+        Ok(FileStore {
+            path,
+            file,
+            len: len as u64,
+            entries,
+        })
+    }
+
+    /// Rewrites the log as a single batch of the current entries. The new log
+    /// is written beside the old one and renamed over it, so a crash leaves
+    /// one of the two intact.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let batch: Vec<(T, Option<BTreeSet<T>>)> = self
+            .entries
+            .iter()
+            .map(|(x, adj_x)| (x.clone(), Some(adj_x.clone())))
+            .collect();
+        let compacted = self.path.with_extension("compact");
+        let mut file = File::create(&compacted)?;
+        let frame = encode_frame(&batch);
+        file.write_all(&frame)?;
+        file.sync_all()?;
+        fs::rename(&compacted, &self.path)?;
+        self.file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.len = frame.len() as u64;
+        Ok(())
+    }
+}
+
+impl<T> FileStore<T>
+where
+    T: Ord,
+{
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<T> AdjacencyStore<T> for FileStore<T>
+where
+    T: Ord + Clone + StoreCodec,
+{
+    type Error = io::Error;
+    fn load(&self) -> Result<Vec<(T, BTreeSet<T>)>, Self::Error> {
+        Ok(self
+            .entries
+            .iter()
+            .map(|(x, adj_x)| (x.clone(), adj_x.clone()))
+            .collect())
+    }
+
+    fn commit(&mut self, batch: Vec<(T, Option<BTreeSet<T>>)>) -> Result<(), Self::Error> {
+        let frame = encode_frame(&batch);
+        // Write over anything past the valid frames, such as a frame torn by
+        // an earlier failed commit.
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.file.write_all(&frame)?;
+        self.file.set_len(self.len + frame.len() as u64)?;
+        self.file.sync_data()?;
+        self.len += frame.len() as u64;
+        for (x, adj_x) in batch.into_iter() {
+            match adj_x {
+                Some(adj_x) => self.entries.insert(x, adj_x),
+                None => self.entries.remove(&x),
+            };
+        }
+        Ok(())
+    }
+}
+
+impl<T> PersistentDAG<T, FileStore<T>>
+where
+    T: Ord + Clone + StoreCodec,
+{
+    /// Opens the dag persisted in the file at the given path, creating an
+    /// empty dag if the file does not exist.
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, StorageError<io::Error>> {
+        PersistentDAG::open(FileStore::open(path).map_err(StorageError::Store)?)
+    }
+}
+
+/// The FNV-1a hash of the bytes, which detects a torn or corrupted frame.
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ *b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Returns the payload of the frame starting at the offset, and the offset of
+/// the next frame, or `None` if the frame is incomplete or corrupt.
+fn frame(bytes: &[u8], offset: usize) -> Option<(&[u8], usize)> {
+    let header = bytes.get(offset..offset + 8)?;
+    let len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let sum = u32::from_le_bytes(header[4..].try_into().ok()?);
+    let payload = bytes.get(offset + 8..offset + 8 + len)?;
+    if checksum(payload) != sum {
+        return None;
+    }
+    Some((payload, offset + 8 + len))
+}
+
+fn encode_frame<T>(batch: &[(T, Option<BTreeSet<T>>)]) -> Vec<u8>
+where
+    T: StoreCodec,
+{
+    let mut payload: Vec<u8> = Vec::new();
+    let put = |payload: &mut Vec<u8>, bytes: &[u8]| {
+        payload.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        payload.extend_from_slice(bytes);
+    };
+    for (x, adj_x) in batch.iter() {
+        put(&mut payload, &x.encode());
+        match adj_x {
+            Some(adj_x) => {
+                payload.push(1);
+                payload.extend_from_slice(&(adj_x.len() as u32).to_le_bytes());
+                for y in adj_x.iter() {
+                    put(&mut payload, &y.encode());
+                }
+            }
+            None => payload.push(0),
+        }
+    }
+    let mut frame: Vec<u8> = Vec::with_capacity(payload.len() + 8);
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&checksum(&payload).to_le_bytes());
+    frame.extend_from_slice(&payload);
+    frame
+}
+
+/// Decodes the batch in a frame's payload. The payload passed its checksum,
+/// so a malformed batch is corruption rather than a torn write, and is an
+/// error rather than discarded.
+fn decode_batch<T>(mut payload: &[u8]) -> io::Result<Vec<(T, Option<BTreeSet<T>>)>>
+where
+    T: Ord + StoreCodec,
+{
+    fn invalid() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, "malformed batch in store")
+    }
+    fn take<'a>(payload: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+        if payload.len() < n {
+            return Err(invalid());
+        }
+        let (taken, rest) = payload.split_at(n);
+        *payload = rest;
+        Ok(taken)
+    }
+    fn take_u32(payload: &mut &[u8]) -> io::Result<usize> {
+        Ok(u32::from_le_bytes(take(payload, 4)?.try_into().unwrap()) as usize)
+    }
+    fn take_vertex<T: StoreCodec>(payload: &mut &[u8]) -> io::Result<T> {
+        let len = take_u32(payload)?;
+        T::decode(take(payload, len)?).ok_or_else(invalid)
+    }
+
+    let mut batch: Vec<(T, Option<BTreeSet<T>>)> = Vec::new();
+    while !payload.is_empty() {
+        let x: T = take_vertex(&mut payload)?;
+        let adj_x = match take(&mut payload, 1)?[0] {
+            0 => None,
+            1 => {
+                let n = take_u32(&mut payload)?;
+                let mut adj_x: BTreeSet<T> = BTreeSet::new();
+                for _ in 0..n {
+                    adj_x.insert(take_vertex(&mut payload)?);
+                }
+                Some(adj_x)
+            }
+            _ => return Err(invalid()),
+        };
+        batch.push((x, adj_x));
+    }
+    Ok(batch)
+}
@@ -0,0 +1,237 @@
+#[cfg(feature = "std")]
+mod file;
+mod test;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use crate::dag::{is_acyclic, AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+#[cfg(feature = "std")]
+pub use file::*;
+
+/// `AdjacencyStore` is a key-value store holding one entry per vertex, mapping
+/// the vertex to its adjacency set, through which a `PersistentDAG` persists
+/// incrementally. Implementations encode the vertices as they see fit, and must
+/// commit each batch atomically so a crash can not persist part of a batch.
+/// With the `std` feature, `FileStore` persists the entries in a file; embedded
+/// stores such as sled or redb can be adapted by implementing this trait.
+pub trait AdjacencyStore<T> {
+    type Error;
+    /// Returns every vertex in the store, with its adjacency set.
+    fn load(&self) -> Result<Vec<(T, BTreeSet<T>)>, Self::Error>;
+    /// Atomically writes every entry in the batch, where `None` removes the
+    /// vertex from the store.
+    fn commit(&mut self, batch: Vec<(T, Option<BTreeSet<T>>)>) -> Result<(), Self::Error>;
+}
+
+/// `MemoryStore` is an `AdjacencyStore` held in memory, for testing. It is not
+/// persisted.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct MemoryStore<T>
+where
+    T: Ord,
+{
+    pub entries: BTreeMap<T, BTreeSet<T>>,
+}
+
+impl<T> AdjacencyStore<T> for MemoryStore<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Infallible;
+    fn load(&self) -> Result<Vec<(T, BTreeSet<T>)>, Self::Error> {
+        Ok(self
+            .entries
+            .iter()
+            .map(|(x, adj_x)| (x.clone(), adj_x.clone()))
+            .collect())
+    }
+
+    fn commit(&mut self, batch: Vec<(T, Option<BTreeSet<T>>)>) -> Result<(), Self::Error> {
+        for (x, adj_x) in batch.into_iter() {
+            match adj_x {
+                Some(adj_x) => self.entries.insert(x, adj_x),
+                None => self.entries.remove(&x),
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Errors which may occur while opening a `PersistentDAG`: either the store
+/// failed, or its contents are not a valid dag.
+#[derive(PartialEq, Debug)]
+pub enum StorageError<E> {
+    Store(E),
+    Dag(Error),
+}
+
+/// `PersistentDAG` wraps a `BTreeDAG` persisted in an `AdjacencyStore`. Mutations
+/// are applied in memory and batched, and `flush` commits the adjacency sets of
+/// every vertex changed since the last flush in a single batch, so a crash
+/// loses at most the unflushed mutations.
+#[derive(Debug)]
+pub struct PersistentDAG<T, S>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    store: S,
+    dirty: BTreeSet<T>,
+}
+
+impl<T, S> PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    /// Opens the dag persisted in the store. An error is returned if the store
+    /// fails, if an edge refers to a vertex which is not stored, or if the
+    /// stored edges contain a cycle.
+    pub fn open(store: S) -> Result<Self, StorageError<S::Error>> {
+        let vertices: BTreeMap<T, Arc<BTreeSet<T>>> = store
+            .load()
+            .map_err(StorageError::Store)?
+            .into_iter()
+            .map(|(x, adj_x)| (x, Arc::new(adj_x)))
+            .collect();
+        if vertices
+            .values()
+            .flat_map(|adj| adj.iter())
+            .any(|y| !vertices.contains_key(y))
+        {
+            return Err(StorageError::Dag(Error::VertexDoesNotExist));
+        }
+        if !is_acyclic(&vertices) {
+            return Err(StorageError::Dag(Error::EdgeExists));
+        }
+        Ok(PersistentDAG {
+            dag: BTreeDAG::from_vertices(vertices),
+            store,
+            dirty: BTreeSet::new(),
+        })
+    }
+
+    /// Commits every vertex changed since the last flush to the store in a
+    /// single batch. If the commit fails the changes remain unflushed.
+    pub fn flush(&mut self) -> Result<(), S::Error> {
+        let dag = &self.dag;
+        let batch: Vec<(T, Option<BTreeSet<T>>)> = self
+            .dirty
+            .iter()
+            .map(|x| {
+                (
+                    x.clone(),
+                    dag.vertices.get(x).map(|adj| BTreeSet::clone(adj)),
+                )
+            })
+            .collect();
+        self.store.commit(batch)?;
+        self.dirty.clear();
+        Ok(())
+    }
+
+    /// Returns the vertices which change when the given vertices are removed:
+    /// the vertices themselves, and every vertex with an edge to one of them.
+    fn changed_by_removal(&self, mut removed: BTreeSet<T>) -> BTreeSet<T> {
+        let predecessors: Vec<T> = self
+            .dag
+            .vertices
+            .iter()
+            .filter(|(_, adj_x)| adj_x.iter().any(|y| removed.contains(y)))
+            .map(|(x, _)| x.clone())
+            .collect();
+        removed.extend(predecessors);
+        removed
+    }
+}
+
+impl<T, S> PersistentDAG<T, S>
+where
+    T: Ord,
+{
+    /// Returns the persisted dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns true if there are mutations which have not been flushed.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+}
+
+impl<T, S> AddVertex<T> for PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.dirty.insert(x.clone());
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T, S> AddEdge<T> for PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.add_edge(x.clone(), y)?;
+        self.dirty.insert(x);
+        Ok(adj_x)
+    }
+}
+
+impl<T, S> RemoveEdge<T> for PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_edge(x.clone(), y)?;
+        self.dirty.insert(x);
+        Ok(adj_x)
+    }
+}
+
+impl<T, S> RemoveVertex<T> for PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let mut changed = self.changed_by_removal(BTreeSet::from([x.clone()]));
+        let adj_x = self.dag.remove_vertex(x)?;
+        self.dirty.append(&mut changed);
+        Ok(adj_x)
+    }
+}
+
+impl<T, S> Prune<T> for PersistentDAG<T, S>
+where
+    T: Ord + Clone,
+    S: AdjacencyStore<T>,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        let mut removed: BTreeSet<T> = self.dag.descendants_of(&x).into_iter().cloned().collect();
+        removed.insert(x.clone());
+        let mut changed = self.changed_by_removal(removed);
+        self.dag.prune(x)?;
+        self.dirty.append(&mut changed);
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::storage::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn flush_and_reopen() -> Result<(), Error> {
+        let mut dag = PersistentDAG::open(MemoryStore::default()).unwrap();
+
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        assert!(dag.store().entries.is_empty());
+        dag.flush().unwrap();
+        assert!(!dag.is_dirty());
+        assert_eq!(dag.store().entries[&1], BTreeSet::from([2]));
+
+        // Removing 1 also rewrites 0, which loses its edge.
+        dag.remove_vertex(1)?;
+        dag.flush().unwrap();
+        assert_eq!(dag.store().entries[&0], BTreeSet::new());
+        assert!(!dag.store().entries.contains_key(&1));
+
+        // Reopening the store restores the dag.
+        let reopened = PersistentDAG::open(dag.store().clone()).unwrap();
+        assert_eq!(reopened.dag(), dag.dag());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn open_invalid() {
+        // A store with an edge (0, 1) and (1, 0).
+        let mut store: MemoryStore<usize> = MemoryStore::default();
+        store.entries.insert(0, BTreeSet::from([1]));
+        store.entries.insert(1, BTreeSet::from([0]));
+        assert_eq!(
+            PersistentDAG::open(store.clone()).unwrap_err(),
+            StorageError::Dag(Error::EdgeExists)
+        );
+
+        // A store with an edge to a vertex which is not stored.
+        store.entries.remove(&1);
+        assert_eq!(
+            PersistentDAG::open(store).unwrap_err(),
+            StorageError::Dag(Error::VertexDoesNotExist)
+        );
+
+        // Test passed.
+    }
+
+    /// Returns a path in the temporary directory unique to the test, removing
+    /// any file left there by an earlier run.
+    #[cfg(feature = "std")]
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(std::format!(
+            "btree_dag_{}_{}.log",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn file_store() -> Result<(), Error> {
+        let path = temp_path("file_store");
+        let mut dag: PersistentDAG<usize, FileStore<usize>> =
+            PersistentDAG::open_path(&path).unwrap();
+        assert!(dag.dag().vertices().is_empty());
+
+        // Add three nodes, and an edge (0, 1) and (1, 2), then remove 1.
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.flush().unwrap();
+        dag.remove_vertex(1)?;
+        dag.flush().unwrap();
+
+        // Reopening the file restores the dag, before and after compaction.
+        let reopened = PersistentDAG::open_path(&path).unwrap();
+        assert_eq!(reopened.dag(), dag.dag());
+        let mut store: FileStore<usize> = FileStore::open(&path).unwrap();
+        let before = std::fs::metadata(&path).unwrap().len();
+        store.compact().unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < before);
+        let reopened = PersistentDAG::open_path(&path).unwrap();
+        assert_eq!(reopened.dag(), dag.dag());
+
+        std::fs::remove_file(&path).unwrap();
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn file_store_torn_batch() -> Result<(), Error> {
+        use std::io::Write;
+
+        let path = temp_path("file_store_torn_batch");
+        let mut dag: PersistentDAG<usize, FileStore<usize>> =
+            PersistentDAG::open_path(&path).unwrap();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+        dag.flush().unwrap();
+        let flushed = dag.dag().clone();
+        let valid = std::fs::metadata(&path).unwrap().len();
+
+        // A crash while committing the next batch leaves part of it behind.
+        dag.add_vertex(2);
+        dag.add_edge(1, 2)?;
+        dag.flush().unwrap();
+        let committed = std::fs::metadata(&path).unwrap().len();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(committed - 3).unwrap();
+        drop(file);
+
+        // The torn batch is discarded whole, and truncated from the file.
+        let mut reopened = PersistentDAG::open_path(&path).unwrap();
+        assert_eq!(reopened.dag(), &flushed);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), valid);
+
+        // A corrupted batch is discarded the same way.
+        reopened.add_vertex(3);
+        reopened.flush().unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&[4, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4])
+            .unwrap();
+        drop(file);
+        let reopened: PersistentDAG<usize, FileStore<usize>> =
+            PersistentDAG::open_path(&path).unwrap();
+        assert_eq!(reopened.dag().vertices().len(), 3);
+        assert!(reopened.dag().vertices().contains(&3));
+
+        std::fs::remove_file(&path).unwrap();
+
+        // Test passed.
+        Ok(())
+    }
+}
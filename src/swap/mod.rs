@@ -0,0 +1,112 @@
+mod test;
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use arc_swap::{ArcSwap, Guard};
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `SwapDAG` is a read-mostly, thread-safe container for a `BTreeDAG` built on
+/// `ArcSwap`. Readers load the currently published dag without ever blocking.
+/// Each mutation is applied to a snapshot of the published dag (see
+/// `BTreeDAG::snapshot`), which is then published atomically; if another
+/// mutation was published in the meantime, the mutation is retried against
+/// the newer dag.
+///
+/// Share a `SwapDAG` between threads by wrapping it in an `Arc`.
+#[derive(Debug)]
+pub struct SwapDAG<T>
+where
+    T: Ord,
+{
+    dag: ArcSwap<BTreeDAG<T>>,
+}
+
+impl<T> SwapDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        SwapDAG {
+            dag: ArcSwap::from_pointee(BTreeDAG::new()),
+        }
+    }
+
+    /// Returns the currently published dag. The returned dag is immutable and
+    /// is unaffected by later mutations.
+    pub fn load(&self) -> Arc<BTreeDAG<T>> {
+        self.dag.load_full()
+    }
+
+    /// Publishes the dag, replacing the current one.
+    pub fn store(&self, dag: BTreeDAG<T>) {
+        self.dag.store(Arc::new(dag))
+    }
+}
+
+impl<T> SwapDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Applies f to a snapshot of the published dag, and publishes the result
+    /// if f succeeds. Nothing is published if f returns an error. Since f is
+    /// retried when a concurrent mutation wins the race, f may be called more
+    /// than once.
+    pub fn update<R>(
+        &self,
+        mut f: impl FnMut(&mut BTreeDAG<T>) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let mut current: Arc<BTreeDAG<T>> = self.dag.load_full();
+        loop {
+            let mut next: BTreeDAG<T> = current.snapshot();
+            let result = f(&mut next)?;
+            let previous = self.dag.compare_and_swap(&current, Arc::new(next));
+            if Arc::ptr_eq(&previous, &current) {
+                return Ok(result);
+            }
+            current = Guard::into_inner(previous);
+        }
+    }
+
+    pub fn add_vertex(&self, x: T) -> Option<BTreeSet<T>> {
+        // Adding a vertex cannot fail, so we can safely unwrap.
+        self.update(|dag| Ok(dag.add_vertex(x.clone()))).unwrap()
+    }
+
+    pub fn add_edge(&self, x: T, y: T) -> Result<BTreeSet<T>, Error> {
+        self.update(|dag| dag.add_edge(x.clone(), y.clone()))
+    }
+
+    pub fn remove_edge(&self, x: T, y: T) -> Result<BTreeSet<T>, Error> {
+        self.update(|dag| dag.remove_edge(x.clone(), y.clone()))
+    }
+
+    pub fn remove_vertex(&self, x: T) -> Result<BTreeSet<T>, Error> {
+        self.update(|dag| dag.remove_vertex(x.clone()))
+    }
+
+    pub fn prune(&self, x: T) -> Result<(), Error> {
+        self.update(|dag| dag.prune(x.clone()))
+    }
+}
+
+impl<T> Default for SwapDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<BTreeDAG<T>> for SwapDAG<T>
+where
+    T: Ord,
+{
+    fn from(dag: BTreeDAG<T>) -> Self {
+        SwapDAG {
+            dag: ArcSwap::from_pointee(dag),
+        }
+    }
+}
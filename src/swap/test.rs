@@ -0,0 +1,88 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::swap::*;
+    use crate::Error;
+    use alloc::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn load_is_unaffected_by_mutation() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1).
+        let dag: SwapDAG<usize> = SwapDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+
+        // A loaded dag is not affected by later mutations.
+        let loaded: Arc<BTreeDAG<usize>> = dag.load();
+        dag.add_edge(1, 2)?;
+        dag.remove_edge(0, 1)?;
+        assert!(loaded.adjacent(0, 1)?);
+        assert!(!loaded.adjacent(1, 2)?);
+        assert!(dag.load().adjacent(1, 2)?);
+        assert!(!dag.load().adjacent(0, 1)?);
+
+        // Failed mutations publish nothing.
+        assert_eq!(dag.add_edge(2, 1).unwrap_err(), Error::EdgeExists);
+        assert_eq!(
+            dag.update(|dag| -> Result<(), Error> {
+                dag.add_vertex(3);
+                dag.add_edge(3, 4)?;
+                Ok(())
+            })
+            .unwrap_err(),
+            Error::VertexDoesNotExist
+        );
+        assert_eq!(dag.load().vertices().len(), 3);
+
+        dag.remove_vertex(2)?;
+        dag.prune(1)?;
+        assert_eq!(dag.load().vertices().len(), 1);
+
+        // Storing replaces the published dag.
+        dag.store(BTreeDAG::new());
+        assert!(dag.load().vertices().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_mutation() {
+        let dag: Arc<SwapDAG<usize>> = Arc::new(SwapDAG::default());
+        for v in 0..8 {
+            dag.add_vertex(v);
+        }
+
+        // Race threads adding edges in both directions; retried updates
+        // ensure exactly one direction of each pair is published.
+        let threads: Vec<thread::JoinHandle<()>> = (0..8)
+            .map(|x| {
+                let dag = Arc::clone(&dag);
+                thread::spawn(move || {
+                    for y in 0..8 {
+                        if x != y {
+                            let _ = dag.add_edge(x, y);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+
+        let dag: Arc<BTreeDAG<usize>> = dag.load();
+        for x in 0..8 {
+            for y in (x + 1)..8 {
+                assert!(dag.adjacent(x, y).unwrap() ^ dag.adjacent(y, x).unwrap());
+            }
+        }
+
+        // Test passed.
+    }
+}
@@ -0,0 +1,210 @@
+mod test;
+
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dag::{AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `TombstoneDAG` wraps a `BTreeDAG`, allowing edges to be disabled without
+/// removing them. A disabled edge is kept as a tombstone outside of the dag,
+/// so it is excluded from every traversal of the dag until it is enabled
+/// again. By default cycle checks ignore disabled edges; when disabled edges
+/// are checked, no edge may be added or enabled which would form a cycle with
+/// them, so every disabled edge can always be enabled again.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TombstoneDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    disabled: BTreeSet<(T, T)>,
+    check_disabled: bool,
+}
+
+impl<T> TombstoneDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        TombstoneDAG {
+            dag: BTreeDAG::new(),
+            disabled: BTreeSet::new(),
+            check_disabled: false,
+        }
+    }
+
+    /// Returns the dag of enabled edges.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the disabled edges.
+    pub fn disabled_edges(&self) -> &BTreeSet<(T, T)> {
+        &self.disabled
+    }
+
+    /// Sets whether cycle checks consider disabled edges.
+    pub fn check_disabled(&mut self, check: bool) {
+        self.check_disabled = check;
+    }
+}
+
+impl<T> TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Returns true if the edge (x, y) is disabled.
+    pub fn is_disabled(&self, x: &T, y: &T) -> bool {
+        self.disabled.contains(&(x.clone(), y.clone()))
+    }
+
+    /// Disables the edge (x, y), removing it from the dag and keeping it as
+    /// a tombstone. An error is returned if the edge does not exist.
+    pub fn disable_edge(&mut self, x: T, y: T) -> Result<(), Error> {
+        if !self.dag.adjacent(x.clone(), y.clone())? {
+            return Err(Error::EdgeDoesNotExist);
+        }
+        self.dag.remove_edge(x.clone(), y.clone())?;
+        self.disabled.insert((x, y));
+        Ok(())
+    }
+
+    /// Enables the disabled edge (x, y), adding it back into the dag. An
+    /// error is returned if the edge is not disabled, or if it would form a
+    /// cycle, in which case it remains disabled.
+    pub fn enable_edge(&mut self, x: T, y: T) -> Result<(), Error> {
+        let edge = (x, y);
+        if !self.disabled.contains(&edge) {
+            return Err(Error::EdgeDoesNotExist);
+        }
+        self.dag.add_edge(edge.0.clone(), edge.1.clone())?;
+        self.disabled.remove(&edge);
+        Ok(())
+    }
+
+    /// Returns an error if the edge (x, y) would form a cycle with the
+    /// enabled and disabled edges, which it does if x is reachable from y
+    /// along them. The search runs over the dag and the tombstones as they
+    /// are, indexing the tombstones by their first vertex, which they are
+    /// already sorted by.
+    fn check_with_disabled(&self, x: &T, y: &T) -> Result<(), Error> {
+        let y = match self.dag.vertices.get_key_value(y) {
+            Some((y, _)) => y,
+            None => return Ok(()),
+        };
+        let disabled: Vec<&(T, T)> = self.disabled.iter().collect();
+        let disabled_from = |v: &T| {
+            let start = disabled.partition_point(|(u, _)| u < v);
+            let end = disabled.partition_point(|(u, _)| u <= v);
+            disabled[start..end].iter().map(|(_, w)| w)
+        };
+        let mut visited: BTreeSet<&T> = BTreeSet::new();
+        let mut stack: Vec<&T> = vec![y];
+        while let Some(v) = stack.pop() {
+            if v == x {
+                return Err(Error::EdgeExists);
+            }
+            if visited.insert(v) {
+                if let Some(adj_v) = self.dag.vertices.get(v) {
+                    stack.extend(adj_v.iter());
+                }
+                stack.extend(disabled_from(v));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the tombstones of every edge incident to a removed vertex.
+    fn retain_existing(&mut self) {
+        let dag = &self.dag;
+        self.disabled
+            .retain(|(x, y)| dag.vertices.contains_key(x) && dag.vertices.contains_key(y));
+    }
+}
+
+impl<T> Default for TombstoneDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AddVertex<T> for TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        let adj_x = self.dag.add_vertex(x.clone());
+        // Re-adding a vertex removes its edges, including disabled ones.
+        if adj_x.is_some() {
+            self.disabled.retain(|(v, _)| *v != x);
+        }
+        adj_x
+    }
+}
+
+impl<T> AddEdge<T> for TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if self.check_disabled {
+            self.check_with_disabled(&x, &y)?;
+        }
+        let adj_x = self.dag.add_edge(x.clone(), y.clone())?;
+        // Adding a disabled edge enables it.
+        self.disabled.remove(&(x, y));
+        Ok(adj_x)
+    }
+}
+
+impl<T> RemoveEdge<T> for TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_edge(x.clone(), y.clone())?;
+        self.disabled.remove(&(x, y));
+        Ok(adj_x)
+    }
+}
+
+impl<T> RemoveVertex<T> for TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_vertex(x)?;
+        self.retain_existing();
+        Ok(adj_x)
+    }
+}
+
+impl<T> Prune<T> for TombstoneDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        let pruned = self.dag.prune(x);
+        self.retain_existing();
+        pruned
+    }
+}
+
+impl<T> Adjacent<T> for TombstoneDAG<T>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        self.dag.adjacent(x, y)
+    }
+}
@@ -0,0 +1,97 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::tombstone::*;
+    use crate::Error;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn disable_edge() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: TombstoneDAG<usize> = TombstoneDAG::new();
+        for x in 0..3 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+
+        // A disabled edge is excluded from traversal.
+        dag.disable_edge(1, 2)?;
+        assert!(!dag.adjacent(1, 2)?);
+        assert!(dag.is_disabled(&1, &2));
+        assert_eq!(dag.dag().descendants_of(&0), BTreeSet::from([&1]));
+        assert_eq!(dag.disable_edge(1, 2), Err(Error::EdgeDoesNotExist));
+
+        // Cycle checks ignore disabled edges by default.
+        dag.add_edge(2, 0)?;
+        assert_eq!(dag.enable_edge(1, 2), Err(Error::EdgeExists));
+        assert!(dag.is_disabled(&1, &2));
+        dag.remove_edge(2, 0)?;
+        dag.enable_edge(1, 2)?;
+        assert!(dag.adjacent(1, 2)?);
+        assert_eq!(dag.enable_edge(1, 2), Err(Error::EdgeDoesNotExist));
+
+        // Removing a vertex removes its tombstones.
+        dag.disable_edge(0, 1)?;
+        dag.remove_vertex(0)?;
+        assert!(dag.disabled_edges().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn check_disabled() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: TombstoneDAG<usize> = TombstoneDAG::new();
+        for x in 0..3 {
+            dag.add_vertex(x);
+        }
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        dag.disable_edge(1, 2)?;
+
+        // With disabled edges checked, (2, 0) would form a cycle with (1, 2).
+        dag.check_disabled(true);
+        assert_eq!(dag.add_edge(2, 0), Err(Error::EdgeExists));
+        dag.check_disabled(false);
+        dag.add_edge(2, 0)?;
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn check_disabled_paths() -> Result<(), Error> {
+        // Add six nodes, and an edge (0, 1), (1, 2), (2, 3) and (3, 4), of
+        // which (1, 2) and (3, 4) are disabled.
+        let mut dag: TombstoneDAG<usize> = TombstoneDAG::new();
+        for x in 0..6 {
+            dag.add_vertex(x);
+        }
+        for x in 0..4 {
+            dag.add_edge(x, x + 1)?;
+        }
+        dag.disable_edge(1, 2)?;
+        dag.disable_edge(3, 4)?;
+        dag.check_disabled(true);
+
+        // A cycle may run through enabled and disabled edges alike.
+        assert_eq!(dag.add_edge(4, 0), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(4, 2), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(2, 1), Err(Error::EdgeExists));
+        dag.add_edge(0, 4)?;
+        dag.add_edge(4, 5)?;
+        assert_eq!(dag.add_edge(5, 3), Err(Error::EdgeExists));
+        assert_eq!(dag.add_edge(5, 6), Err(Error::VertexDoesNotExist));
+
+        // Every disabled edge can still be enabled.
+        dag.enable_edge(1, 2)?;
+        dag.enable_edge(3, 4)?;
+        assert!(dag.disabled_edges().is_empty());
+
+        // Test passed.
+        Ok(())
+    }
+}
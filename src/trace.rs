@@ -0,0 +1,49 @@
+//! Instrumentation macros, which emit `tracing` spans and events when the
+//! `tracing` feature is enabled, and check the invariants of a dag after a
+//! mutation when the `debug-invariants` feature is enabled. Otherwise, they
+//! expand to nothing.
+
+/// Enters a debug level span, which is exited when the returned guard is
+/// dropped.
+macro_rules! debug_span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::debug_span!($($arg)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::trace::NoSpan;
+        guard
+    }};
+}
+
+/// Emits a debug level event.
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Evaluates the body of a mutation of a dag, then panics with a report if
+/// the invariants of the dag no longer hold. The body is evaluated within a
+/// closure, so returning from it still checks the invariants.
+#[cfg(feature = "debug-invariants")]
+macro_rules! checked_mutation {
+    ($dag:expr, $body:block) => {{
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| $body)();
+        $dag.assert_invariants();
+        result
+    }};
+}
+
+/// Evaluates the body of a mutation of a dag.
+#[cfg(not(feature = "debug-invariants"))]
+macro_rules! checked_mutation {
+    ($dag:expr, $body:block) => {
+        $body
+    };
+}
+
+/// `NoSpan` stands in for a span guard when the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
@@ -0,0 +1,155 @@
+mod test;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+
+use crate::dag::{AddEdge, AddVertex, Adjacent, BTreeDAG, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+type EdgeValidator<T, E> = Box<dyn Fn(&T, &T) -> Result<(), E>>;
+
+/// Errors which may occur while adding an edge to a `ValidatedDAG`: either the
+/// edge validator rejected the edge, or the dag did.
+#[derive(PartialEq, Debug)]
+pub enum ValidationError<E> {
+    Rejected(E),
+    Dag(Error),
+}
+
+impl<E> From<Error> for ValidationError<E> {
+    fn from(e: Error) -> Self {
+        ValidationError::Dag(e)
+    }
+}
+
+/// `ValidatedDAG` wraps a `BTreeDAG`, running an edge validator on every edge
+/// added. The validator runs once both vertices of the edge are known to
+/// exist, and before the cycle check, so domain rules are enforced at the same
+/// point as acyclicity.
+pub struct ValidatedDAG<T, E>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    validator: Option<EdgeValidator<T, E>>,
+}
+
+impl<T, E> ValidatedDAG<T, E>
+where
+    T: Ord,
+{
+    /// Returns a dag with no edge validator.
+    pub fn new() -> Self {
+        ValidatedDAG {
+            dag: BTreeDAG::new(),
+            validator: None,
+        }
+    }
+
+    /// Returns the validated dag.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the dag, discarding the edge validator.
+    pub fn into_dag(self) -> BTreeDAG<T> {
+        self.dag
+    }
+
+    /// Sets the edge validator, replacing any previous one. Edges already in
+    /// the dag are not validated.
+    pub fn set_edge_validator(&mut self, validator: impl Fn(&T, &T) -> Result<(), E> + 'static) {
+        self.validator = Some(Box::new(validator));
+    }
+
+    /// Removes the edge validator, so only acyclicity is enforced.
+    pub fn clear_edge_validator(&mut self) {
+        self.validator = None;
+    }
+}
+
+impl<T, E> Default for ValidatedDAG<T, E>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, E> From<BTreeDAG<T>> for ValidatedDAG<T, E>
+where
+    T: Ord,
+{
+    fn from(dag: BTreeDAG<T>) -> Self {
+        ValidatedDAG {
+            dag,
+            validator: None,
+        }
+    }
+}
+
+impl<T, E> AddVertex<T> for ValidatedDAG<T, E>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T, E> AddEdge<T> for ValidatedDAG<T, E>
+where
+    T: Ord + Clone,
+{
+    type Error = ValidationError<E>;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        if !self.dag.vertices.contains_key(&x) || !self.dag.vertices.contains_key(&y) {
+            return Err(ValidationError::Dag(Error::VertexDoesNotExist));
+        }
+        if let Some(validator) = &self.validator {
+            validator(&x, &y).map_err(ValidationError::Rejected)?;
+        }
+        Ok(self.dag.add_edge(x, y)?)
+    }
+}
+
+impl<T, E> RemoveEdge<T> for ValidatedDAG<T, E>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.remove_edge(x, y)
+    }
+}
+
+impl<T, E> RemoveVertex<T> for ValidatedDAG<T, E>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        self.dag.remove_vertex(x)
+    }
+}
+
+impl<T, E> Prune<T> for ValidatedDAG<T, E>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x)
+    }
+}
+
+impl<T, E> Adjacent<T> for ValidatedDAG<T, E>
+where
+    T: Ord,
+{
+    type Error = Error;
+    fn adjacent(&self, x: T, y: T) -> Result<bool, Self::Error> {
+        self.dag.adjacent(x, y)
+    }
+}
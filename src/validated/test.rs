@@ -0,0 +1,41 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::validated::*;
+    use crate::Error;
+
+    #[test]
+    fn edge_validator() -> Result<(), ValidationError<&'static str>> {
+        // Add three nodes, only allowing edges to a lesser vertex.
+        let mut dag: ValidatedDAG<usize, &str> = ValidatedDAG::new();
+        for x in 0..3 {
+            dag.add_vertex(x);
+        }
+        dag.set_edge_validator(|x, y| if y < x { Ok(()) } else { Err("incompatible") });
+        dag.add_edge(2, 1)?;
+        dag.add_edge(1, 0)?;
+        assert_eq!(
+            dag.add_edge(0, 2),
+            Err(ValidationError::Rejected("incompatible"))
+        );
+
+        // Missing vertices are reported before the validator runs.
+        assert_eq!(
+            dag.add_edge(0, 3),
+            Err(ValidationError::Dag(Error::VertexDoesNotExist))
+        );
+
+        // Without a validator, only acyclicity is enforced.
+        dag.clear_edge_validator();
+        assert_eq!(
+            dag.add_edge(0, 2),
+            Err(ValidationError::Dag(Error::EdgeExists))
+        );
+        dag.remove_edge(1, 0)?;
+        dag.add_edge(0, 2)?;
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+mod test;
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::default::Default;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::dag::{AddEdge, AddVertex, BTreeDAG, Mutation, Prune, RemoveEdge, RemoveVertex};
+use crate::Error;
+
+/// `Revision` is a single entry of a `VersionedDAG`'s change log; the mutation
+/// which produced the given version.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Revision<T> {
+    pub version: u64,
+    pub mutation: Mutation<T>,
+}
+
+/// `MutationRecord` is an entry of a write-ahead log of mutations. With the `serde`
+/// feature, records can be appended to a durable log in any serde format as they
+/// are made, taken with `VersionedDAG::records_since`, and replayed on startup
+/// with `VersionedDAG::replay`.
+pub type MutationRecord<T> = Revision<T>;
+
+/// `VersionedDAG` wraps a `BTreeDAG`, recording every successful mutation with
+/// a monotonically increasing version. Version 0 is the empty dag, and each
+/// recorded mutation increments the version by one.
+///
+/// A deserialized `VersionedDAG` is validated by replaying its log, which must
+/// rebuild its dag.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VersionedDAG<T>
+where
+    T: Ord,
+{
+    dag: BTreeDAG<T>,
+    log: Vec<Revision<T>>,
+}
+
+impl<T> VersionedDAG<T>
+where
+    T: Ord,
+{
+    pub fn new() -> Self {
+        VersionedDAG {
+            dag: BTreeDAG::new(),
+            log: Vec::new(),
+        }
+    }
+
+    /// Returns the dag at the current version.
+    pub fn dag(&self) -> &BTreeDAG<T> {
+        &self.dag
+    }
+
+    /// Returns the current version.
+    pub fn version(&self) -> u64 {
+        self.log.len() as u64
+    }
+
+    /// Returns the change log, ordered by version.
+    pub fn log(&self) -> &[Revision<T>] {
+        &self.log
+    }
+
+    /// Returns the records of every mutation after the given version, which
+    /// have yet to be appended to a log which is up to date as of the version.
+    pub fn records_since(&self, version: u64) -> &[MutationRecord<T>] {
+        let start = (version as usize).min(self.log.len());
+        &self.log[start..]
+    }
+
+    fn record(&mut self, mutation: Mutation<T>) {
+        let version = self.version() + 1;
+        self.log.push(Revision { version, mutation });
+    }
+}
+
+impl<T> VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    /// Reconstructs the dag as it was at the given version by replaying the
+    /// change log, or returns `None` if the version does not exist yet.
+    pub fn as_of(&self, version: u64) -> Option<BTreeDAG<T>> {
+        if version > self.version() {
+            return None;
+        }
+        let mut dag: BTreeDAG<T> = BTreeDAG::new();
+        dag.set_max_traversal_depth(self.dag.max_traversal_depth());
+        for revision in self.log.iter().take(version as usize) {
+            // Only successful mutations are recorded, and a deserialized log
+            // is validated by replaying it, so replaying them in order cannot
+            // fail.
+            revision.mutation.clone().apply(&mut dag).unwrap();
+        }
+        Some(dag)
+    }
+
+    /// Rebuilds a versioned dag by replaying a log of records, which must
+    /// start at version 1 and be consecutive. An error is returned if a
+    /// version is out of sequence, with `Error::VersionOutOfSequence`, or if a
+    /// mutation fails.
+    pub fn replay(records: impl IntoIterator<Item = MutationRecord<T>>) -> Result<Self, Error> {
+        let mut dag: VersionedDAG<T> = VersionedDAG::new();
+        for record in records.into_iter() {
+            let expected = dag.version() + 1;
+            if record.version != expected {
+                return Err(Error::VersionOutOfSequence {
+                    expected,
+                    found: record.version,
+                });
+            }
+            record.mutation.clone().apply(&mut dag.dag)?;
+            dag.log.push(record);
+        }
+        Ok(dag)
+    }
+}
+
+impl<T> Default for VersionedDAG<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AddVertex<T> for VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    fn add_vertex(&mut self, x: T) -> Option<BTreeSet<T>> {
+        self.record(Mutation::AddVertex(x.clone()));
+        self.dag.add_vertex(x)
+    }
+}
+
+impl<T> AddEdge<T> for VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn add_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.add_edge(x.clone(), y.clone())?;
+        self.record(Mutation::AddEdge(x, y));
+        Ok(adj_x)
+    }
+}
+
+impl<T> RemoveEdge<T> for VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_edge(&mut self, x: T, y: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_edge(x.clone(), y.clone())?;
+        self.record(Mutation::RemoveEdge(x, y));
+        Ok(adj_x)
+    }
+}
+
+impl<T> RemoveVertex<T> for VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn remove_vertex(&mut self, x: T) -> Result<BTreeSet<T>, Self::Error> {
+        let adj_x = self.dag.remove_vertex(x.clone())?;
+        self.record(Mutation::RemoveVertex(x));
+        Ok(adj_x)
+    }
+}
+
+impl<T> Prune<T> for VersionedDAG<T>
+where
+    T: Ord + Clone,
+{
+    type Error = Error;
+    fn prune(&mut self, x: T) -> Result<(), Self::Error> {
+        self.dag.prune(x.clone())?;
+        self.record(Mutation::Prune(x));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for VersionedDAG<T>
+where
+    T: Ord + Clone + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// The fields of a `VersionedDAG`, before they are validated.
+        #[derive(Deserialize)]
+        #[serde(rename = "VersionedDAG")]
+        struct Unvalidated<T>
+        where
+            T: Ord,
+        {
+            dag: BTreeDAG<T>,
+            log: Vec<Revision<T>>,
+        }
+
+        let unvalidated = Unvalidated::deserialize(deserializer)?;
+        let versioned = VersionedDAG::replay(unvalidated.log).map_err(|_| {
+            serde::de::Error::custom("the log is not a valid sequence of mutations")
+        })?;
+        if versioned.dag != unvalidated.dag {
+            return Err(serde::de::Error::custom("the dag does not match its log"));
+        }
+        Ok(versioned)
+    }
+}
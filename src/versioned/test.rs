@@ -0,0 +1,189 @@
+#![cfg(test)]
+
+mod unit_tests {
+    use crate::dag::*;
+    use crate::versioned::*;
+    use crate::Error;
+
+    #[test]
+    fn new_and_default() {
+        // Instantiate a versioned dag using the implementation of default.
+        let dag: VersionedDAG<usize> = VersionedDAG::new();
+
+        // Check the versioned dag is equivalent to the struct generated
+        // with the new associated function.
+        assert_eq!(dag, VersionedDAG::default());
+        assert_eq!(dag.version(), 0);
+        assert!(dag.log().is_empty());
+
+        // Test passed.
+    }
+
+    #[test]
+    fn record_mutations() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2).
+        let mut dag: VersionedDAG<usize> = VersionedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        assert_eq!(dag.version(), 5);
+
+        // Failed mutations are not recorded.
+        assert!(dag.add_edge(2, 0).is_err());
+        assert!(dag.remove_vertex(3).is_err());
+        assert_eq!(dag.version(), 5);
+
+        // Remove the edge (1, 2), and the vertex 0, then prune 1.
+        dag.remove_edge(1, 2)?;
+        dag.remove_vertex(0)?;
+        dag.prune(1)?;
+        assert_eq!(dag.version(), 8);
+
+        let log: Vec<Revision<usize>> = dag.log().to_vec();
+        assert_eq!(
+            log[5],
+            Revision {
+                version: 6,
+                mutation: Mutation::RemoveEdge(1, 2),
+            }
+        );
+        assert_eq!(
+            log[7],
+            Revision {
+                version: 8,
+                mutation: Mutation::Prune(1),
+            }
+        );
+
+        // The current dag contains only the vertex 2.
+        assert_eq!(dag.dag().vertices().len(), 1);
+        assert!(dag.dag().vertices().contains(&2));
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn as_of() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1) and (1, 2), then remove 1.
+        let mut dag: VersionedDAG<usize> = VersionedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        dag.add_edge(1, 2)?;
+        let snapshot: BTreeDAG<usize> = dag.dag().clone();
+        dag.remove_vertex(1)?;
+
+        // Version 0 is the empty dag.
+        assert_eq!(dag.as_of(0).unwrap(), BTreeDAG::new());
+
+        // Version 3 is the three nodes without edges.
+        let as_of_3 = dag.as_of(3).unwrap();
+        assert_eq!(as_of_3.vertices().len(), 3);
+        assert!(!as_of_3.adjacent(0, 1)?);
+
+        // Version 5 is the dag before the vertex 1 was removed.
+        assert_eq!(dag.as_of(5).unwrap(), snapshot);
+
+        // The current version matches the current dag.
+        assert_eq!(&dag.as_of(dag.version()).unwrap(), dag.dag());
+
+        // Future versions do not exist.
+        assert!(dag.as_of(7).is_none());
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    fn replay() -> Result<(), Error> {
+        // Add three nodes, and an edge (0, 1), then append the log so far.
+        let mut dag: VersionedDAG<usize> = VersionedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_vertex(2);
+        dag.add_edge(0, 1)?;
+        let mut log: Vec<MutationRecord<usize>> = dag.records_since(0).to_vec();
+
+        // Append only the records made since.
+        dag.add_edge(1, 2)?;
+        dag.remove_vertex(0)?;
+        assert_eq!(dag.records_since(4).len(), 2);
+        log.extend_from_slice(dag.records_since(log.len() as u64));
+        assert!(dag.records_since(10).is_empty());
+
+        // Replaying the log rebuilds the dag.
+        assert_eq!(VersionedDAG::replay(log.clone())?, dag);
+
+        // Out of sequence records are rejected.
+        log.remove(2);
+        assert_eq!(
+            VersionedDAG::replay(log),
+            Err(Error::VersionOutOfSequence {
+                expected: 3,
+                found: 4
+            })
+        );
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn replay_json() -> Result<(), Error> {
+        use alloc::string::String;
+        use try_encoding_from::serde_json;
+
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: VersionedDAG<usize> = VersionedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+
+        // Write the log as JSON lines, then read it back.
+        let mut lines: Vec<String> = Vec::new();
+        for record in dag.records_since(0) {
+            lines.push(serde_json::to_string(record)?);
+        }
+        let mut records: Vec<MutationRecord<usize>> = Vec::new();
+        for line in lines.iter() {
+            records.push(serde_json::from_str(line)?);
+        }
+        assert_eq!(VersionedDAG::replay(records)?, dag);
+
+        // Test passed.
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn deserialize_validates_log() -> Result<(), Error> {
+        use alloc::string::String;
+        use try_encoding_from::serde_json;
+
+        // Add two nodes, and an edge (0, 1).
+        let mut dag: VersionedDAG<usize> = VersionedDAG::new();
+        dag.add_vertex(0);
+        dag.add_vertex(1);
+        dag.add_edge(0, 1)?;
+        let json: String = serde_json::to_string(&dag)?;
+        assert_eq!(serde_json::from_str::<VersionedDAG<usize>>(&json)?, dag);
+
+        // A log which can not be replayed is rejected, rather than panicking
+        // in `as_of`.
+        let invalid = json.replacen("AddVertex", "RemoveVertex", 1);
+        assert!(serde_json::from_str::<VersionedDAG<usize>>(&invalid).is_err());
+
+        // A dag which does not match its log is rejected.
+        let mismatched = json.replacen("[1]", "[]", 1);
+        assert_ne!(mismatched, json);
+        assert!(serde_json::from_str::<VersionedDAG<usize>>(&mismatched).is_err());
+
+        // Test passed.
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+mod test;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+use crate::dag::{
+    AddEdge, AddVertex, Adjacent, BTreeDAG, Connections, Prune, RemoveEdge, RemoveVertex,
+    TopologicalSort, Vertices,
+};
+use crate::dot::ToDot;
+use crate::Error;
+
+/// `JsDag` exposes a `BTreeDAG` keyed by strings to JavaScript. Errors are
+/// thrown as JavaScript `Error`s whose message names the `Error` variant.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsDag {
+    dag: BTreeDAG<String>,
+}
+
+#[wasm_bindgen]
+impl JsDag {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsDag {
+        JsDag {
+            dag: BTreeDAG::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = addVertex)]
+    pub fn add_vertex(&mut self, x: String) {
+        self.dag.add_vertex(x);
+    }
+
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, x: String, y: String) -> Result<(), JsError> {
+        self.dag.add_edge(x, y).map(|_| ()).map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = removeEdge)]
+    pub fn remove_edge(&mut self, x: String, y: String) -> Result<(), JsError> {
+        self.dag.remove_edge(x, y).map(|_| ()).map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = removeVertex)]
+    pub fn remove_vertex(&mut self, x: String) -> Result<(), JsError> {
+        self.dag.remove_vertex(x).map(|_| ()).map_err(js_error)
+    }
+
+    pub fn prune(&mut self, x: String) -> Result<(), JsError> {
+        self.dag.prune(x).map_err(js_error)
+    }
+
+    pub fn adjacent(&self, x: String, y: String) -> Result<bool, JsError> {
+        self.dag.adjacent(x, y).map_err(js_error)
+    }
+
+    pub fn vertices(&self) -> Vec<String> {
+        self.dag.vertices().into_iter().cloned().collect()
+    }
+
+    pub fn connections(&self, x: String) -> Option<Vec<String>> {
+        self.dag
+            .connections(x)
+            .map(|adj| adj.iter().cloned().collect())
+    }
+
+    #[wasm_bindgen(js_name = topologicalSort)]
+    pub fn topological_sort(&self) -> Vec<String> {
+        self.dag.topological_sort().into_iter().cloned().collect()
+    }
+
+    #[wasm_bindgen(js_name = toDot)]
+    pub fn to_dot(&self) -> String {
+        self.dag.to_dot()
+    }
+}
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(&format!("{:?}", err))
+}
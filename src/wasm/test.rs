@@ -0,0 +1,42 @@
+#![cfg(test)]
+
+// Errors are converted to JavaScript values, which can only be constructed
+// on a wasm target, so only the successful paths are tested natively.
+mod unit_tests {
+    use crate::wasm::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[test]
+    fn js_dag() {
+        let mut dag: JsDag = JsDag::new();
+        dag.add_vertex(String::from("origin"));
+        dag.add_vertex(String::from("destination"));
+        assert!(dag
+            .add_edge(String::from("origin"), String::from("destination"))
+            .is_ok());
+
+        assert!(dag
+            .adjacent(String::from("origin"), String::from("destination"))
+            .is_ok());
+        assert_eq!(
+            dag.topological_sort(),
+            vec![String::from("origin"), String::from("destination")]
+        );
+        assert_eq!(
+            dag.connections(String::from("origin")),
+            Some(vec![String::from("destination")])
+        );
+        assert!(dag.connections(String::from("waypoint")).is_none());
+        assert!(dag.to_dot().contains("\"origin\" -> \"destination\";"));
+
+        assert!(dag
+            .remove_edge(String::from("origin"), String::from("destination"))
+            .is_ok());
+        assert!(dag.remove_vertex(String::from("destination")).is_ok());
+        assert!(dag.prune(String::from("origin")).is_ok());
+        assert!(dag.vertices().is_empty());
+
+        // Test passed.
+    }
+}